@@ -0,0 +1,388 @@
+//! `#[derive(Validate)]` for `config_loader::validate::Validate`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Expr, Fields, LitStr, Path, Type, meta::ParseNestedMeta, parse_macro_input,
+    parse_quote, spanned::Spanned,
+};
+
+/// Derive `Validate` for a struct with named fields.
+///
+/// Field rules, combined in a single `#[validate(...)]`:
+/// - `range(min = 1, max = 65535)` - inclusive bounds, either may be omitted
+/// - `non_empty` - strings, vectors and maps
+/// - `url` - `scheme://host...`
+/// - `host_port` - `host:port` entries, as a list or comma-separated
+/// - `nested` - validate a field that implements `Validate` itself
+/// - `redact` - hide the value when the configuration is logged or diffed
+///
+/// Rules on `Option` fields only apply when the value is present. Type parameters must
+/// implement `Validate`. `Secret` fields are always redacted, like those of `nested`
+/// fields. Keys follow `#[serde(rename)]`, `#[serde(flatten)]` and the struct's
+/// `#[serde(rename_all)]`.
+///
+/// Cross-field rules go on the struct as `#[validate(custom = path::to::fn)]`, where the
+/// function has the signature `fn(&Self, path: &str, errors: &mut ValidationErrors)`.
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+enum Rule {
+    Range {
+        min: Option<Box<Expr>>,
+        max: Option<Box<Expr>>,
+    },
+    NonEmpty,
+    Url,
+    HostPort,
+    Nested,
+    Redact,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    // like serde, require every type parameter to implement the trait
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(::config_loader::validate::Validate));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut customs = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("custom") {
+                customs.push(parse_path_value(&meta)?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported struct-level validate attribute, expected `custom`"))
+            }
+        })?;
+    }
+
+    let no_fields = syn::punctuated::Punctuated::new();
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => &no_fields,
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "Validate can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Validate can only be derived for structs",
+            ));
+        }
+    };
+
+    let rename_all = serde_rename_all(&input.attrs)?;
+    let mut checks = Vec::new();
+    let mut redactions = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let mut rules = Vec::new();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+            attr.parse_nested_meta(|meta| {
+                rules.push(parse_rule(&meta)?);
+                Ok(())
+            })?;
+        }
+
+        let redact = rules.iter().any(|rule| matches!(rule, Rule::Redact)) || is_secret(&field.ty);
+        let nested = rules.iter().any(|rule| matches!(rule, Rule::Nested));
+        rules.retain(|rule| !matches!(rule, Rule::Redact));
+        if rules.is_empty() && !redact {
+            continue;
+        }
+
+        let (key_name, flatten) = serde_key(field, rename_all.as_ref())?;
+        let key = if flatten {
+            quote! { path.to_string() }
+        } else {
+            quote! { ::config_loader::validate::join_key(path, #key_name) }
+        };
+
+        let ty = &field.ty;
+        if redact {
+            redactions.push(quote! { keys.push(#key); });
+        } else if nested {
+            redactions.push(quote! {
+                <#ty as ::config_loader::validate::Validate>::redacted_keys(&#key, keys);
+            });
+        }
+        if rules.is_empty() {
+            continue;
+        }
+
+        let optional = is_option(&field.ty);
+        let mut value_checks = Vec::new();
+        for rule in rules {
+            value_checks.push(match rule {
+                Rule::Range { min, max } => {
+                    let min = option_tokens(min);
+                    let max = option_tokens(max);
+                    quote! {
+                        if let Err(message) = ::config_loader::validate::rules::range(value, #min, #max) {
+                            errors.add(key.clone(), message);
+                        }
+                    }
+                }
+                Rule::NonEmpty => quote! {
+                    if let Err(message) = ::config_loader::validate::rules::non_empty(value) {
+                        errors.add(key.clone(), message);
+                    }
+                },
+                Rule::Url => quote! {
+                    if let Err(message) = ::config_loader::validate::rules::url(value) {
+                        errors.add(key.clone(), message);
+                    }
+                },
+                Rule::HostPort => quote! {
+                    if let Err(message) = ::config_loader::validate::rules::host_port(value) {
+                        errors.add(key.clone(), message);
+                    }
+                },
+                Rule::Nested => quote! {
+                    ::config_loader::validate::Validate::validate_at(value, &key, errors);
+                },
+                Rule::Redact => unreachable!("removed above"),
+            });
+        }
+
+        checks.push(if optional {
+            quote! {
+                if let Some(value) = &self.#ident {
+                    let key = #key;
+                    #(#value_checks)*
+                }
+            }
+        } else {
+            quote! {
+                {
+                    let value = &self.#ident;
+                    let key = #key;
+                    #(#value_checks)*
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::config_loader::validate::Validate for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn validate_at(&self, path: &str, errors: &mut ::config_loader::validate::ValidationErrors) {
+                #(#checks)*
+                #(#customs(self, path, errors);)*
+            }
+
+            #[allow(unused_variables)]
+            fn redacted_keys(path: &str, keys: &mut ::std::vec::Vec<::std::string::String>) {
+                #(#redactions)*
+            }
+        }
+    })
+}
+
+fn parse_rule(meta: &ParseNestedMeta) -> syn::Result<Rule> {
+    let path = &meta.path;
+    if path.is_ident("range") {
+        let (mut min, mut max) = (None, None);
+        meta.parse_nested_meta(|bound| {
+            if bound.path.is_ident("min") {
+                min = Some(Box::new(bound.value()?.parse()?));
+            } else if bound.path.is_ident("max") {
+                max = Some(Box::new(bound.value()?.parse()?));
+            } else {
+                return Err(bound.error("expected `min` or `max`"));
+            }
+            Ok(())
+        })?;
+        if min.is_none() && max.is_none() {
+            return Err(meta.error("range needs `min`, `max` or both"));
+        }
+        Ok(Rule::Range { min, max })
+    } else if path.is_ident("non_empty") {
+        Ok(Rule::NonEmpty)
+    } else if path.is_ident("url") {
+        Ok(Rule::Url)
+    } else if path.is_ident("host_port") {
+        Ok(Rule::HostPort)
+    } else if path.is_ident("nested") {
+        Ok(Rule::Nested)
+    } else if path.is_ident("redact") {
+        Ok(Rule::Redact)
+    } else {
+        Err(meta.error(
+            "unknown validate rule, expected `range`, `non_empty`, `url`, `host_port`, `nested` \
+             or `redact`",
+        ))
+    }
+}
+
+/// `custom = path` or `custom = "path"`.
+fn parse_path_value(meta: &ParseNestedMeta) -> syn::Result<Path> {
+    let value = meta.value()?;
+    if value.peek(LitStr) {
+        value.parse::<LitStr>()?.parse()
+    } else {
+        value.parse()
+    }
+}
+
+/// The container's `#[serde(rename_all)]` rule for deserializing, if any.
+fn serde_rename_all(attrs: &[syn::Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut rule = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                if let Some(value) = serde_name(&meta)? {
+                    rule = Some(value);
+                }
+            } else {
+                skip_serde_meta(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(rule)
+}
+
+/// Key name of a field after `#[serde(rename)]` or the container's `rename_all`, and
+/// whether it is `#[serde(flatten)]`.
+fn serde_key(field: &syn::Field, rename_all: Option<&LitStr>) -> syn::Result<(String, bool)> {
+    let ident = field.ident.as_ref().expect("named field").to_string();
+    let ident = ident.trim_start_matches("r#");
+    let mut name = None;
+    let mut flatten = false;
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                if let Some(value) = serde_name(&meta)? {
+                    name = Some(value.value());
+                }
+            } else if meta.path.is_ident("flatten") {
+                flatten = true;
+            } else {
+                skip_serde_meta(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    let name = match (name, rename_all) {
+        (Some(name), _) => name,
+        (None, Some(rule)) => apply_rename_all(ident, rule)?,
+        (None, None) => ident.to_string(),
+    };
+    Ok((name, flatten))
+}
+
+/// `name = "..."`, or the `deserialize` part of `name(serialize = "...", deserialize = "...")`.
+fn serde_name(meta: &ParseNestedMeta) -> syn::Result<Option<LitStr>> {
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+    let mut name = None;
+    meta.parse_nested_meta(|nested| {
+        let value = nested.value()?.parse::<LitStr>()?;
+        if nested.path.is_ident("deserialize") {
+            name = Some(value);
+        }
+        Ok(())
+    })?;
+    Ok(name)
+}
+
+/// Consume a serde attribute that doesn't affect key names.
+fn skip_serde_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| {
+            if nested.input.peek(syn::Token![=]) {
+                nested.value()?.parse::<Expr>()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Rename a snake_case field like serde's `rename_all = "<rule>"`.
+fn apply_rename_all(field: &str, rule: &LitStr) -> syn::Result<String> {
+    Ok(match rule.value().as_str() {
+        "lowercase" | "snake_case" => field.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        pascal @ ("PascalCase" | "camelCase") => {
+            let mut name = String::with_capacity(field.len());
+            let mut upper = pascal == "PascalCase";
+            for c in field.chars() {
+                if c == '_' {
+                    upper = true;
+                } else if upper {
+                    name.push(c.to_ascii_uppercase());
+                    upper = false;
+                } else {
+                    name.push(c);
+                }
+            }
+            name
+        }
+        _ => {
+            return Err(syn::Error::new(
+                rule.span(),
+                "unknown serde rename_all rule",
+            ));
+        }
+    })
+}
+
+fn is_option(ty: &Type) -> bool {
+    last_segment(ty).is_some_and(|segment| segment.ident == "Option")
+}
+
+/// `Secret<_>` or `Option<Secret<_>>`.
+fn is_secret(ty: &Type) -> bool {
+    let Some(segment) = last_segment(ty) else {
+        return false;
+    };
+    if segment.ident == "Secret" {
+        return true;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if segment.ident == "Option" => {
+            matches!(args.args.first(), Some(syn::GenericArgument::Type(inner)) if is_secret(inner))
+        }
+        _ => false,
+    }
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn option_tokens(expr: Option<Box<Expr>>) -> TokenStream2 {
+    match expr {
+        Some(expr) => quote! { ::core::option::Option::Some(#expr) },
+        None => quote! { ::core::option::Option::None },
+    }
+}
@@ -1,11 +1,47 @@
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::{env::Env, timezone::Timezone, validate::Validate};
+
+#[derive(Debug, Clone, Serialize, JsonSchema, Validate)]
 #[non_exhaustive]
 pub struct BaseAppConfig {
+    #[validate(non_empty)]
     pub name: String,
     pub version: Option<String>,
-    pub env: Option<String>,
-    /// Timezone offset in hours from UTC (e.g., 7 for UTC+7)
-    pub timezone: Option<i8>,
+    pub env: Option<Env>,
+    /// IANA name (e.g. `Asia/Jakarta`) or offset from UTC, in hours (`7`) or as `+05:30`
+    pub timezone: Option<Timezone>,
+    #[serde(skip)]
+    #[schemars(skip)]
+    env_name: Option<String>,
+}
+
+impl BaseAppConfig {
+    /// The environment as written in the configuration, e.g. `prod` rather than the
+    /// canonical `production`.
+    pub fn env_name(&self) -> Option<&str> {
+        self.env_name.as_deref()
+    }
+}
+
+impl<'de> Deserialize<'de> for BaseAppConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            name: String,
+            version: Option<String>,
+            env: Option<String>,
+            timezone: Option<Timezone>,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        Ok(Self {
+            name: raw.name,
+            version: raw.version,
+            env: raw.env.clone().map(Env::from),
+            timezone: raw.timezone,
+            env_name: raw.env,
+        })
+    }
 }
@@ -0,0 +1,356 @@
+use config::{AsyncSource, Config, ConfigError, Map, Source, Value, ValueKind};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{task::JoinSet, time::Instant};
+
+use crate::{
+    crypto::{self, EncryptedFile, Keyring},
+    error::SourceError,
+    format::FormatRegistry,
+    keys::flatten,
+    layered::LayeredConfigLoader,
+    secret::SecretResolver,
+    validate::Validate,
+};
+
+/// Total time allowed for remote fetches by default.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+enum SourceKind {
+    File(PathBuf),
+    Layered(Box<LayeredConfigLoader>),
+    Remote(Arc<dyn AsyncSource + Send + Sync>),
+}
+
+/// A source of an [`AsyncConfigLoader`]: a local file, [`LayeredConfigLoader`] layers,
+/// or a remote source such as an [`HttpSource`](crate::HttpSource).
+///
+/// Sources are required unless marked [`Self::optional`].
+pub struct ConfigSource {
+    name: String,
+    kind: SourceKind,
+    priority: i32,
+    required: bool,
+}
+
+impl ConfigSource {
+    /// A local file, in the format detected from its extension. Encrypted files are
+    /// decrypted, see [`crypto`].
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self::with_kind(path.display().to_string(), SourceKind::File(path))
+    }
+
+    /// Every layer of `loader`, merged as one source named `local`.
+    pub fn layered(loader: LayeredConfigLoader) -> Self {
+        Self::with_kind("local", SourceKind::Layered(Box::new(loader)))
+    }
+
+    /// A remote source, e.g. an [`HttpSource`](crate::HttpSource) or a
+    /// [`SpringCloudConfigSource`](crate::SpringCloudConfigSource).
+    pub fn remote(name: impl Into<String>, source: impl AsyncSource + Send + 'static) -> Self {
+        Self::with_kind(name, SourceKind::Remote(Arc::new(source)))
+    }
+
+    fn with_kind(name: impl Into<String>, kind: SourceKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            priority: 0,
+            required: true,
+        }
+    }
+
+    /// Sources with a higher priority override lower ones. Among equal priorities, sources
+    /// added later win. 0 by default.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Skip the source with a warning if it fails, instead of failing the load.
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    /// Read a local source, blocking.
+    fn collect_local(&self, keyring: Option<&Keyring>) -> Result<Map<String, Value>, ConfigError> {
+        match &self.kind {
+            SourceKind::File(path) => {
+                let format = FormatRegistry::default()
+                    .detect(&crypto::plaintext_path(path))
+                    .ok_or_else(|| {
+                        ConfigError::Message(format!(
+                            "unsupported configuration format for '{}'",
+                            path.display()
+                        ))
+                    })?;
+                EncryptedFile {
+                    path: path.clone(),
+                    format,
+                    keyring: keyring.cloned(),
+                }
+                .collect()
+            }
+            SourceKind::Layered(loader) => loader.build()?.config.cache.into_table(),
+            SourceKind::Remote(_) => unreachable!("remote sources are fetched asynchronously"),
+        }
+    }
+}
+
+impl fmt::Debug for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigSource")
+            .field("name", &self.name)
+            .field("priority", &self.priority)
+            .field("required", &self.required)
+            .finish()
+    }
+}
+
+/// What happened to a source during an [`AsyncConfigLoader`] load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SourceOutcome {
+    /// The source was merged, supplying `keys` keys before overrides.
+    Loaded { keys: usize },
+    /// The optional source failed and was left out.
+    Skipped { reason: String },
+}
+
+/// A source of an [`AsyncConfigLoader`] and its outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceReport {
+    pub name: String,
+    pub priority: i32,
+    pub required: bool,
+    pub outcome: SourceOutcome,
+}
+
+/// Which sources an [`AsyncConfigLoader`] merged, and where each key came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Every source, in merge order, lowest priority first.
+    pub sources: Vec<SourceReport>,
+    /// Final dotted keys and the name of the source that supplied them.
+    pub keys: BTreeMap<String, String>,
+}
+
+impl LoadReport {
+    /// Names of the sources that supplied at least one final key, in merge order.
+    pub fn contributors(&self) -> Vec<&str> {
+        self.sources
+            .iter()
+            .map(|source| source.name.as_str())
+            .filter(|name| self.keys.values().any(|source| source == name))
+            .collect()
+    }
+
+    /// Name of the source that supplied the final value of `key`.
+    pub fn source_of(&self, key: &str) -> Option<&str> {
+        self.keys.get(key).map(String::as_str)
+    }
+}
+
+/// Merges local files and several remote sources by priority.
+///
+/// ```no_run
+/// # use config_loader::{AsyncConfigLoader, ConfigSource, HttpSource};
+/// # async fn load() -> Result<(), config_loader::ConfigError> {
+/// let (config, report) = AsyncConfigLoader::new()
+///     .source(ConfigSource::file("config/base.yaml"))
+///     .source(ConfigSource::remote("org", HttpSource::detect("https://cfg/org.yaml")?).priority(10))
+///     .source(ConfigSource::remote("oms", HttpSource::detect("https://cfg/oms.yaml")?).priority(20).optional())
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Remote sources are fetched concurrently, all within [`Self::timeout`], while local
+/// ones are read on blocking threads. A required source that fails, panics or runs out
+/// of time fails the load with a [`SourceError`]; an optional one is skipped with a
+/// warning. `ENC[...]` values are decrypted once merged, then secret placeholders are
+/// resolved, see [`Self::secrets`].
+#[derive(Debug)]
+pub struct AsyncConfigLoader {
+    sources: Vec<ConfigSource>,
+    timeout: Duration,
+    keyring: Option<Keyring>,
+    secrets: SecretResolver,
+}
+
+impl Default for AsyncConfigLoader {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+            keyring: None,
+            secrets: SecretResolver::default(),
+        }
+    }
+}
+
+impl AsyncConfigLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn source(mut self, source: ConfigSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Total time allowed for every remote fetch, 30 seconds by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Decrypt with `keyring` instead of the keys from the environment.
+    pub fn keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = Some(keyring);
+        self
+    }
+
+    /// Resolve `${scheme:reference}` placeholders with `resolver` instead of
+    /// [`SecretResolver::default`], e.g. to add a [`VaultSecretProvider`](crate::VaultSecretProvider).
+    pub fn secrets(mut self, resolver: SecretResolver) -> Self {
+        self.secrets = resolver;
+        self
+    }
+
+    /// Fetch and merge every source.
+    pub async fn build(self) -> Result<(Config, LoadReport), ConfigError> {
+        let deadline = Instant::now() + self.timeout;
+        let mut sources = self.sources;
+        // stable, so later sources stay after earlier ones of the same priority
+        sources.sort_by_key(|source| source.priority);
+        let sources: Vec<Arc<ConfigSource>> = sources.into_iter().map(Arc::new).collect();
+
+        let mut results: Vec<Option<Result<Map<String, Value>, String>>> =
+            sources.iter().map(|_| None).collect();
+
+        // start the remote fetches first, so they run while the local files are read
+        let mut fetches = JoinSet::new();
+        let mut fetch_ids = HashMap::new();
+        for (i, source) in sources.iter().enumerate() {
+            if let SourceKind::Remote(remote) = &source.kind {
+                let remote = remote.clone();
+                let task = fetches.spawn(async move { (i, remote.collect().await) });
+                fetch_ids.insert(task.id(), i);
+            }
+        }
+
+        let mut reads = Vec::new();
+        for (i, source) in sources.iter().enumerate() {
+            if !matches!(source.kind, SourceKind::Remote(_)) {
+                let source = source.clone();
+                let keyring = self.keyring.clone();
+                let read =
+                    tokio::task::spawn_blocking(move || source.collect_local(keyring.as_ref()));
+                reads.push((i, read));
+            }
+        }
+        for (i, read) in reads {
+            results[i] = Some(match read.await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            });
+        }
+
+        loop {
+            match tokio::time::timeout_at(deadline, fetches.join_next()).await {
+                Ok(Some(Ok((i, result)))) => results[i] = Some(result.map_err(|e| e.to_string())),
+                // a panicking fetch fails its source only
+                Ok(Some(Err(e))) => results[fetch_ids[&e.id()]] = Some(Err(e.to_string())),
+                Ok(None) => break,
+                Err(_) => {
+                    fetches.abort_all();
+                    break;
+                }
+            }
+        }
+
+        let mut builder = Config::builder();
+        let mut report = LoadReport::default();
+        let mut source_keys = Vec::new();
+        for (source, result) in sources.iter().zip(results) {
+            let result =
+                result.unwrap_or_else(|| Err(format!("timed out after {:?}", self.timeout)));
+            let outcome = match result {
+                Ok(map) => {
+                    let keys = flatten(&Value::new(None, ValueKind::Table(map.clone())));
+                    builder = builder.add_source(Collected(map));
+                    let outcome = SourceOutcome::Loaded { keys: keys.len() };
+                    source_keys.push((source.name.clone(), keys));
+                    outcome
+                }
+                Err(reason) if source.required => {
+                    return Err(SourceError {
+                        name: source.name.clone(),
+                        reason,
+                    }
+                    .into());
+                }
+                Err(reason) => {
+                    tracing::warn!(source = %source.name, error = %reason, "optional configuration source failed, skipping it");
+                    SourceOutcome::Skipped { reason }
+                }
+            };
+            report.sources.push(SourceReport {
+                name: source.name.clone(),
+                priority: source.priority,
+                required: source.required,
+                outcome,
+            });
+        }
+
+        let config = builder.build()?;
+        report.keys = flatten(&config.cache)
+            .into_keys()
+            .filter_map(|key| {
+                let (name, _) = source_keys
+                    .iter()
+                    .rev()
+                    .find(|(_, keys)| keys.contains_key(&key))?;
+                Some((key, name.clone()))
+            })
+            .collect();
+
+        let config = crypto::decrypt_config(config, self.keyring.as_ref())?;
+        let config = self.secrets.resolve_config(config).await?;
+        Ok((config, report))
+    }
+
+    /// Fetch and merge every source, deserialize and validate the result.
+    pub async fn load<T: DeserializeOwned + Validate>(
+        self,
+    ) -> Result<(T, LoadReport), ConfigError> {
+        let (config, report) = self.build().await?;
+        let config: T = config.try_deserialize()?;
+        config.validate()?;
+        Ok((config, report))
+    }
+}
+
+/// Values already collected from a source.
+#[derive(Debug, Clone)]
+struct Collected(Map<String, Value>);
+
+impl Source for Collected {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.0.clone())
+    }
+}
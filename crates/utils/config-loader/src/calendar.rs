@@ -0,0 +1,289 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, Weekday};
+
+use crate::{
+    timezone::Timezone,
+    validate::{Validate, ValidationErrors, join_key},
+};
+
+/// Days to look ahead for the next session, enough to cross any holiday season.
+const MAX_LOOKAHEAD_DAYS: i64 = 366;
+
+/// Trading hours of a market: sessions in the market's local time, the weekdays it
+/// trades, holidays and early closes.
+///
+/// ```toml
+/// [calendar]
+/// timezone = "America/New_York"
+/// sessions = [
+///     { name = "pre", open = "04:00", close = "09:30" },
+///     { name = "regular", open = "09:30", close = "16:00" },
+/// ]
+/// holidays = ["2026-12-25", "2027-01-01"]
+/// half_days = [{ date = "2026-11-27", close = "13:00" }]
+/// ```
+///
+/// A session closing at or before its opening time runs past midnight, and belongs to
+/// the trading day it opens on.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, Validate)]
+#[validate(custom = check_calendar)]
+#[non_exhaustive]
+pub struct TradingCalendar {
+    pub timezone: Timezone,
+    /// Days the market trades, Monday to Friday by default.
+    #[serde(default = "weekdays", with = "weekday_names")]
+    #[schemars(with = "Vec<String>")]
+    #[validate(non_empty)]
+    pub days: Vec<Weekday>,
+    #[validate(non_empty, nested)]
+    pub sessions: Vec<Session>,
+    /// Days the market is closed, as `YYYY-MM-DD`, in a list or comma-separated.
+    #[serde(default, with = "dates")]
+    #[schemars(with = "Vec<String>")]
+    pub holidays: Vec<Date>,
+    /// Days the market closes early.
+    #[serde(default)]
+    #[validate(nested)]
+    pub half_days: Vec<HalfDay>,
+}
+
+/// A trading session, in the calendar's timezone.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, Validate)]
+#[non_exhaustive]
+pub struct Session {
+    #[validate(non_empty)]
+    pub name: Option<String>,
+    /// Opening time, `HH:MM` or `HH:MM:SS`.
+    #[serde(with = "clock")]
+    #[schemars(with = "String")]
+    pub open: Time,
+    /// Closing time, `HH:MM` or `HH:MM:SS`.
+    #[serde(with = "clock")]
+    #[schemars(with = "String")]
+    pub close: Time,
+}
+
+/// A day the market closes early.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, Validate)]
+#[non_exhaustive]
+pub struct HalfDay {
+    /// `YYYY-MM-DD`
+    #[serde(with = "date")]
+    #[schemars(with = "String")]
+    pub date: Date,
+    /// Time every session ends by on that day.
+    #[serde(with = "clock")]
+    #[schemars(with = "String")]
+    pub close: Time,
+}
+
+fn weekdays() -> Vec<Weekday> {
+    vec![
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+    ]
+}
+
+impl TradingCalendar {
+    /// Whether the market trades on the local date `date`.
+    pub fn is_trading_day(&self, date: Date) -> bool {
+        self.days.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// Early closing time on the local date `date`, if it is a half day.
+    pub fn early_close(&self, date: Date) -> Option<Time> {
+        self.half_days
+            .iter()
+            .find(|half_day| half_day.date == date)
+            .map(|half_day| half_day.close)
+    }
+
+    /// Session open at `now`, if any.
+    pub fn session_at(&self, now: OffsetDateTime) -> Option<&Session> {
+        let now = local(self.timezone.to_local(now));
+        [now.date().previous_day(), Some(now.date())]
+            .into_iter()
+            .flatten()
+            .flat_map(|day| self.sessions_on(day))
+            .find(|(_, open, close)| (*open..*close).contains(&now))
+            .map(|(session, _, _)| session)
+    }
+
+    /// Whether any session is open at `now`.
+    pub fn is_market_open(&self, now: OffsetDateTime) -> bool {
+        self.session_at(now).is_some()
+    }
+
+    /// Next time a session opens after `now`, in the calendar's timezone.
+    pub fn next_open(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        let now = local(self.timezone.to_local(now));
+        (0..=MAX_LOOKAHEAD_DAYS)
+            .filter_map(|days| now.date().checked_add(Duration::days(days)))
+            .find_map(|day| {
+                self.sessions_on(day)
+                    .map(|(_, open, _)| open)
+                    .filter(|open| *open > now)
+                    .min()
+            })
+            .map(|open| self.timezone.from_local(open))
+    }
+
+    /// Local opening and closing times of the sessions that open on `day`.
+    fn sessions_on(
+        &self,
+        day: Date,
+    ) -> impl Iterator<Item = (&Session, PrimitiveDateTime, PrimitiveDateTime)> {
+        let trading = self.is_trading_day(day);
+        let early_close = self.early_close(day).map(|close| day.with_time(close));
+        self.sessions
+            .iter()
+            .filter(move |_| trading)
+            .filter_map(move |session| {
+                let open = day.with_time(session.open);
+                let close = if session.close > session.open {
+                    day.with_time(session.close)
+                } else {
+                    day.next_day()?.with_time(session.close)
+                };
+                let close = early_close.map_or(close, |early| close.min(early));
+                (close > open).then_some((session, open, close))
+            })
+    }
+}
+
+fn local(at: OffsetDateTime) -> PrimitiveDateTime {
+    PrimitiveDateTime::new(at.date(), at.time())
+}
+
+fn check_calendar(calendar: &TradingCalendar, path: &str, errors: &mut ValidationErrors) {
+    for (i, session) in calendar.sessions.iter().enumerate() {
+        if session.open == session.close {
+            errors.add(
+                format!("{}[{}]", join_key(path, "sessions"), i),
+                "opens and closes at the same time",
+            );
+        }
+    }
+    for (i, half_day) in calendar.half_days.iter().enumerate() {
+        if calendar.holidays.contains(&half_day.date) {
+            errors.add(
+                format!("{}[{}].date", join_key(path, "half_days"), i),
+                format!("{} is also a holiday", half_day.date),
+            );
+        }
+    }
+}
+
+/// `HH:MM` or `HH:MM:SS`
+mod clock {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use time::{Time, macros::format_description};
+
+    pub(super) fn parse(text: &str) -> Result<Time, String> {
+        let text = text.trim();
+        Time::parse(text, format_description!("[hour]:[minute]:[second]"))
+            .or_else(|_| Time::parse(text, format_description!("[hour]:[minute]")))
+            .map_err(|_| format!("invalid time '{}', expected HH:MM or HH:MM:SS", text))
+    }
+
+    pub(super) fn serialize<S: Serializer>(time: &Time, serializer: S) -> Result<S::Ok, S::Error> {
+        let (hour, minute, second) = time.as_hms();
+        if second == 0 {
+            serializer.collect_str(&format_args!("{:02}:{:02}", hour, minute))
+        } else {
+            serializer.collect_str(&format_args!("{:02}:{:02}:{:02}", hour, minute, second))
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Time, D::Error> {
+        parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// `YYYY-MM-DD`
+mod date {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use time::{Date, macros::format_description};
+
+    pub(super) fn parse(text: &str) -> Result<Date, String> {
+        let text = text.trim();
+        Date::parse(text, format_description!("[year]-[month]-[day]"))
+            .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", text))
+    }
+
+    pub(super) fn serialize<S: Serializer>(date: &Date, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(date)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Date, D::Error> {
+        parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// Dates as a list or a comma-separated string.
+mod dates {
+    use serde::{Deserializer, Serializer, de};
+    use time::Date;
+
+    use crate::de::one_or_many;
+
+    pub(super) fn serialize<S: Serializer>(
+        dates: &[Date],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(dates.iter().map(ToString::to_string))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Date>, D::Error> {
+        one_or_many(deserializer)?
+            .iter()
+            .map(|text| super::date::parse(text).map_err(de::Error::custom))
+            .collect()
+    }
+}
+
+/// Weekday names, full or abbreviated, as a list or a comma-separated string.
+mod weekday_names {
+    use serde::{Deserializer, Serializer, de};
+    use time::Weekday;
+
+    use crate::de::one_or_many;
+
+    pub(super) fn serialize<S: Serializer>(
+        days: &[Weekday],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(days.iter().map(|day| day.to_string().to_lowercase()))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Weekday>, D::Error> {
+        one_or_many(deserializer)?
+            .iter()
+            .map(|name| {
+                let day = match name.to_lowercase().as_str() {
+                    "mon" | "monday" => Weekday::Monday,
+                    "tue" | "tuesday" => Weekday::Tuesday,
+                    "wed" | "wednesday" => Weekday::Wednesday,
+                    "thu" | "thursday" => Weekday::Thursday,
+                    "fri" | "friday" => Weekday::Friday,
+                    "sat" | "saturday" => Weekday::Saturday,
+                    "sun" | "sunday" => Weekday::Sunday,
+                    _ => return Err(de::Error::custom(format!("invalid weekday '{}'", name))),
+                };
+                Ok(day)
+            })
+            .collect()
+    }
+}
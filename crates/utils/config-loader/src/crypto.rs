@@ -0,0 +1,513 @@
+use aes_gcm::{
+    Aes256Gcm, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+};
+use age::secrecy::ExposeSecret;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use config::{Config, ConfigError, Format, Map, Source, Value, ValueKind};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{error::CryptoError, format::ConfigFormat, secret::Secret, validate::join_key};
+
+/// Environment variable holding an age identity, `AGE-SECRET-KEY-1...`.
+pub const AGE_KEY_VAR: &str = "CONFIG_AGE_KEY";
+/// Environment variable holding a base64-encoded 256-bit AES key.
+pub const AES_KEY_VAR: &str = "CONFIG_AES_KEY";
+/// Environment variable holding the path of a key file, one key per line.
+pub const KEY_FILE_VAR: &str = "CONFIG_KEY_FILE";
+
+const AES: &str = "AES256_GCM";
+const AGE: &str = "AGE";
+const AES_TAG_LEN: usize = 16;
+const AGE_BINARY_HEADER: &[u8] = b"age-encryption.org/v1";
+const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// A key decrypting, and encrypting, configuration.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum ConfigKey {
+    /// 256-bit AES-GCM key.
+    Aes256Gcm(Secret<[u8; 32]>),
+    /// age X25519 identity.
+    Age(age::x25519::Identity),
+}
+
+impl ConfigKey {
+    /// Random AES-GCM key.
+    pub fn generate_aes() -> Self {
+        Self::Aes256Gcm(Secret::new(Aes256Gcm::generate_key(OsRng).into()))
+    }
+
+    /// Random age identity.
+    pub fn generate_age() -> Self {
+        Self::Age(age::x25519::Identity::generate())
+    }
+
+    /// The key as [`FromStr`] reads it, to hand to a secret manager.
+    pub fn expose(&self) -> String {
+        match self {
+            Self::Aes256Gcm(key) => STANDARD.encode(key.expose()),
+            Self::Age(identity) => identity.to_string().expose_secret().to_string(),
+        }
+    }
+}
+
+impl fmt::Debug for ConfigKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Aes256Gcm(key) => f.debug_tuple("Aes256Gcm").field(key).finish(),
+            // the recipient is the public half
+            Self::Age(identity) => write!(f, "Age({})", identity.to_public()),
+        }
+    }
+}
+
+/// An age identity (`AGE-SECRET-KEY-1...`) or a base64-encoded 256-bit AES key.
+impl FromStr for ConfigKey {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.to_uppercase().starts_with("AGE-SECRET-KEY-") {
+            return s
+                .parse()
+                .map(Self::Age)
+                .map_err(|reason: &str| CryptoError::InvalidKey {
+                    reason: reason.to_string(),
+                });
+        }
+        let bytes = STANDARD.decode(s).map_err(|_| CryptoError::InvalidKey {
+            reason: "expected an age identity or a base64-encoded 256-bit AES key".to_string(),
+        })?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| CryptoError::InvalidKey {
+                reason: format!("AES key is {} bytes, expected 32", bytes.len()),
+            })?;
+        Ok(Self::Aes256Gcm(Secret::new(key)))
+    }
+}
+
+/// Keys to decrypt configuration with, tried in order. The first one encrypts.
+///
+/// Encrypted values look like `ENC[AES256_GCM,data:...,iv:...,tag:...]` or
+/// `ENC[AGE,data:...]` and can appear anywhere a string can, in any format. AES values are
+/// bound to the dotted key they are stored at, so they can't be moved to another key. Whole
+/// files may be encrypted too, as age files (armored or not) or as a single `ENC[...]` value.
+///
+/// To rotate keys, put the new key first and the old one after it, then
+/// [`Self::rotate`] every file.
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    keys: Vec<ConfigKey>,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `key`, tried after the keys already added.
+    pub fn with_key(mut self, key: ConfigKey) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    pub fn keys(&self) -> &[ConfigKey] {
+        &self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Keys from [`AGE_KEY_VAR`], [`AES_KEY_VAR`], then the file at [`KEY_FILE_VAR`].
+    pub fn from_env() -> Result<Self, CryptoError> {
+        let mut keyring = Self::new();
+        for var in [AGE_KEY_VAR, AES_KEY_VAR] {
+            if let Ok(key) = std::env::var(var) {
+                keyring = keyring.with_key(key.parse()?);
+            }
+        }
+        if let Ok(path) = std::env::var(KEY_FILE_VAR) {
+            keyring.keys.extend(Self::from_file(path)?.keys);
+        }
+        Ok(keyring)
+    }
+
+    /// Keys from a file, one per line. Blank lines and `#` comments are skipped, so
+    /// `age-keygen` output works as is.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, CryptoError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| CryptoError::KeyFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .try_fold(Self::new(), |keyring, line| {
+                Ok(keyring.with_key(line.parse()?))
+            })
+    }
+
+    /// Encrypt `plaintext` into an `ENC[...]` value for the dotted key `key`, e.g.
+    /// `database.password` or `brokers[0].token`, with the first key.
+    ///
+    /// age values aren't bound to `key`: anyone with the recipient can create them.
+    pub fn encrypt(&self, key: &str, plaintext: &str) -> Result<String, CryptoError> {
+        match self.primary()? {
+            ConfigKey::Aes256Gcm(secret) => {
+                let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(secret.expose()));
+                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+                let payload = Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: key.as_bytes(),
+                };
+                let mut data =
+                    cipher
+                        .encrypt(&nonce, payload)
+                        .map_err(|e| CryptoError::Encrypt {
+                            algorithm: AES,
+                            reason: e.to_string(),
+                        })?;
+                let tag = data.split_off(data.len() - AES_TAG_LEN);
+                Ok(format!(
+                    "ENC[{},data:{},iv:{},tag:{}]",
+                    AES,
+                    STANDARD.encode(data),
+                    STANDARD.encode(nonce),
+                    STANDARD.encode(tag)
+                ))
+            }
+            ConfigKey::Age(identity) => {
+                let data = age::encrypt(&identity.to_public(), plaintext.as_bytes())
+                    .map_err(|e| age_encrypt_error(&e))?;
+                Ok(format!("ENC[{},data:{}]", AGE, STANDARD.encode(data)))
+            }
+        }
+    }
+
+    /// Decrypt the `ENC[...]` value stored at the dotted key `key`.
+    pub fn decrypt(&self, key: &str, value: &str) -> Result<String, CryptoError> {
+        let malformed = |reason: &str| CryptoError::Malformed {
+            reason: reason.to_string(),
+        };
+        let inner = value
+            .trim()
+            .strip_prefix("ENC[")
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or_else(|| malformed("expected ENC[...]"))?;
+        let mut parts = inner.split(',');
+        let algorithm = parts.next().unwrap_or_default();
+        let mut fields = HashMap::new();
+        for part in parts {
+            let (name, data) = part
+                .split_once(':')
+                .ok_or_else(|| malformed("expected name:base64 fields"))?;
+            let data = STANDARD
+                .decode(data)
+                .map_err(|_| malformed(&format!("'{}' is not base64", name)))?;
+            fields.insert(name, data);
+        }
+        let mut field = |name: &str| {
+            fields
+                .remove(name)
+                .ok_or_else(|| malformed(&format!("missing '{}'", name)))
+        };
+
+        let plaintext = match algorithm {
+            AES => {
+                let mut data = field("data")?;
+                let iv = field("iv")?;
+                if iv.len() != 12 {
+                    return Err(malformed("iv must be 12 bytes"));
+                }
+                data.extend(field("tag")?);
+                self.decrypt_aes(Nonce::from_slice(&iv), &data, key)?
+            }
+            AGE => self.decrypt_age(&field("data")?)?,
+            other => return Err(malformed(&format!("unknown algorithm '{}'", other))),
+        };
+        String::from_utf8(plaintext).map_err(|_| malformed("plaintext is not UTF-8"))
+    }
+
+    /// Decrypt the `ENC[...]` value stored at `key` and encrypt it again with the first key.
+    pub fn reencrypt(&self, key: &str, value: &str) -> Result<String, CryptoError> {
+        self.encrypt(key, &self.decrypt(key, value)?)
+    }
+
+    /// Encrypt a whole file with the first key: an armored age file, or a single
+    /// `ENC[...]` line for AES.
+    pub fn encrypt_file(&self, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match self.primary()? {
+            ConfigKey::Aes256Gcm(_) => {
+                let text = std::str::from_utf8(plaintext).map_err(|_| CryptoError::Encrypt {
+                    algorithm: AES,
+                    reason: "file is not UTF-8".to_string(),
+                })?;
+                Ok(format!("{}\n", self.encrypt("", text)?).into_bytes())
+            }
+            ConfigKey::Age(identity) => age::encrypt_and_armor(&identity.to_public(), plaintext)
+                .map(String::into_bytes)
+                .map_err(|e| age_encrypt_error(&e)),
+        }
+    }
+
+    /// Decrypt a file written by [`Self::encrypt_file`], or by `age`.
+    pub fn decrypt_file(&self, contents: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if is_age_file(contents) {
+            self.decrypt_age(contents)
+        } else {
+            let text = std::str::from_utf8(contents).map_err(|_| CryptoError::Malformed {
+                reason: "file is neither an age file nor UTF-8".to_string(),
+            })?;
+            self.decrypt("", text).map(String::into_bytes)
+        }
+    }
+
+    /// Re-encrypt a file with the first key: the whole file if it is encrypted,
+    /// otherwise each of its `ENC[...]` values, leaving the rest of the text untouched.
+    ///
+    /// `format` is the one of the plaintext, used to find the key of each value.
+    pub fn rotate(&self, contents: &[u8], format: ConfigFormat) -> Result<Vec<u8>, CryptoError> {
+        if is_encrypted_file(contents) {
+            return self.encrypt_file(&self.decrypt_file(contents)?);
+        }
+        let text = std::str::from_utf8(contents).map_err(|_| CryptoError::Malformed {
+            reason: "file is not UTF-8".to_string(),
+        })?;
+        let values = format
+            .parse(None, text)
+            .map_err(|e| CryptoError::Malformed {
+                reason: e.to_string(),
+            })?;
+        let mut keys = HashMap::new();
+        for (key, value) in &values {
+            encrypted_keys(value, key, &mut keys);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("ENC[") {
+            out.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find(']')
+                .ok_or_else(|| CryptoError::Malformed {
+                    reason: "unterminated ENC[".to_string(),
+                })?;
+            let value = &rest[start..=start + end];
+            let key = keys.get(value).ok_or_else(|| CryptoError::Malformed {
+                reason: "ENC[...] outside of a configuration value".to_string(),
+            })?;
+            out.push_str(&self.reencrypt(key, value).map_err(|e| e.at(key.as_str()))?);
+            rest = &rest[start + end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out.into_bytes())
+    }
+
+    /// Decrypt every `ENC[...]` value of `value` and its children.
+    pub fn decrypt_value(&self, value: &mut Value) -> Result<(), CryptoError> {
+        decrypt_at(self, value, "")
+    }
+
+    /// Decrypt every `ENC[...]` value of a built configuration.
+    pub fn decrypt_config(&self, mut config: Config) -> Result<Config, ConfigError> {
+        self.decrypt_value(&mut config.cache)?;
+        Ok(config)
+    }
+
+    fn primary(&self) -> Result<&ConfigKey, CryptoError> {
+        self.keys.first().ok_or(CryptoError::InvalidKey {
+            reason: "the keyring is empty".to_string(),
+        })
+    }
+
+    fn decrypt_aes(
+        &self,
+        nonce: &Nonce<aes_gcm::aead::consts::U12>,
+        data: &[u8],
+        aad: &str,
+    ) -> Result<Vec<u8>, CryptoError> {
+        let mut keys = self.keys.iter().filter_map(|key| match key {
+            ConfigKey::Aes256Gcm(key) => Some(key.expose()),
+            ConfigKey::Age(_) => None,
+        });
+        let first = keys
+            .next()
+            .ok_or(CryptoError::MissingKey { algorithm: AES })?;
+        std::iter::once(first)
+            .chain(keys)
+            .find_map(|key| {
+                let payload = Payload {
+                    msg: data,
+                    aad: aad.as_bytes(),
+                };
+                Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+                    .decrypt(nonce, payload)
+                    .ok()
+            })
+            .ok_or(CryptoError::Decrypt { algorithm: AES })
+    }
+
+    fn decrypt_age(&self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let mut identities = self.keys.iter().filter_map(|key| match key {
+            ConfigKey::Age(identity) => Some(identity),
+            ConfigKey::Aes256Gcm(_) => None,
+        });
+        let first = identities
+            .next()
+            .ok_or(CryptoError::MissingKey { algorithm: AGE })?;
+        std::iter::once(first)
+            .chain(identities)
+            .find_map(|identity| age::decrypt(identity, data).ok())
+            .ok_or(CryptoError::Decrypt { algorithm: AGE })
+    }
+}
+
+fn age_encrypt_error(e: &age::EncryptError) -> CryptoError {
+    CryptoError::Encrypt {
+        algorithm: AGE,
+        reason: e.to_string(),
+    }
+}
+
+fn decrypt_at(keyring: &Keyring, value: &mut Value, path: &str) -> Result<(), CryptoError> {
+    match &mut value.kind {
+        ValueKind::String(text) if is_encrypted(text) => {
+            *text = keyring.decrypt(path, text).map_err(|e| e.at(path))?;
+        }
+        ValueKind::Table(table) => {
+            for (key, child) in table.iter_mut() {
+                decrypt_at(keyring, child, &join_key(path, key))?;
+            }
+        }
+        ValueKind::Array(array) => {
+            for (i, child) in array.iter_mut().enumerate() {
+                decrypt_at(keyring, child, &format!("{}[{}]", path, i))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Map the `ENC[...]` values of `value` and its children to their dotted keys.
+fn encrypted_keys<'a>(value: &'a Value, path: &str, keys: &mut HashMap<&'a str, String>) {
+    match &value.kind {
+        ValueKind::String(text) if is_encrypted(text) => {
+            keys.insert(text.trim(), path.to_string());
+        }
+        ValueKind::Table(table) => {
+            for (key, child) in table {
+                encrypted_keys(child, &join_key(path, key), keys);
+            }
+        }
+        ValueKind::Array(array) => {
+            for (i, child) in array.iter().enumerate() {
+                encrypted_keys(child, &format!("{}[{}]", path, i), keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether `value` is an `ENC[...]` value.
+pub fn is_encrypted(value: &str) -> bool {
+    let value = value.trim();
+    value.starts_with("ENC[") && value.ends_with(']')
+}
+
+/// Whether the whole of `contents` is encrypted, see [`Keyring::decrypt_file`].
+pub fn is_encrypted_file(contents: &[u8]) -> bool {
+    is_age_file(contents)
+        || std::str::from_utf8(contents)
+            .is_ok_and(|text| is_encrypted(text) && text.trim().matches("ENC[").count() == 1)
+}
+
+fn is_age_file(contents: &[u8]) -> bool {
+    contents.starts_with(AGE_BINARY_HEADER)
+        || contents
+            .trim_ascii_start()
+            .starts_with(AGE_ARMOR_HEADER.as_bytes())
+}
+
+fn contains_encrypted(value: &Value) -> bool {
+    match &value.kind {
+        ValueKind::String(text) => is_encrypted(text),
+        ValueKind::Table(table) => table.values().any(contains_encrypted),
+        ValueKind::Array(array) => array.iter().any(contains_encrypted),
+        _ => false,
+    }
+}
+
+/// `keyring`, or the keys from the environment when it isn't set.
+fn keyring_or_env(keyring: Option<&Keyring>) -> Result<Cow<'_, Keyring>, CryptoError> {
+    match keyring {
+        Some(keyring) => Ok(Cow::Borrowed(keyring)),
+        None => Keyring::from_env().map(Cow::Owned),
+    }
+}
+
+/// Decrypt the `ENC[...]` values of `config`, only reading keys if there are any.
+pub(crate) fn decrypt_config(
+    config: Config,
+    keyring: Option<&Keyring>,
+) -> Result<Config, ConfigError> {
+    if !contains_encrypted(&config.cache) {
+        return Ok(config);
+    }
+    keyring_or_env(keyring)?.decrypt_config(config)
+}
+
+/// `secrets.yaml` for `secrets.yaml.age` or `secrets.yaml.enc`, whose format is the
+/// one of the plaintext.
+pub(crate) fn plaintext_path(path: &Path) -> Cow<'_, Path> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("age" | "enc") => Cow::Owned(path.with_extension("")),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// A configuration file that may be encrypted as a whole.
+#[derive(Debug, Clone)]
+pub(crate) struct EncryptedFile {
+    pub(crate) path: PathBuf,
+    pub(crate) format: ConfigFormat,
+    pub(crate) keyring: Option<Keyring>,
+}
+
+impl Source for EncryptedFile {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let uri = self.path.to_string_lossy().into_owned();
+        let contents = std::fs::read(&self.path).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        let plaintext = if is_encrypted_file(&contents) {
+            keyring_or_env(self.keyring.as_ref())
+                .and_then(|keyring| keyring.decrypt_file(&contents))
+                .map_err(|e| e.at(&uri))?
+        } else {
+            contents
+        };
+        let text = String::from_utf8(plaintext).map_err(|e| ConfigError::FileParse {
+            uri: Some(uri.clone()),
+            cause: Box::new(e),
+        })?;
+        self.format
+            .parse(Some(&uri), &text)
+            .map_err(|cause| ConfigError::FileParse {
+                uri: Some(uri),
+                cause,
+            })
+    }
+}
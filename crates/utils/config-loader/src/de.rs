@@ -0,0 +1,157 @@
+use schemars::{Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer};
+use std::str::FromStr;
+
+/// A list, or a comma-separated string.
+pub(crate) fn one_or_many<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(joined) => joined
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        OneOrMany::Many(list) => list,
+    })
+}
+
+/// Schema of [`one_or_many`] fields.
+pub(crate) fn one_or_many_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "string" },
+            { "type": "array", "items": { "type": "string" } }
+        ]
+    })
+}
+
+/// An optional value, or a string holding one: values buffered by serde, e.g. in
+/// `#[serde(flatten)]` fields, skip the loaders' own string conversions.
+pub(crate) fn parsed_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw<T> {
+        Value(T),
+        Text(String),
+    }
+
+    match Option::<Raw<T>>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Raw::Value(value)) => Ok(Some(value)),
+        Some(Raw::Text(text)) => text
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("invalid value '{}'", text))),
+    }
+}
+
+/// Durations written `500ms`, `5s`, `2m`, `1h` or combined like `1m30s`. Bare numbers
+/// are seconds.
+pub(crate) mod duration {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::time::Duration;
+
+    pub(crate) fn parse(text: &str) -> Result<Duration, String> {
+        let invalid = || {
+            format!(
+                "invalid duration '{}', expected e.g. 500ms, 5s, 2m or 1h",
+                text
+            )
+        };
+        let text = text.trim();
+        if let Ok(secs) = text.parse::<u64>() {
+            return Ok(Duration::from_secs(secs));
+        }
+
+        let mut total = Duration::ZERO;
+        let mut rest = text;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let amount: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+            rest = &rest[digits..];
+            let unit = rest
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let part = match &rest[..unit] {
+                "ms" => Duration::from_millis(amount),
+                "s" => Duration::from_secs(amount),
+                "m" => Duration::from_secs(amount * 60),
+                "h" => Duration::from_secs(amount * 3600),
+                _ => return Err(invalid()),
+            };
+            total += part;
+            rest = &rest[unit..];
+        }
+        Ok(total)
+    }
+
+    /// Largest units first, e.g. `1m30s`, to millisecond precision.
+    pub(crate) fn format(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        let parts = [
+            (secs / 3600, "h"),
+            (secs / 60 % 60, "m"),
+            (secs % 60, "s"),
+            (u64::from(duration.subsec_millis()), "ms"),
+        ];
+        let text: String = parts
+            .iter()
+            .filter(|(amount, _)| *amount > 0)
+            .map(|(amount, unit)| format!("{}{}", amount, unit))
+            .collect();
+        if text.is_empty() {
+            "0s".to_string()
+        } else {
+            text
+        }
+    }
+
+    pub(crate) fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format(*duration))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Duration, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Secs(u64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Secs(secs) => Ok(Duration::from_secs(secs)),
+            Raw::Text(text) => parse(&text).map_err(D::Error::custom),
+        }
+    }
+}
+
+/// Schema of [`duration`] fields.
+pub(crate) fn duration_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "string", "pattern": "^\\s*([0-9]+(ms|s|m|h))+\\s*$|^\\s*[0-9]+\\s*$" },
+            { "type": "integer", "minimum": 0 }
+        ]
+    })
+}
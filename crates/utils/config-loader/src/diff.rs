@@ -0,0 +1,285 @@
+use config::{Config, ConfigError, Format, Value, ValueKind};
+use serde::Serialize;
+use serde_json::Value as Json;
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use crate::{
+    crypto, dump,
+    format::{ConfigFormat, FormatRegistry},
+    schema::{self, Schema},
+    secret::REDACTED,
+    validate::join_key,
+};
+
+/// A key that differs between two configurations.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Change {
+    Added { key: String, value: Json },
+    Removed { key: String, value: Json },
+    Changed { key: String, old: Json, new: Json },
+}
+
+impl Change {
+    /// Dotted key, e.g. `database.pool_size` or `kafka.brokers[1]`, empty for a whole
+    /// encrypted file, see [`diff_files`].
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Added { key, .. } | Self::Removed { key, .. } | Self::Changed { key, .. } => key,
+        }
+    }
+
+    fn values_mut(&mut self) -> Vec<&mut Json> {
+        match self {
+            Self::Added { value, .. } | Self::Removed { value, .. } => vec![value],
+            Self::Changed { old, new, .. } => vec![old, new],
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { key, value } => write!(f, "+ {} = {}", key, value),
+            Self::Removed { key, value } => write!(f, "- {} = {}", key, value),
+            Self::Changed { key, .. } if key.is_empty() => f.write_str("~ encrypted file"),
+            Self::Changed { key, old, new } => write!(f, "~ {}: {} -> {}", key, old, new),
+        }
+    }
+}
+
+/// Keys added, removed and changed from one configuration to another, sorted by key.
+///
+/// Tables are compared key by key and arrays element by element (`kafka.brokers[1]`).
+/// Values are redacted as in a [`ConfigDump`](crate::ConfigDump), and so are encrypted
+/// `ENC[...]` values; changes to redacted values are still listed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConfigDiff {
+    pub changes: Vec<Change>,
+}
+
+impl ConfigDiff {
+    pub fn between(old: &Config, new: &Config) -> Self {
+        Self::between_values(&old.cache, &new.cache)
+    }
+
+    pub fn between_values(old: &Value, new: &Value) -> Self {
+        Self::between_json(&to_json(old), &to_json(new))
+    }
+
+    /// Differences between the serialized forms of two typed configurations.
+    ///
+    /// [`Secret`](crate::Secret) fields serialize as [`REDACTED`], so changes to them
+    /// don't show; diff the [`Config`]s they were loaded from to see those.
+    pub fn between_typed<T: Serialize>(old: &T, new: &T) -> Result<Self, serde_json::Error> {
+        Ok(Self::between_json(
+            &serde_json::to_value(old)?,
+            &serde_json::to_value(new)?,
+        ))
+    }
+
+    fn between_json(old: &Json, new: &Json) -> Self {
+        let old = leaves(old);
+        let new = leaves(new);
+
+        let mut changes = Vec::new();
+        for (key, value) in &old {
+            match new.get(key) {
+                None => changes.push(Change::Removed {
+                    key: key.clone(),
+                    value: value.clone(),
+                }),
+                Some(current) if current != value => changes.push(Change::Changed {
+                    key: key.clone(),
+                    old: value.clone(),
+                    new: current.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (key, value) in &new {
+            if !old.contains_key(key) {
+                changes.push(Change::Added {
+                    key: key.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+        changes.sort_by(|a, b| a.key().cmp(b.key()));
+
+        let mut diff = Self { changes };
+        diff.redact_keys(&[]);
+        diff
+    }
+
+    /// Also redact `key`, and every key below it.
+    pub fn redact(mut self, key: impl Into<String>) -> Self {
+        self.redact_keys(&[key.into()]);
+        self
+    }
+
+    /// Also redact the `writeOnly` fields of `schema`, see [`schema::schema_for`].
+    pub fn redact_schema(mut self, schema: &Schema) -> Self {
+        self.redact_keys(&schema::write_only_keys(schema));
+        self
+    }
+
+    pub(crate) fn redact_keys(&mut self, redacted: &[String]) {
+        for change in &mut self.changes {
+            let redact = dump::is_redacted(change.key(), redacted)
+                || change
+                    .values_mut()
+                    .iter()
+                    .any(|value| value.as_str().is_some_and(crypto::is_encrypted));
+            if redact {
+                for value in change.values_mut() {
+                    *value = Json::String(REDACTED.to_string());
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn added(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|change| matches!(change, Change::Added { .. }))
+    }
+
+    pub fn removed(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|change| matches!(change, Change::Removed { .. }))
+    }
+
+    pub fn changed(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|change| matches!(change, Change::Changed { .. }))
+    }
+}
+
+/// One change per line: `+ key = value`, `- key = value` and `~ key: old -> new`.
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Differences between two configuration files, in the formats detected from their
+/// extensions, e.g. before deploying a new revision.
+///
+/// `ENC[...]` values are compared as written and always redacted. Files encrypted as a
+/// whole aren't decrypted: unless both files are identical, the diff holds a single
+/// redacted change of the empty key, standing for the whole file.
+pub fn diff_files(old: impl AsRef<Path>, new: impl AsRef<Path>) -> Result<ConfigDiff, ConfigError> {
+    let (old, new) = (read_file(old.as_ref())?, read_file(new.as_ref())?);
+    if crypto::is_encrypted_file(&old.1) || crypto::is_encrypted_file(&new.1) {
+        let changes = if old.1 == new.1 {
+            Vec::new()
+        } else {
+            let redacted = || Json::String(REDACTED.to_string());
+            vec![Change::Changed {
+                key: String::new(),
+                old: redacted(),
+                new: redacted(),
+            }]
+        };
+        return Ok(ConfigDiff { changes });
+    }
+    Ok(ConfigDiff::between_values(
+        &parse_file(old)?,
+        &parse_file(new)?,
+    ))
+}
+
+/// The uri, contents and format of a configuration file.
+fn read_file(path: &Path) -> Result<(String, Vec<u8>, ConfigFormat), ConfigError> {
+    let format = FormatRegistry::default()
+        .detect(&crypto::plaintext_path(path))
+        .ok_or_else(|| {
+            ConfigError::Message(format!(
+                "unsupported configuration format for '{}'",
+                path.display()
+            ))
+        })?;
+    let contents = fs::read(path).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+    Ok((path.to_string_lossy().into_owned(), contents, format))
+}
+
+fn parse_file(
+    (uri, contents, format): (String, Vec<u8>, ConfigFormat),
+) -> Result<Value, ConfigError> {
+    let parse_error = |cause| ConfigError::FileParse {
+        uri: Some(uri.clone()),
+        cause,
+    };
+    let text = String::from_utf8(contents).map_err(|e| parse_error(Box::new(e)))?;
+    let map = format.parse(Some(&uri), &text).map_err(parse_error)?;
+    Ok(Value::new(None, ValueKind::Table(map)))
+}
+
+/// Keys whose values are `${...}` placeholders or `ENC[...]`, which must stay redacted
+/// once resolved.
+pub(crate) fn templated_keys(value: &Value) -> Vec<String> {
+    leaves(&to_json(value))
+        .into_iter()
+        .filter(|(_, value)| {
+            value
+                .as_str()
+                .is_some_and(|text| text.contains("${") || crypto::is_encrypted(text))
+        })
+        .map(|(key, _)| key)
+        .collect()
+}
+
+fn to_json(value: &Value) -> Json {
+    match &value.kind {
+        ValueKind::Table(table) => Json::Object(
+            table
+                .iter()
+                .map(|(name, child)| (name.clone(), to_json(child)))
+                .collect(),
+        ),
+        ValueKind::Array(items) => Json::Array(items.iter().map(to_json).collect()),
+        ValueKind::Nil => Json::Null,
+        ValueKind::Boolean(b) => Json::Bool(*b),
+        ValueKind::I64(i) => Json::from(*i),
+        ValueKind::U64(u) => Json::from(*u),
+        ValueKind::Float(f) => Json::from(*f),
+        _ => Json::String(value.to_string()),
+    }
+}
+
+/// Scalars, empty tables and empty arrays, by dotted key.
+fn leaves(value: &Json) -> BTreeMap<String, Json> {
+    fn walk(key: &str, value: &Json, out: &mut BTreeMap<String, Json>) {
+        match value {
+            Json::Object(table) if !table.is_empty() => {
+                for (name, child) in table {
+                    walk(&join_key(key, name), child, out);
+                }
+            }
+            Json::Array(items) if !items.is_empty() => {
+                for (i, item) in items.iter().enumerate() {
+                    walk(&format!("{}[{}]", key, i), item, out);
+                }
+            }
+            _ if key.is_empty() => {}
+            _ => {
+                out.insert(key.to_string(), value.clone());
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    walk("", value, &mut out);
+    out
+}
@@ -0,0 +1,125 @@
+use config::{FileStoredFormat, Format, Map, Value, ValueKind};
+
+use crate::{error::SyntaxError, format::Cursor, keys::insert_path};
+
+/// `.env` files, as read by docker compose and most dotenv libraries.
+///
+/// One `KEY=value` per line, `#` comments and an optional `export ` prefix. Values are
+/// bare (a ` #` starts a comment), single-quoted (literal) or double-quoted (`\n`, `\t`,
+/// `\"` escapes, may span lines).
+///
+/// Keys are lower-cased and `__` nests, so `DATABASE__HOST` sets `database.host`, like
+/// the environment variable layer of [`LayeredConfigLoader`](crate::LayeredConfigLoader).
+#[derive(Debug, Clone, Default)]
+pub struct DotenvFile;
+
+impl Format for DotenvFile {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut result = Map::new();
+        for (key, value) in parse(text)? {
+            let key = key.to_lowercase().replace("__", ".");
+            insert_path(&mut result, &key, Value::new(uri, ValueKind::String(value)))?;
+        }
+        Ok(result)
+    }
+}
+
+impl FileStoredFormat for DotenvFile {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["env"]
+    }
+}
+
+/// Parse dotenv text into its variables, in file order.
+pub fn parse(text: &str) -> Result<Vec<(String, String)>, SyntaxError> {
+    let mut cursor = Cursor::new("dotenv", text);
+    let mut entries = Vec::new();
+
+    loop {
+        while cursor.peek().is_some_and(char::is_whitespace) {
+            cursor.next();
+        }
+        match cursor.peek() {
+            None => break,
+            Some('#') => {
+                cursor.skip_line();
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        let start = cursor.mark();
+        let mut key = String::new();
+        while let Some(c) = cursor.peek().filter(|c| !matches!(c, '=' | '\n')) {
+            key.push(c);
+            cursor.next();
+        }
+        if !cursor.eat("=") {
+            return Err(cursor.error_at(start, "expected KEY=value"));
+        }
+        let key = key
+            .strip_prefix("export")
+            .filter(|rest| rest.starts_with([' ', '\t']))
+            .unwrap_or(&key)
+            .trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(cursor.error_at(start, format!("invalid key '{}'", key)));
+        }
+
+        cursor.skip_blanks();
+        let value = match cursor.peek() {
+            Some(quote @ ('\'' | '"')) => {
+                let value = read_quoted(&mut cursor, quote)?;
+                cursor.skip_blanks();
+                if cursor.peek() == Some('#') {
+                    cursor.skip_line();
+                }
+                if cursor.peek().is_some_and(|c| c != '\n') {
+                    return Err(cursor.error("unexpected characters after quoted value"));
+                }
+                value
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(c) = cursor.peek().filter(|c| *c != '\n') {
+                    if c == '#' && value.ends_with([' ', '\t']) {
+                        cursor.skip_line();
+                        break;
+                    }
+                    value.push(c);
+                    cursor.next();
+                }
+                value.trim_end().to_string()
+            }
+        };
+
+        entries.push((key.to_string(), value));
+    }
+
+    Ok(entries)
+}
+
+fn read_quoted(cursor: &mut Cursor, quote: char) -> Result<String, SyntaxError> {
+    let start = cursor.mark();
+    cursor.next();
+
+    let mut value = String::new();
+    loop {
+        match cursor.next() {
+            None => return Err(cursor.error_at(start, "unterminated quoted value")),
+            Some(c) if c == quote => return Ok(value),
+            Some('\\') if quote == '"' => match cursor.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some(c) => value.push(c),
+                None => return Err(cursor.error_at(start, "unterminated quoted value")),
+            },
+            Some(c) => value.push(c),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use config::{Value, ValueKind};
+use serde_json::{Map as JsonMap, Value as Json};
+use std::fmt::Write;
+
+use crate::{
+    layered::{ConfigLayer, LayeredConfig},
+    properties,
+    schema::{self, Schema},
+    secret::REDACTED,
+    validate::{Validate, join_key},
+};
+
+/// Key names whose values are always redacted, matched case-insensitively against every
+/// segment of a key, e.g. `kafka.security.sasl.password` or `api_token`.
+pub const SENSITIVE_NAMES: &[&str] = &["password", "secret", "token"];
+
+/// Output format of a [`ConfigDump`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum DumpFormat {
+    /// Nested YAML, each value followed by a `# source` comment.
+    #[default]
+    Yaml,
+    /// `{"config": {...}, "sources": {"dotted.key": "source"}}`
+    Json,
+    /// Sorted `key=value` lines, grouped under `# source` comments.
+    Properties,
+}
+
+/// The effective configuration of a [`LayeredConfig`], with the layer or file each key
+/// came from and sensitive values replaced by [`REDACTED`].
+///
+/// Values are redacted when a key segment contains one of [`SENSITIVE_NAMES`], when the
+/// key was passed to [`Self::redact`], when it is a [`Secret`](crate::secret::Secret) or
+/// `#[validate(redact)]` field of the type passed to [`Self::redact_type`], or when the
+/// schema marks it `writeOnly`.
+#[derive(Debug, Clone)]
+pub struct ConfigDump<'a> {
+    config: &'a LayeredConfig,
+    redacted: Vec<String>,
+}
+
+impl<'a> ConfigDump<'a> {
+    pub fn new(config: &'a LayeredConfig) -> Self {
+        Self {
+            config,
+            redacted: Vec::new(),
+        }
+    }
+
+    /// Also redact `key`, and every key below it.
+    pub fn redact(mut self, key: impl Into<String>) -> Self {
+        self.redacted.push(key.into());
+        self
+    }
+
+    /// Also redact the `writeOnly` fields of `schema`, see [`schema::schema_for`].
+    pub fn redact_schema(mut self, schema: &Schema) -> Self {
+        self.redacted.extend(schema::write_only_keys(schema));
+        self
+    }
+
+    /// Also redact the secrets of the configuration type `T`, see [`Validate::redacted_keys`].
+    pub fn redact_type<T: Validate>(mut self) -> Self {
+        T::redacted_keys("", &mut self.redacted);
+        self
+    }
+
+    /// Whether the value at the dotted `key` is printed as [`REDACTED`].
+    pub fn is_redacted(&self, key: &str) -> bool {
+        is_redacted(key, &self.redacted)
+    }
+
+    /// Layer that supplied `key`, or the array or table holding it.
+    pub fn source_of(&self, key: &str) -> Option<&ConfigLayer> {
+        let mut key = key;
+        loop {
+            if let Some(layer) = self.config.source_of(key) {
+                return Some(layer);
+            }
+            key = &key[..key.rfind(['.', '['])?];
+        }
+    }
+
+    /// The redacted configuration as JSON.
+    pub fn to_json(&self) -> Json {
+        self.json_at("", &self.config.config.cache)
+    }
+
+    pub fn render(&self, format: DumpFormat) -> String {
+        let config = self.to_json();
+        match format {
+            DumpFormat::Yaml => {
+                let mut out = String::new();
+                if let Json::Object(table) = &config {
+                    self.write_yaml("", table, 0, &mut out);
+                }
+                out
+            }
+            DumpFormat::Json => {
+                let mut sources = JsonMap::new();
+                for (key, layer) in &self.config.sources {
+                    sources.insert(key.clone(), Json::String(layer.to_string()));
+                }
+                let dump = serde_json::json!({ "config": config, "sources": sources });
+                serde_json::to_string_pretty(&dump).unwrap_or_default() + "\n"
+            }
+            DumpFormat::Properties => {
+                let mut entries = Vec::new();
+                flatten("", &config, &mut entries);
+                entries.sort();
+
+                let mut out = String::new();
+                let mut last_source = None;
+                for (key, value) in entries {
+                    let source = self.source_of(&key).map(ToString::to_string);
+                    if source != last_source {
+                        let _ = writeln!(out, "# {}", source.as_deref().unwrap_or("unknown"));
+                        last_source = source;
+                    }
+                    properties::escape(&key, true, &mut out);
+                    out.push('=');
+                    properties::escape(&value, false, &mut out);
+                    out.push('\n');
+                }
+                out
+            }
+        }
+    }
+
+    fn json_at(&self, key: &str, value: &Value) -> Json {
+        match &value.kind {
+            ValueKind::Table(table) => {
+                let mut names: Vec<_> = table.keys().collect();
+                names.sort();
+                let object = names
+                    .into_iter()
+                    .map(|name| {
+                        (
+                            name.clone(),
+                            self.json_at(&join_key(key, name), &table[name]),
+                        )
+                    })
+                    .collect();
+                Json::Object(object)
+            }
+            ValueKind::Array(items) => Json::Array(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| self.json_at(&format!("{}[{}]", key, i), item))
+                    .collect(),
+            ),
+            ValueKind::Nil => Json::Null,
+            _ if self.is_redacted(key) => Json::String(REDACTED.to_string()),
+            ValueKind::Boolean(b) => Json::Bool(*b),
+            ValueKind::I64(i) => Json::from(*i),
+            ValueKind::U64(u) => Json::from(*u),
+            ValueKind::Float(f) => Json::from(*f),
+            _ => Json::String(value.to_string()),
+        }
+    }
+
+    fn write_yaml(
+        &self,
+        path: &str,
+        table: &JsonMap<String, Json>,
+        indent: usize,
+        out: &mut String,
+    ) {
+        let pad = " ".repeat(indent);
+        for (name, value) in table {
+            let key = join_key(path, name);
+            let source = self
+                .source_of(&key)
+                .map(|layer| format!("  # {}", layer))
+                .unwrap_or_default();
+            let name = yaml_key(name);
+            match value {
+                Json::Object(child) if !child.is_empty() => {
+                    let _ = writeln!(out, "{}{}:", pad, name);
+                    self.write_yaml(&key, child, indent + 2, out);
+                }
+                Json::Array(items) if !items.is_empty() => {
+                    let _ = writeln!(out, "{}{}:{}", pad, name, source);
+                    for item in items {
+                        let _ = writeln!(out, "{}  - {}", pad, item);
+                    }
+                }
+                scalar => {
+                    let _ = writeln!(out, "{}{}: {}{}", pad, name, scalar, source);
+                }
+            }
+        }
+    }
+}
+
+/// Whether `key` has a sensitive name or is, or is below, one of `redacted`.
+pub(crate) fn is_redacted(key: &str, redacted: &[String]) -> bool {
+    let sensitive = key.split('.').any(|segment| {
+        let segment = segment.to_lowercase();
+        SENSITIVE_NAMES.iter().any(|name| segment.contains(name))
+    });
+    sensitive
+        || redacted.iter().any(|redacted| {
+            strip_pattern(key, redacted)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+        })
+}
+
+/// `key` after the prefix matching `pattern`, where `*` matches a list index or map key.
+fn strip_pattern<'a>(key: &'a str, pattern: &str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        None => key.strip_prefix(pattern),
+        Some((head, tail)) => {
+            let rest = key.strip_prefix(head)?;
+            let end = rest.find(['.', '[', ']']).unwrap_or(rest.len());
+            strip_pattern(&rest[end..], tail)
+        }
+    }
+}
+
+fn yaml_key(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+    {
+        name.to_string()
+    } else {
+        Json::String(name.to_string()).to_string()
+    }
+}
+
+fn flatten(key: &str, value: &Json, out: &mut Vec<(String, String)>) {
+    match value {
+        Json::Object(table) => {
+            for (name, child) in table {
+                flatten(&join_key(key, name), child, out);
+            }
+        }
+        Json::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(&format!("{}[{}]", key, i), item, out);
+            }
+        }
+        Json::Null => out.push((key.to_string(), String::new())),
+        Json::String(s) => out.push((key.to_string(), s.clone())),
+        other => out.push((key.to_string(), other.to_string())),
+    }
+}
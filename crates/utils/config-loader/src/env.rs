@@ -1,10 +1,16 @@
+use config::{ConfigError, Map, Source, Value, ValueKind};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
+/// Deployment environment.
+///
+/// Deserializes from any string with the same aliases as [`From<String>`], e.g. `prod`
+/// or `Production`, unknown names are kept as [`Env::Unknown`].
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(from = "String", into = "String")]
 #[non_exhaustive]
 pub enum Env {
-    #[serde(rename = "dev")]
     Development,
     Staging,
     Production,
@@ -21,3 +27,119 @@ impl From<String> for Env {
         }
     }
 }
+
+impl From<&str> for Env {
+    fn from(value: &str) -> Self {
+        Env::from(value.to_string())
+    }
+}
+
+impl From<Env> for String {
+    fn from(env: Env) -> Self {
+        env.name().to_string()
+    }
+}
+
+impl JsonSchema for Env {
+    fn schema_name() -> Cow<'static, str> {
+        "Env".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Deployment environment: dev, staging, prod, or any other name.",
+            "type": "string"
+        })
+    }
+}
+
+impl Env {
+    /// Names this environment goes by, canonical name first.
+    ///
+    /// Used to find the profile file, e.g. `config/prod.toml` for [`Env::Production`].
+    pub fn aliases(&self) -> Vec<&str> {
+        match self {
+            Env::Development => vec!["dev", "development", "sit"],
+            Env::Staging => vec!["staging", "stg"],
+            Env::Production => vec!["production", "prod"],
+            Env::Unknown(name) => vec![name.as_str()],
+        }
+    }
+
+    /// Canonical name of the environment.
+    pub fn name(&self) -> &str {
+        self.aliases()[0]
+    }
+
+    /// Whether `name` is one of the [`Self::aliases`], ignoring case.
+    pub fn matches(&self, name: &str) -> bool {
+        self.aliases()
+            .iter()
+            .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for Env {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Source applying the environment-scoped sections of `inner` for `env`.
+///
+/// Sections named after an alias of `env`, e.g. `[production.kafka]`, are merged over
+/// the top-level keys. Sections of the other known environments are dropped. Keys that
+/// hold a value rather than a section, like `dev = true`, are left alone.
+#[derive(Debug)]
+pub(crate) struct EnvScoped {
+    pub(crate) inner: Box<dyn Source + Send + Sync>,
+    pub(crate) env: Env,
+}
+
+impl Source for EnvScoped {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(EnvScoped {
+            inner: self.inner.clone_into_box(),
+            env: self.env.clone(),
+        })
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let mut table = self.inner.collect()?;
+        let known = [
+            Env::Development,
+            Env::Staging,
+            Env::Production,
+            self.env.clone(),
+        ];
+
+        let mut scoped = Vec::new();
+        table.retain(|key, value| {
+            let ValueKind::Table(section) = &value.kind else {
+                return true;
+            };
+            if self.env.matches(key) {
+                scoped.push(section.clone());
+            }
+            !known.iter().any(|env| env.matches(key))
+        });
+        for section in scoped {
+            merge(&mut table, section);
+        }
+        Ok(table)
+    }
+}
+
+/// Deep-merge `overlay` into `table`, `overlay` wins.
+fn merge(table: &mut Map<String, Value>, overlay: Map<String, Value>) {
+    for (key, mut value) in overlay {
+        if let Some(existing) = table.get_mut(&key)
+            && let (ValueKind::Table(existing), ValueKind::Table(nested)) =
+                (&mut existing.kind, &mut value.kind)
+        {
+            merge(existing, std::mem::take(nested));
+            continue;
+        }
+        table.insert(key, value);
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt;
+use thiserror::Error;
+
+/// Error that occurs when resolving a secret placeholder.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SecretError {
+    #[error("no secret provider registered for '{scheme}'")]
+    UnknownProvider { scheme: String },
+
+    #[error("secret provider '{scheme}' resolves asynchronously, use an asynchronous loader")]
+    AsyncProvider { scheme: String },
+
+    #[error("secret '{reference}' not found")]
+    NotFound { reference: String },
+
+    #[error("unterminated secret placeholder in '{value}'")]
+    Unterminated { value: String },
+
+    #[error("failed to resolve secret '{reference}'")]
+    Provider {
+        reference: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl SecretError {
+    pub fn provider<E>(reference: impl Into<String>, source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self::Provider {
+            reference: reference.into(),
+            source: source.into(),
+        }
+    }
+}
+
+impl From<SecretError> for config::ConfigError {
+    fn from(e: SecretError) -> Self {
+        config::ConfigError::Foreign(Box::new(e))
+    }
+}
+
+/// Error that occurs when fetching remote configuration.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum RemoteError {
+    #[error("request to '{uri}' failed")]
+    Request {
+        uri: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("'{uri}' responded with status {status}")]
+    Status { uri: String, status: u16 },
+
+    #[error("'{uri}' responded 304 Not Modified but nothing is cached")]
+    NotCached { uri: String },
+}
+
+impl RemoteError {
+    /// Whether the server couldn't be reached or failed on its side.
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            RemoteError::Request { .. } => true,
+            RemoteError::Status { status, .. } => *status >= 500,
+            RemoteError::NotCached { .. } => false,
+        }
+    }
+}
+
+impl From<RemoteError> for config::ConfigError {
+    fn from(e: RemoteError) -> Self {
+        config::ConfigError::Foreign(Box::new(e))
+    }
+}
+
+/// Error that occurs when decrypting or encrypting configuration.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CryptoError {
+    #[error(
+        "no {algorithm} key to decrypt with, set CONFIG_AGE_KEY, CONFIG_AES_KEY or CONFIG_KEY_FILE"
+    )]
+    MissingKey { algorithm: &'static str },
+
+    #[error("invalid encryption key: {reason}")]
+    InvalidKey { reason: String },
+
+    #[error("malformed encrypted value: {reason}")]
+    Malformed { reason: String },
+
+    #[error(
+        "failed to decrypt {algorithm} data, wrong key, corrupted ciphertext or value encrypted for another key"
+    )]
+    Decrypt { algorithm: &'static str },
+
+    #[error("failed to encrypt with {algorithm}: {reason}")]
+    Encrypt {
+        algorithm: &'static str,
+        reason: String,
+    },
+
+    #[error("failed to read key file '{}'", path.display())]
+    KeyFile {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("cannot decrypt '{key}': {source}")]
+    Value {
+        /// Dotted key of the encrypted value, or the path of an encrypted file.
+        key: String,
+        #[source]
+        source: Box<CryptoError>,
+    },
+}
+
+impl CryptoError {
+    /// Attach the key of the value being decrypted.
+    pub fn at(self, key: impl Into<String>) -> Self {
+        Self::Value {
+            key: key.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl From<CryptoError> for config::ConfigError {
+    fn from(e: CryptoError) -> Self {
+        config::ConfigError::Foreign(Box::new(e))
+    }
+}
+
+/// Error that occurs when creating a client from
+/// [`HttpClientSettings`](crate::HttpClientSettings).
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum HttpClientError {
+    #[error("invalid default header '{name}'")]
+    Header { name: String },
+
+    #[error("invalid proxy '{url}'")]
+    Proxy {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("cannot read '{path}'")]
+    Read {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid certificate or key in '{path}'")]
+    Certificate {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("cannot create the HTTP client")]
+    Build {
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl From<HttpClientError> for config::ConfigError {
+    fn from(e: HttpClientError) -> Self {
+        config::ConfigError::Foreign(Box::new(e))
+    }
+}
+
+/// Error returned when a required source of an
+/// [`AsyncConfigLoader`](crate::AsyncConfigLoader) fails or runs out of time.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("required configuration source '{name}' failed: {reason}")]
+pub struct SourceError {
+    pub name: String,
+    pub reason: String,
+}
+
+impl From<SourceError> for config::ConfigError {
+    fn from(e: SourceError) -> Self {
+        config::ConfigError::Foreign(Box::new(e))
+    }
+}
+
+/// Error returned when an optional section a component needs isn't configured.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("missing configuration section '{section}'")]
+pub struct MissingSection {
+    /// Dotted key of the section, e.g. `logger.otel`.
+    pub section: &'static str,
+}
+
+impl From<MissingSection> for config::ConfigError {
+    fn from(e: MissingSection) -> Self {
+        config::ConfigError::Foreign(Box::new(e))
+    }
+}
+
+/// Error that occurs when parsing a `.properties` document.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("line {line}, column {column}: {message}")]
+pub struct PropertiesError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Error that occurs when parsing a dotenv or HOCON document.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("{format} line {line}, column {column}: {message}")]
+pub struct SyntaxError {
+    /// Name of the format, e.g. `HOCON`.
+    pub format: &'static str,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// A single failed validation rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Full dotted key, e.g. `database.pool_size`.
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Every violation found while validating a configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationErrors {
+    pub fn add(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.violations.push(Violation {
+            key: key.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Violations for `key`.
+    pub fn get(&self, key: &str) -> impl Iterator<Item = &Violation> {
+        self.violations.iter().filter(move |v| v.key == key)
+    }
+
+    /// `Ok` if nothing was recorded.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration")?;
+        for violation in &self.violations {
+            write!(f, "\n  {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl From<ValidationErrors> for config::ConfigError {
+    fn from(e: ValidationErrors) -> Self {
+        config::ConfigError::Foreign(Box::new(e))
+    }
+}
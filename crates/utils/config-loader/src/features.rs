@@ -0,0 +1,161 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    de::{one_or_many, one_or_many_schema},
+    env::Env,
+    validate::{Validate, ValidationErrors},
+};
+
+/// Feature flags by name, usually the `features` section.
+///
+/// ```toml
+/// [features]
+/// new_router = true
+/// smart_routing = { percentage = 25 }
+/// dark_pool = { envs = ["staging", "prod"] }
+///
+/// [production.features]
+/// smart_routing = { percentage = 5 }
+/// ```
+///
+/// Flags live in the configuration, so a [`ConfigWatcher`](crate::ConfigWatcher) reloads
+/// them with everything else. Unknown flags are off.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(transparent)]
+pub struct FeatureFlags(pub BTreeMap<String, FeatureFlag>);
+
+/// A feature flag: on, off, or rolled out gradually.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(untagged)]
+#[non_exhaustive]
+pub enum FeatureFlag {
+    Enabled(bool),
+    Rollout(Rollout),
+}
+
+/// A flag enabled for a share of the keys, in some environments.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, Validate)]
+#[non_exhaustive]
+pub struct Rollout {
+    /// Share of keys the flag is on for, from 0 to 100.
+    #[serde(default = "full_rollout", deserialize_with = "number")]
+    #[validate(range(min = 0.0, max = 100.0))]
+    pub percentage: f64,
+    /// Environments the flag is on in, as a list or a comma-separated string. Every
+    /// environment when empty.
+    #[serde(default, deserialize_with = "one_or_many")]
+    #[schemars(schema_with = "one_or_many_schema")]
+    pub envs: Vec<String>,
+}
+
+fn full_rollout() -> f64 {
+    100.0
+}
+
+/// A number, or a string holding one: inside untagged enums, values skip the loaders'
+/// own string conversions.
+fn number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Number(f64),
+        Text(String),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Number(number) => Ok(number),
+        Raw::Text(text) => text
+            .trim()
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid number '{}'", text))),
+    }
+}
+
+impl<'de> Deserialize<'de> for FeatureFlag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bool(bool),
+            // environment variables and `.properties` files only have strings
+            Text(String),
+            Rollout(Rollout),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Bool(enabled) => Ok(FeatureFlag::Enabled(enabled)),
+            Raw::Text(text) => match text.trim().to_lowercase().as_str() {
+                "true" | "on" | "yes" | "1" => Ok(FeatureFlag::Enabled(true)),
+                "false" | "off" | "no" | "0" => Ok(FeatureFlag::Enabled(false)),
+                _ => Err(serde::de::Error::custom(format!(
+                    "invalid feature flag '{}', expected a boolean or a rollout",
+                    text
+                ))),
+            },
+            Raw::Rollout(rollout) => Ok(FeatureFlag::Rollout(rollout)),
+        }
+    }
+}
+
+impl Validate for FeatureFlag {
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        if let FeatureFlag::Rollout(rollout) = self {
+            rollout.validate_at(path, errors);
+        }
+    }
+}
+
+impl Validate for FeatureFlags {
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        self.0.validate_at(path, errors);
+    }
+}
+
+impl FeatureFlags {
+    /// Whether `name` is fully on in `env`. Partial rollouts need a key, see
+    /// [`Self::is_enabled_for`].
+    pub fn is_enabled(&self, name: &str, env: Option<&Env>) -> bool {
+        match self.0.get(name) {
+            Some(FeatureFlag::Enabled(enabled)) => *enabled,
+            Some(FeatureFlag::Rollout(rollout)) => {
+                rollout.applies_to(env) && rollout.percentage >= 100.0
+            }
+            None => false,
+        }
+    }
+
+    /// Whether `name` is on in `env` for `key`, e.g. an account or instrument id.
+    ///
+    /// A key always lands in the same bucket, so raising the percentage only adds keys.
+    pub fn is_enabled_for(&self, name: &str, env: Option<&Env>, key: &str) -> bool {
+        match self.0.get(name) {
+            Some(FeatureFlag::Enabled(enabled)) => *enabled,
+            Some(FeatureFlag::Rollout(rollout)) => {
+                rollout.applies_to(env) && (bucket(name, key) as f64) < rollout.percentage * 100.0
+            }
+            None => false,
+        }
+    }
+}
+
+impl Rollout {
+    fn applies_to(&self, env: Option<&Env>) -> bool {
+        self.envs.is_empty()
+            || env.is_some_and(|env| self.envs.iter().any(|name| env.matches(name)))
+    }
+}
+
+/// Stable bucket of `key` for the flag `name`, from 0 to 9999.
+///
+/// FNV-1a rather than `DefaultHasher`, which may change between Rust releases and
+/// would reshuffle rollouts on upgrade.
+fn bucket(name: &str, key: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in name.bytes().chain(*b":").chain(key.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash % 10_000
+}
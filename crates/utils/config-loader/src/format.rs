@@ -0,0 +1,273 @@
+use config::{ConfigError, File, FileFormat, FileSourceFile, FileStoredFormat, Format, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::{dotenv::DotenvFile, error::SyntaxError, hocon::HoconFile, properties::PropertiesFile};
+
+/// Every format the loaders understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConfigFormat {
+    /// A format built into the `config` crate.
+    File(FileFormat),
+    /// Java `.properties`, see [`PropertiesFile`].
+    Properties,
+    /// `.env` files, see [`DotenvFile`].
+    Dotenv,
+    /// HOCON-style `.conf` files, see [`HoconFile`].
+    Hocon,
+}
+
+impl From<FileFormat> for ConfigFormat {
+    fn from(format: FileFormat) -> Self {
+        Self::File(format)
+    }
+}
+
+impl Format for ConfigFormat {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Self::File(format) => format.parse(uri, text),
+            Self::Properties => PropertiesFile.parse(uri, text),
+            Self::Dotenv => DotenvFile.parse(uri, text),
+            Self::Hocon => HoconFile.parse(uri, text),
+        }
+    }
+}
+
+impl FileStoredFormat for ConfigFormat {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::File(format) => format.file_extensions(),
+            Self::Properties => PropertiesFile.file_extensions(),
+            Self::Dotenv => DotenvFile.file_extensions(),
+            Self::Hocon => HoconFile.file_extensions(),
+        }
+    }
+}
+
+/// Maps file extensions to formats.
+///
+/// The default registry knows TOML, YAML, JSON, JSON5, `.properties`, HOCON (`.conf`,
+/// `.hocon`) and dotenv (`.env`, including `.env.local`-style names). When probing for
+/// `config/<name>.*`, extensions are tried in registration order.
+#[derive(Debug, Clone)]
+pub struct FormatRegistry {
+    formats: Vec<(String, ConfigFormat)>,
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        [
+            ConfigFormat::File(FileFormat::Toml),
+            ConfigFormat::File(FileFormat::Yaml),
+            ConfigFormat::File(FileFormat::Json),
+            ConfigFormat::File(FileFormat::Json5),
+            ConfigFormat::Properties,
+            ConfigFormat::Hocon,
+            ConfigFormat::Dotenv,
+        ]
+        .into_iter()
+        .fold(Self::empty(), |registry, format| {
+            format
+                .file_extensions()
+                .iter()
+                .fold(registry, |registry, ext| registry.register(*ext, format))
+        })
+    }
+}
+
+impl FormatRegistry {
+    /// Registry with every built-in format.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry without any format.
+    pub fn empty() -> Self {
+        Self {
+            formats: Vec::new(),
+        }
+    }
+
+    /// Read files ending in `.extension` as `format`, replacing any previous mapping.
+    pub fn register(mut self, extension: impl AsRef<str>, format: impl Into<ConfigFormat>) -> Self {
+        let extension = extension.as_ref().trim_start_matches('.').to_lowercase();
+        let format = format.into();
+        match self.formats.iter_mut().find(|(ext, _)| *ext == extension) {
+            Some(entry) => entry.1 = format,
+            None => self.formats.push((extension, format)),
+        }
+        self
+    }
+
+    /// Registered extensions, in probing order.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.formats.iter().map(|(ext, _)| ext.as_str())
+    }
+
+    /// Format of `path`, from its extension.
+    pub fn detect(&self, path: &Path) -> Option<ConfigFormat> {
+        let name = path.file_name()?.to_str()?;
+        // `.env` and `.env.local` are dotenv files, whatever their "extension"
+        let extension = if name == ".env" || name.starts_with(".env.") {
+            "env"
+        } else {
+            path.extension()?.to_str()?
+        };
+        self.get(extension)
+    }
+
+    /// Format of the document at `uri`, from the extension of its path.
+    pub fn detect_uri(&self, uri: &str) -> Option<ConfigFormat> {
+        let path = uri.split(['?', '#']).next().unwrap_or_default();
+        let path = match path.split_once("://") {
+            // drop the authority
+            Some((_, rest)) => rest.split_once('/')?.1,
+            None => path,
+        };
+        self.detect(Path::new(path))
+    }
+
+    /// Source reading `path` in its detected format.
+    pub fn file(&self, path: &Path) -> Result<File<FileSourceFile, ConfigFormat>, ConfigError> {
+        let format = self.detect(path).ok_or_else(|| {
+            ConfigError::Message(format!(
+                "unsupported configuration format for '{}'",
+                path.display()
+            ))
+        })?;
+        Ok(File::new(&path.to_string_lossy(), format))
+    }
+
+    fn get(&self, extension: &str) -> Option<ConfigFormat> {
+        let extension = extension.to_lowercase();
+        self.formats
+            .iter()
+            .find(|(ext, _)| *ext == extension)
+            .map(|(_, format)| *format)
+    }
+
+    /// First existing `dir/name.<ext>`, limited to the extensions of `only` if set.
+    pub(crate) fn find(
+        &self,
+        dir: &Path,
+        name: &str,
+        only: Option<ConfigFormat>,
+    ) -> Option<(PathBuf, ConfigFormat)> {
+        let candidates: Vec<(&str, ConfigFormat)> = match only {
+            Some(format) => format
+                .file_extensions()
+                .iter()
+                .map(|ext| (*ext, format))
+                .collect(),
+            None => self
+                .formats
+                .iter()
+                .map(|(ext, format)| (ext.as_str(), *format))
+                .collect(),
+        };
+
+        candidates
+            .into_iter()
+            .map(|(ext, format)| (dir.join(format!("{}.{}", name, ext)), format))
+            .find(|(path, _)| path.is_file())
+    }
+}
+
+/// Character reader shared by the hand-written parsers, tracking line and column.
+pub(crate) struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    format: &'static str,
+}
+
+impl Cursor {
+    pub(crate) fn new(format: &'static str, text: &str) -> Self {
+        Self {
+            chars: text
+                .replace("\r\n", "\n")
+                .replace('\r', "\n")
+                .chars()
+                .collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+            format,
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    pub(crate) fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    pub(crate) fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Consume `s` if the input continues with it.
+    pub(crate) fn eat(&mut self, s: &str) -> bool {
+        if !self.starts_with(s) {
+            return false;
+        }
+        for _ in s.chars() {
+            self.next();
+        }
+        true
+    }
+
+    /// Skip spaces and tabs, but not newlines.
+    pub(crate) fn skip_blanks(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.next();
+        }
+    }
+
+    /// Skip up to, but not including, the next newline.
+    pub(crate) fn skip_line(&mut self) {
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.next();
+        }
+    }
+
+    /// Current line and column.
+    pub(crate) fn mark(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    pub(crate) fn error(&self, message: impl Into<String>) -> SyntaxError {
+        self.error_at(self.mark(), message)
+    }
+
+    pub(crate) fn error_at(
+        &self,
+        (line, column): (usize, usize),
+        message: impl Into<String>,
+    ) -> SyntaxError {
+        SyntaxError {
+            format: self.format,
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
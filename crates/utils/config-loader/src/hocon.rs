@@ -0,0 +1,502 @@
+use config::{FileStoredFormat, Format, Map, Value, ValueKind};
+use std::collections::BTreeMap;
+
+use crate::{error::SyntaxError, format::Cursor};
+
+/// Deepest chain of `${...}` substitutions followed before assuming a cycle.
+const MAX_SUBSTITUTION_DEPTH: usize = 32;
+
+/// HOCON-style `.conf` files, as used by Akka and Lightbend Config.
+///
+/// Supports the commonly used subset:
+/// - `key = value`, `key: value` and `key { ... }`, with optional root braces
+/// - dotted keys (`database.host = db`) and quoted keys, duplicate objects are merged
+/// - `#` and `//` comments, newlines or commas between fields and array elements
+/// - quoted, `"""`-quoted and unquoted strings, numbers, booleans and `null`
+/// - `${path}` and `${?path}` substitutions, falling back to environment variables,
+///   and string concatenation such as `url = "http://"${host}":"${port}`
+///
+/// `include`, `+=` and array/object concatenation aren't supported. Placeholders with
+/// a scheme, like `${env:DB_PASS}`, are kept as text for the
+/// [`SecretResolver`](crate::SecretResolver).
+#[derive(Debug, Clone, Default)]
+pub struct HoconFile;
+
+impl Format for HoconFile {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let root = parse(text)?;
+        let mut result = Map::new();
+        for (key, node) in &root {
+            if let Some(value) = resolve(node, &root, uri, 0)? {
+                result.insert(key.clone(), value);
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl FileStoredFormat for HoconFile {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["conf", "hocon"]
+    }
+}
+
+type Object = BTreeMap<String, Node>;
+
+enum Node {
+    Object(Object),
+    Array(Vec<Node>),
+    Scalar(ValueKind),
+    /// String concatenation, or a lone substitution.
+    Concat(Vec<Part>),
+}
+
+enum Part {
+    Text(String),
+    Substitution {
+        path: Vec<String>,
+        optional: bool,
+        at: (usize, usize),
+    },
+}
+
+fn parse(text: &str) -> Result<Object, SyntaxError> {
+    let mut cursor = Cursor::new("HOCON", text);
+    skip_separators(&mut cursor);
+    let root = if cursor.eat("{") {
+        parse_fields(&mut cursor, Some('}'))?
+    } else {
+        parse_fields(&mut cursor, None)?
+    };
+    skip_separators(&mut cursor);
+    if cursor.peek().is_some() {
+        return Err(cursor.error("unexpected content after the root object"));
+    }
+    Ok(root)
+}
+
+/// Skip whitespace, newlines, commas and comments.
+fn skip_separators(cursor: &mut Cursor) {
+    loop {
+        match cursor.peek() {
+            Some(c) if c.is_whitespace() || c == ',' => {
+                cursor.next();
+            }
+            Some('#') => cursor.skip_line(),
+            Some('/') if cursor.starts_with("//") => cursor.skip_line(),
+            _ => break,
+        }
+    }
+}
+
+/// Skip blanks and a trailing comment, then require the end of the field or element.
+fn end_of_item(cursor: &mut Cursor, close: Option<char>) -> Result<(), SyntaxError> {
+    cursor.skip_blanks();
+    if cursor.peek() == Some('#') || cursor.starts_with("//") {
+        cursor.skip_line();
+    }
+    match cursor.peek() {
+        None | Some('\n' | ',') => Ok(()),
+        Some(c) if Some(c) == close => Ok(()),
+        Some(c) => Err(cursor.error(format!("unexpected '{}'", c))),
+    }
+}
+
+fn parse_fields(cursor: &mut Cursor, close: Option<char>) -> Result<Object, SyntaxError> {
+    let mut object = Object::new();
+    loop {
+        skip_separators(cursor);
+        match (cursor.peek(), close) {
+            (None, None) => return Ok(object),
+            (None, Some(close)) => return Err(cursor.error(format!("expected '{}'", close))),
+            (Some(c), Some(close)) if c == close => {
+                cursor.next();
+                return Ok(object);
+            }
+            _ => {}
+        }
+
+        let start = cursor.mark();
+        let path = parse_key(cursor)?;
+        cursor.skip_blanks();
+        if path == ["include"] && cursor.peek() == Some('"') {
+            return Err(cursor.error_at(start, "include is not supported"));
+        }
+
+        let value = if cursor.peek() == Some('{') {
+            parse_value(cursor)?
+        } else if cursor.starts_with("+=") {
+            return Err(cursor.error("'+=' is not supported"));
+        } else if cursor.eat("=") || cursor.eat(":") {
+            cursor.skip_blanks();
+            parse_value(cursor)?
+        } else {
+            return Err(cursor.error("expected '=', ':' or '{' after the key"));
+        };
+
+        insert(&mut object, &path, value);
+        end_of_item(cursor, close)?;
+    }
+}
+
+fn parse_key(cursor: &mut Cursor) -> Result<Vec<String>, SyntaxError> {
+    let mut path = Vec::new();
+    loop {
+        let start = cursor.mark();
+        let segment = if cursor.peek() == Some('"') {
+            read_quoted(cursor)?
+        } else {
+            let mut segment = String::new();
+            while let Some(c) = cursor.peek() {
+                if c.is_whitespace()
+                    || matches!(
+                        c,
+                        '.' | '=' | ':' | '{' | '}' | '[' | ']' | ',' | '#' | '"' | '$'
+                    )
+                    || cursor.starts_with("//")
+                    || cursor.starts_with("+=")
+                {
+                    break;
+                }
+                segment.push(c);
+                cursor.next();
+            }
+            if segment.is_empty() {
+                return Err(cursor.error_at(start, "expected a key"));
+            }
+            segment
+        };
+        path.push(segment);
+
+        if !cursor.eat(".") {
+            return Ok(path);
+        }
+    }
+}
+
+fn parse_value(cursor: &mut Cursor) -> Result<Node, SyntaxError> {
+    match cursor.peek() {
+        Some('{') => {
+            cursor.next();
+            Ok(Node::Object(parse_fields(cursor, Some('}'))?))
+        }
+        Some('[') => {
+            cursor.next();
+            let mut items = Vec::new();
+            loop {
+                skip_separators(cursor);
+                match cursor.peek() {
+                    None => return Err(cursor.error("expected ']'")),
+                    Some(']') => {
+                        cursor.next();
+                        return Ok(Node::Array(items));
+                    }
+                    Some(_) => {
+                        items.push(parse_value(cursor)?);
+                        end_of_item(cursor, Some(']'))?;
+                    }
+                }
+            }
+        }
+        _ => parse_concat(cursor),
+    }
+}
+
+/// A value made of quoted strings, unquoted text and substitutions, up to the end of the item.
+fn parse_concat(cursor: &mut Cursor) -> Result<Node, SyntaxError> {
+    let start = cursor.mark();
+    let mut parts: Vec<Part> = Vec::new();
+    let mut quoted = false;
+    let mut unquoted = String::new();
+
+    let flush = |unquoted: &mut String, parts: &mut Vec<Part>| {
+        if !unquoted.is_empty() {
+            push_text(parts, std::mem::take(unquoted));
+        }
+    };
+
+    loop {
+        match cursor.peek() {
+            None | Some('\n' | ',' | '}' | ']' | '#') => break,
+            Some('/') if cursor.starts_with("//") => break,
+            Some('"') => {
+                flush(&mut unquoted, &mut parts);
+                quoted = true;
+                let text = if cursor.starts_with("\"\"\"") {
+                    read_multiline(cursor)?
+                } else {
+                    read_quoted(cursor)?
+                };
+                push_text(&mut parts, text);
+            }
+            Some('$') if cursor.starts_with("${") => {
+                flush(&mut unquoted, &mut parts);
+                parts.push(read_substitution(cursor)?);
+            }
+            Some(c) => {
+                unquoted.push(c);
+                cursor.next();
+            }
+        }
+    }
+
+    let trimmed = unquoted.trim_end().len();
+    unquoted.truncate(trimmed);
+    flush(&mut unquoted, &mut parts);
+
+    match parts.as_slice() {
+        [] => Err(cursor.error_at(start, "expected a value")),
+        [Part::Text(text)] if !quoted => Ok(Node::Scalar(typed(text))),
+        [Part::Text(text)] => Ok(Node::Scalar(ValueKind::String(text.clone()))),
+        _ => Ok(Node::Concat(parts)),
+    }
+}
+
+fn push_text(parts: &mut Vec<Part>, text: String) {
+    match parts.last_mut() {
+        Some(Part::Text(last)) => last.push_str(&text),
+        _ => parts.push(Part::Text(text)),
+    }
+}
+
+/// Type of an unquoted value.
+fn typed(text: &str) -> ValueKind {
+    match text {
+        "true" => return ValueKind::Boolean(true),
+        "false" => return ValueKind::Boolean(false),
+        "null" => return ValueKind::Nil,
+        _ => {}
+    }
+
+    let numeric = text
+        .trim_start_matches('-')
+        .starts_with(|c: char| c.is_ascii_digit());
+    if numeric {
+        if let Ok(i) = text.parse() {
+            return ValueKind::I64(i);
+        }
+        if let Ok(f) = text.parse() {
+            return ValueKind::Float(f);
+        }
+    }
+    ValueKind::String(text.to_string())
+}
+
+fn read_quoted(cursor: &mut Cursor) -> Result<String, SyntaxError> {
+    let start = cursor.mark();
+    cursor.next();
+
+    let mut text = String::new();
+    loop {
+        match cursor.next() {
+            None | Some('\n') => return Err(cursor.error_at(start, "unterminated string")),
+            Some('"') => return Ok(text),
+            Some('\\') => {
+                let at = cursor.mark();
+                match cursor.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('r') => text.push('\r'),
+                    Some('b') => text.push('\x08'),
+                    Some('f') => text.push('\x0c'),
+                    Some(c @ ('"' | '\\' | '/')) => text.push(c),
+                    Some('u') => {
+                        let digits: String = (0..4).filter_map(|_| cursor.next()).collect();
+                        let c = u32::from_str_radix(&digits, 16)
+                            .ok()
+                            .filter(|_| digits.len() == 4)
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| cursor.error_at(at, "malformed \\uXXXX escape"))?;
+                        text.push(c);
+                    }
+                    _ => return Err(cursor.error_at(at, "invalid escape")),
+                }
+            }
+            Some(c) => text.push(c),
+        }
+    }
+}
+
+fn read_multiline(cursor: &mut Cursor) -> Result<String, SyntaxError> {
+    let start = cursor.mark();
+    cursor.eat("\"\"\"");
+
+    let mut text = String::new();
+    loop {
+        if cursor.starts_with("\"\"\"") {
+            // extra quotes before the closing ones belong to the string
+            while cursor.starts_with("\"\"\"\"") {
+                text.push('"');
+                cursor.next();
+            }
+            cursor.eat("\"\"\"");
+            return Ok(text);
+        }
+        match cursor.next() {
+            Some(c) => text.push(c),
+            None => return Err(cursor.error_at(start, "unterminated string")),
+        }
+    }
+}
+
+fn read_substitution(cursor: &mut Cursor) -> Result<Part, SyntaxError> {
+    let at = cursor.mark();
+    cursor.eat("${");
+
+    let mut inner = String::new();
+    loop {
+        match cursor.next() {
+            Some('}') => break,
+            Some('\n') | None => return Err(cursor.error_at(at, "unterminated substitution")),
+            Some(c) => inner.push(c),
+        }
+    }
+
+    // `${scheme:reference}` is a secret placeholder, not a substitution
+    if inner.contains(':') {
+        return Ok(Part::Text(format!("${{{}}}", inner)));
+    }
+
+    let (optional, path) = match inner.strip_prefix('?') {
+        Some(path) => (true, path),
+        None => (false, inner.as_str()),
+    };
+    let path: Vec<String> = path.split('.').map(|s| s.trim().to_string()).collect();
+    if path.iter().any(String::is_empty) {
+        return Err(cursor.error_at(at, "invalid substitution path"));
+    }
+
+    Ok(Part::Substitution { path, optional, at })
+}
+
+/// Set `path` in `object`, merging into existing objects.
+fn insert(object: &mut Object, path: &[String], value: Node) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+
+    if !rest.is_empty() {
+        let child = object
+            .entry(first.clone())
+            .or_insert_with(|| Node::Object(Object::new()));
+        if !matches!(child, Node::Object(_)) {
+            *child = Node::Object(Object::new());
+        }
+        if let Node::Object(child) = child {
+            insert(child, rest, value);
+        }
+        return;
+    }
+
+    match (object.get_mut(first), value) {
+        (Some(Node::Object(existing)), Node::Object(new)) => {
+            for (key, value) in new {
+                insert(existing, &[key], value);
+            }
+        }
+        (_, value) => {
+            object.insert(first.clone(), value);
+        }
+    }
+}
+
+fn lookup<'a>(root: &'a Object, path: &[String]) -> Option<&'a Node> {
+    let (first, rest) = path.split_first()?;
+    let node = root.get(first)?;
+    match (node, rest.is_empty()) {
+        (_, true) => Some(node),
+        (Node::Object(child), false) => lookup(child, rest),
+        _ => None,
+    }
+}
+
+/// Convert `node` to a value, following substitutions. `None` for an unset `${?path}`.
+fn resolve(
+    node: &Node,
+    root: &Object,
+    uri: Option<&String>,
+    depth: usize,
+) -> Result<Option<Value>, SyntaxError> {
+    let value = match node {
+        Node::Object(object) => {
+            let mut table = Map::new();
+            for (key, child) in object {
+                if let Some(value) = resolve(child, root, uri, depth)? {
+                    table.insert(key.clone(), value);
+                }
+            }
+            ValueKind::Table(table)
+        }
+        Node::Array(items) => {
+            let mut array = Vec::new();
+            for item in items {
+                array.extend(resolve(item, root, uri, depth)?);
+            }
+            ValueKind::Array(array)
+        }
+        Node::Scalar(kind) => kind.clone(),
+        Node::Concat(parts) => {
+            if let [part @ Part::Substitution { .. }] = parts.as_slice() {
+                return substitute(part, root, uri, depth);
+            }
+
+            let mut text = String::new();
+            for part in parts {
+                match part {
+                    Part::Text(s) => text.push_str(s),
+                    Part::Substitution { at, .. } => {
+                        if let Some(value) = substitute(part, root, uri, depth)? {
+                            let value = value.into_string().map_err(|_| {
+                                substitution_error(*at, "only simple values can be concatenated")
+                            })?;
+                            text.push_str(&value);
+                        }
+                    }
+                }
+            }
+            ValueKind::String(text)
+        }
+    };
+    Ok(Some(Value::new(uri, value)))
+}
+
+fn substitute(
+    part: &Part,
+    root: &Object,
+    uri: Option<&String>,
+    depth: usize,
+) -> Result<Option<Value>, SyntaxError> {
+    let Part::Substitution { path, optional, at } = part else {
+        return Ok(None);
+    };
+    if depth >= MAX_SUBSTITUTION_DEPTH {
+        return Err(substitution_error(*at, "substitution cycle"));
+    }
+
+    if let Some(node) = lookup(root, path) {
+        return resolve(node, root, uri, depth + 1);
+    }
+    if let Ok(value) = std::env::var(path.join(".")) {
+        return Ok(Some(Value::new(uri, ValueKind::String(value))));
+    }
+    if *optional {
+        return Ok(None);
+    }
+    Err(substitution_error(
+        *at,
+        format!("unresolved substitution ${{{}}}", path.join(".")),
+    ))
+}
+
+fn substitution_error((line, column): (usize, usize), message: impl Into<String>) -> SyntaxError {
+    SyntaxError {
+        format: "HOCON",
+        line,
+        column,
+        message: message.into(),
+    }
+}
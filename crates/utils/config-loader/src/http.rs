@@ -0,0 +1,243 @@
+use http_client::{
+    Certificate, ClientWithMiddleware, HttpClientBuilder, HttpClientBuilderConfig, Identity, Proxy,
+    RetryConfig,
+    header::{HeaderName, HeaderValue},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, time::Duration};
+
+use crate::{
+    de::{duration, duration_schema},
+    error::HttpClientError,
+    validate::{Validate, ValidationErrors, join_key},
+};
+
+/// HTTP client settings, convertible into an [`HttpClientBuilder`].
+///
+/// ```toml
+/// [http]
+/// base_url = "https://refdata.internal/api/"
+/// timeout = "5s"
+/// connect_timeout = "500ms"
+/// proxy = "http://proxy.internal:3128"
+///
+/// [http.headers]
+/// X-Client = "oms"
+///
+/// [http.tls]
+/// ca_file = "/etc/ssl/internal-ca.pem"
+///
+/// [http.retry]
+/// max_retries = 5
+/// ```
+///
+/// Durations are written `500ms`, `5s`, `2m`, `1h` or combined like `1m30s`; bare numbers
+/// are seconds. Every field has a default.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct HttpClientSettings {
+    /// Prefix of the relative URLs passed to [`Self::url`].
+    #[validate(url)]
+    pub base_url: Option<String>,
+    /// Time allowed for a whole request, 10 seconds by default.
+    #[serde(with = "duration")]
+    #[schemars(schema_with = "duration_schema")]
+    pub timeout: Duration,
+    /// 5 seconds by default.
+    #[serde(with = "duration")]
+    #[schemars(schema_with = "duration_schema")]
+    pub connect_timeout: Duration,
+    /// Idle connections kept per host, 8 by default.
+    pub pool_max_idle_per_host: usize,
+    /// Sent with every request, in addition to `Accept: application/json`.
+    pub headers: BTreeMap<String, String>,
+    /// Proxy for every request, instead of the system proxy.
+    #[validate(url)]
+    pub proxy: Option<String>,
+    #[validate(nested)]
+    pub tls: Option<HttpTlsSettings>,
+    /// Retry transient failures, off by default.
+    #[validate(nested)]
+    pub retry: Option<RetrySettings>,
+}
+
+impl Default for HttpClientSettings {
+    fn default() -> Self {
+        let defaults = HttpClientBuilderConfig::default();
+        Self {
+            base_url: None,
+            timeout: defaults.timeout.unwrap_or_default(),
+            connect_timeout: defaults.connect_timeout.unwrap_or_default(),
+            pool_max_idle_per_host: defaults.max_idle_per_host.unwrap_or_default(),
+            headers: BTreeMap::new(),
+            proxy: None,
+            tls: None,
+            retry: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, Validate)]
+#[validate(custom = check_identity)]
+#[non_exhaustive]
+pub struct HttpTlsSettings {
+    /// PEM CA bundle trusted in addition to the system roots.
+    #[validate(non_empty)]
+    pub ca_file: Option<String>,
+    /// PEM client certificate, for mutual TLS.
+    #[validate(non_empty)]
+    pub cert_file: Option<String>,
+    /// PEM private key of `cert_file`, unless it's in the same file.
+    #[validate(non_empty)]
+    pub key_file: Option<String>,
+}
+
+/// Retry of transient failures, see [`RetryConfig`].
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct RetrySettings {
+    /// 3 by default.
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on every attempt, 100ms by default.
+    #[serde(with = "duration")]
+    #[schemars(schema_with = "duration_schema")]
+    pub min_backoff: Duration,
+    /// 5 seconds by default.
+    #[serde(with = "duration")]
+    #[schemars(schema_with = "duration_schema")]
+    pub max_backoff: Duration,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        let defaults = RetryConfig::default();
+        Self {
+            max_retries: defaults.max_retries,
+            min_backoff: defaults.min_backoff,
+            max_backoff: defaults.max_backoff,
+        }
+    }
+}
+
+impl From<&RetrySettings> for RetryConfig {
+    fn from(settings: &RetrySettings) -> Self {
+        Self {
+            max_retries: settings.max_retries,
+            min_backoff: settings.min_backoff,
+            max_backoff: settings.max_backoff,
+        }
+    }
+}
+
+fn check_identity(tls: &HttpTlsSettings, path: &str, errors: &mut ValidationErrors) {
+    if tls.key_file.is_some() && tls.cert_file.is_none() {
+        errors.add(join_key(path, "cert_file"), "is required with key_file");
+    }
+}
+
+impl HttpClientSettings {
+    /// `path` relative to [`Self::base_url`], unchanged if absolute or without a base URL.
+    pub fn url(&self, path: &str) -> String {
+        match &self.base_url {
+            Some(base) if !path.contains("://") => format!(
+                "{}/{}",
+                base.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            ),
+            _ => path.to_string(),
+        }
+    }
+
+    /// The client these settings describe.
+    pub fn client(&self) -> Result<ClientWithMiddleware, HttpClientError> {
+        HttpClientBuilder::try_from(self)?
+            .try_build()
+            .map_err(|e| HttpClientError::Build {
+                source: Box::new(e),
+            })
+    }
+}
+
+/// Client of the default [`HttpClientSettings`].
+pub(crate) fn default_client() -> ClientWithMiddleware {
+    HttpClientSettings::default()
+        .client()
+        .expect("default HTTP client settings build a client")
+}
+
+impl TryFrom<&HttpClientSettings> for HttpClientBuilder {
+    type Error = HttpClientError;
+
+    /// Reads the TLS files, so fails if they can't be read or parsed.
+    fn try_from(settings: &HttpClientSettings) -> Result<Self, Self::Error> {
+        let mut config = HttpClientBuilderConfig {
+            timeout: Some(settings.timeout),
+            connect_timeout: Some(settings.connect_timeout),
+            max_idle_per_host: Some(settings.pool_max_idle_per_host),
+            ..Default::default()
+        };
+
+        let headers = config.default_headers.get_or_insert_default();
+        for (name, value) in &settings.headers {
+            let invalid = || HttpClientError::Header { name: name.clone() };
+            headers.insert(
+                HeaderName::try_from(name.as_str()).map_err(|_| invalid())?,
+                HeaderValue::try_from(value.as_str()).map_err(|_| invalid())?,
+            );
+        }
+
+        let build = |e| HttpClientError::Build {
+            source: Box::new(e),
+        };
+        let mut builder = HttpClientBuilder::try_new(Some(config)).map_err(build)?;
+
+        if let Some(url) = &settings.proxy {
+            builder = builder.proxy(Proxy::all(url).map_err(|e| HttpClientError::Proxy {
+                url: url.clone(),
+                source: Box::new(e),
+            })?);
+        }
+
+        if let Some(tls) = &settings.tls {
+            if let Some(path) = &tls.ca_file {
+                let certificates = Certificate::from_pem_bundle(&read(path)?).map_err(|e| {
+                    HttpClientError::Certificate {
+                        path: path.clone(),
+                        source: Box::new(e),
+                    }
+                })?;
+                for certificate in certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+            }
+            if let Some(path) = &tls.cert_file {
+                let mut pem = read(path)?;
+                if let Some(key) = &tls.key_file {
+                    pem.push(b'\n');
+                    pem.extend(read(key)?);
+                }
+                let identity =
+                    Identity::from_pem(&pem).map_err(|e| HttpClientError::Certificate {
+                        path: path.clone(),
+                        source: Box::new(e),
+                    })?;
+                builder = builder.identity(identity);
+            }
+        }
+
+        Ok(match &settings.retry {
+            Some(retry) => builder.with_retry(retry.into()),
+            None => builder,
+        })
+    }
+}
+
+fn read(path: &str) -> Result<Vec<u8>, HttpClientError> {
+    fs::read(path).map_err(|source| HttpClientError::Read {
+        path: path.to_string(),
+        source,
+    })
+}
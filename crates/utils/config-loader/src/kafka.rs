@@ -1,9 +1,459 @@
-use serde::{Deserialize, Serialize};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::{
+    de::{one_or_many, one_or_many_schema},
+    secret::Secret,
+    validate::{Validate, ValidationErrors, join_key, rules},
+};
+
+/// Kafka client configuration.
+///
+/// Convert it with [`KafkaConfig::producer_properties`] or
+/// [`KafkaConfig::consumer_properties`] into librdkafka properties.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[validate(custom = check_servers)]
 #[non_exhaustive]
 pub struct KafkaConfig {
     pub enabled: bool,
+    #[validate(non_empty)]
     pub client_id: String,
-    pub servers: String,
+    /// Bootstrap servers, as a list or a comma-separated string.
+    #[serde(deserialize_with = "one_or_many")]
+    #[schemars(schema_with = "one_or_many_schema")]
+    pub servers: Vec<String>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub security: KafkaSecurity,
+    #[validate(nested)]
+    pub producer: Option<KafkaProducerConfig>,
+    #[validate(nested)]
+    pub consumer: Option<KafkaConsumerConfig>,
+    /// Extra librdkafka properties, applied last.
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, Validate)]
+#[validate(custom = check_security)]
+#[non_exhaustive]
+pub struct KafkaSecurity {
+    #[serde(default)]
+    pub protocol: SecurityProtocol,
+    #[validate(nested)]
+    pub sasl: Option<SaslConfig>,
+    #[validate(nested)]
+    pub tls: Option<KafkaTlsConfig>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[schemars(extend("enum" = [
+    "PLAINTEXT", "SSL", "SASL_PLAINTEXT", "SASL_SSL",
+    "plaintext", "ssl", "sasl_plaintext", "sasl_ssl"
+]))]
+#[non_exhaustive]
+pub enum SecurityProtocol {
+    #[default]
+    #[serde(alias = "plaintext")]
+    Plaintext,
+    #[serde(alias = "ssl")]
+    Ssl,
+    #[serde(alias = "sasl_plaintext")]
+    SaslPlaintext,
+    #[serde(alias = "sasl_ssl")]
+    SaslSsl,
+}
+
+impl SecurityProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plaintext => "plaintext",
+            Self::Ssl => "ssl",
+            Self::SaslPlaintext => "sasl_plaintext",
+            Self::SaslSsl => "sasl_ssl",
+        }
+    }
+
+    pub fn uses_sasl(&self) -> bool {
+        matches!(self, Self::SaslPlaintext | Self::SaslSsl)
+    }
+
+    pub fn uses_tls(&self) -> bool {
+        matches!(self, Self::Ssl | Self::SaslSsl)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[non_exhaustive]
+pub struct SaslConfig {
+    pub mechanism: SaslMechanism,
+    #[validate(non_empty)]
+    pub username: String,
+    #[validate(non_empty)]
+    pub password: Secret<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[non_exhaustive]
+pub enum SaslMechanism {
+    #[serde(rename = "PLAIN")]
+    Plain,
+    #[serde(rename = "SCRAM-SHA-256")]
+    ScramSha256,
+    #[serde(rename = "SCRAM-SHA-512")]
+    ScramSha512,
+    #[serde(rename = "OAUTHBEARER")]
+    OAuthBearer,
+    #[serde(rename = "GSSAPI")]
+    Gssapi,
+}
+
+impl SaslMechanism {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plain => "PLAIN",
+            Self::ScramSha256 => "SCRAM-SHA-256",
+            Self::ScramSha512 => "SCRAM-SHA-512",
+            Self::OAuthBearer => "OAUTHBEARER",
+            Self::Gssapi => "GSSAPI",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[non_exhaustive]
+pub struct KafkaTlsConfig {
+    /// CA bundle used to verify the brokers.
+    #[validate(non_empty)]
+    pub ca_location: Option<String>,
+    /// Client certificate, for mutual TLS.
+    #[validate(non_empty)]
+    pub certificate_location: Option<String>,
+    #[validate(non_empty)]
+    pub key_location: Option<String>,
+    pub key_password: Option<Secret<String>>,
+    /// Check the broker hostname against its certificate, on by default.
+    pub verify_hostname: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[validate(custom = check_producer)]
+#[non_exhaustive]
+pub struct KafkaProducerConfig {
+    /// Default topic to produce to.
+    #[validate(non_empty)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub acks: Acks,
+    /// In milliseconds
+    pub linger_ms: Option<u32>,
+    #[serde(default)]
+    pub compression: Compression,
+    pub enable_idempotence: Option<bool>,
+    /// In milliseconds
+    #[validate(range(min = 1))]
+    pub message_timeout_ms: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[non_exhaustive]
+pub struct KafkaConsumerConfig {
+    #[validate(non_empty)]
+    pub group_id: String,
+    /// Topics to subscribe to, as a list or a comma-separated string.
+    #[serde(deserialize_with = "one_or_many")]
+    #[schemars(schema_with = "one_or_many_schema")]
+    #[validate(non_empty)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub auto_offset_reset: AutoOffsetReset,
+    pub enable_auto_commit: Option<bool>,
+    /// In milliseconds
+    #[validate(range(min = 1))]
+    pub session_timeout_ms: Option<u32>,
+}
+
+/// Broker acknowledgements a producer waits for: `0`, `1` or `all` (also `-1`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Acks {
+    None,
+    Leader,
+    #[default]
+    All,
+}
+
+impl Acks {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "0",
+            Self::Leader => "1",
+            Self::All => "all",
+        }
+    }
+}
+
+impl JsonSchema for Acks {
+    fn schema_name() -> Cow<'static, str> {
+        "Acks".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Broker acknowledgements a producer waits for.",
+            "enum": [0, 1, -1, "0", "1", "-1", "none", "leader", "all"]
+        })
+    }
+}
+
+impl Serialize for Acks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Acks {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AcksVisitor;
+
+        impl de::Visitor<'_> for AcksVisitor {
+            type Value = Acks;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("0, 1, -1 or \"all\"")
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Acks, E> {
+                match v {
+                    0 => Ok(Acks::None),
+                    1 => Ok(Acks::Leader),
+                    -1 => Ok(Acks::All),
+                    _ => Err(E::invalid_value(de::Unexpected::Signed(v), &self)),
+                }
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Acks, E> {
+                match i64::try_from(v) {
+                    Ok(v) => self.visit_i64(v),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Acks, E> {
+                match v.trim().to_ascii_lowercase().as_str() {
+                    "0" | "none" => Ok(Acks::None),
+                    "1" | "leader" => Ok(Acks::Leader),
+                    "-1" | "all" => Ok(Acks::All),
+                    _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(AcksVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
+impl Compression {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip => "gzip",
+            Self::Snappy => "snappy",
+            Self::Lz4 => "lz4",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum AutoOffsetReset {
+    Earliest,
+    #[default]
+    Latest,
+    /// Fail instead of resetting.
+    Error,
+}
+
+impl AutoOffsetReset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Earliest => "earliest",
+            Self::Latest => "latest",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl KafkaConfig {
+    /// librdkafka properties shared by producers and consumers: servers, client id and security.
+    pub fn client_properties(&self) -> HashMap<String, String> {
+        self.with_extra(self.common_properties())
+    }
+
+    /// librdkafka properties for a producer, including the `producer` section.
+    pub fn producer_properties(&self) -> HashMap<String, String> {
+        let mut props = self.common_properties();
+        if let Some(producer) = &self.producer {
+            producer.apply(&mut props);
+        }
+        self.with_extra(props)
+    }
+
+    /// librdkafka properties for a consumer, including the `consumer` section.
+    ///
+    /// Topics aren't a client property, subscribe to [`KafkaConsumerConfig::topics`].
+    pub fn consumer_properties(&self) -> HashMap<String, String> {
+        let mut props = self.common_properties();
+        if let Some(consumer) = &self.consumer {
+            consumer.apply(&mut props);
+        }
+        self.with_extra(props)
+    }
+
+    fn common_properties(&self) -> HashMap<String, String> {
+        let mut props = HashMap::new();
+        props.insert("bootstrap.servers".to_string(), self.servers.join(","));
+        props.insert("client.id".to_string(), self.client_id.clone());
+        self.security.apply(&mut props);
+        props
+    }
+
+    fn with_extra(&self, mut props: HashMap<String, String>) -> HashMap<String, String> {
+        props.extend(
+            self.properties
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        props
+    }
+}
+
+impl KafkaSecurity {
+    fn apply(&self, props: &mut HashMap<String, String>) {
+        let mut set = |key: &str, value: &str| props.insert(key.to_string(), value.to_string());
+
+        set("security.protocol", self.protocol.as_str());
+        if let Some(sasl) = &self.sasl {
+            set("sasl.mechanism", sasl.mechanism.as_str());
+            set("sasl.username", &sasl.username);
+            set("sasl.password", sasl.password.expose());
+        }
+        if let Some(tls) = &self.tls {
+            if let Some(ca) = &tls.ca_location {
+                set("ssl.ca.location", ca);
+            }
+            if let Some(cert) = &tls.certificate_location {
+                set("ssl.certificate.location", cert);
+            }
+            if let Some(key) = &tls.key_location {
+                set("ssl.key.location", key);
+            }
+            if let Some(password) = &tls.key_password {
+                set("ssl.key.password", password.expose());
+            }
+            if let Some(verify) = tls.verify_hostname {
+                set(
+                    "ssl.endpoint.identification.algorithm",
+                    if verify { "https" } else { "none" },
+                );
+            }
+        }
+    }
+}
+
+impl KafkaProducerConfig {
+    fn apply(&self, props: &mut HashMap<String, String>) {
+        props.insert("acks".to_string(), self.acks.as_str().to_string());
+        props.insert(
+            "compression.type".to_string(),
+            self.compression.as_str().to_string(),
+        );
+        if let Some(linger) = self.linger_ms {
+            props.insert("linger.ms".to_string(), linger.to_string());
+        }
+        if let Some(idempotence) = self.enable_idempotence {
+            props.insert("enable.idempotence".to_string(), idempotence.to_string());
+        }
+        if let Some(timeout) = self.message_timeout_ms {
+            props.insert("message.timeout.ms".to_string(), timeout.to_string());
+        }
+    }
+}
+
+impl KafkaConsumerConfig {
+    fn apply(&self, props: &mut HashMap<String, String>) {
+        props.insert("group.id".to_string(), self.group_id.clone());
+        props.insert(
+            "auto.offset.reset".to_string(),
+            self.auto_offset_reset.as_str().to_string(),
+        );
+        if let Some(auto_commit) = self.enable_auto_commit {
+            props.insert("enable.auto.commit".to_string(), auto_commit.to_string());
+        }
+        if let Some(timeout) = self.session_timeout_ms {
+            props.insert("session.timeout.ms".to_string(), timeout.to_string());
+        }
+    }
+}
+
+fn check_servers(config: &KafkaConfig, path: &str, errors: &mut ValidationErrors) {
+    if !config.enabled {
+        return;
+    }
+    if let Err(message) = rules::host_port(&config.servers) {
+        errors.add(join_key(path, "servers"), message);
+    }
+}
+
+fn check_security(security: &KafkaSecurity, path: &str, errors: &mut ValidationErrors) {
+    if security.protocol.uses_sasl() && security.sasl.is_none() {
+        errors.add(
+            join_key(path, "sasl"),
+            format!(
+                "is required with security protocol {}",
+                security.protocol.as_str()
+            ),
+        );
+    }
+    if !security.protocol.uses_tls() && security.tls.is_some() {
+        errors.add(
+            join_key(path, "tls"),
+            format!(
+                "is not used with security protocol {}",
+                security.protocol.as_str()
+            ),
+        );
+    }
+}
+
+fn check_producer(producer: &KafkaProducerConfig, path: &str, errors: &mut ValidationErrors) {
+    if producer.enable_idempotence == Some(true) && producer.acks != Acks::All {
+        errors.add(
+            join_key(path, "acks"),
+            format!(
+                "must be all when idempotence is enabled, got {}",
+                producer.acks.as_str()
+            ),
+        );
+    }
 }
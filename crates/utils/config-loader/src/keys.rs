@@ -0,0 +1,133 @@
+use config::{ConfigError, Map, Value, ValueKind};
+use std::collections::BTreeMap;
+
+/// Flatten nested tables into dotted keys. Arrays are kept as single values.
+pub(crate) fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    fn walk(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+        match &value.kind {
+            ValueKind::Table(table) if !table.is_empty() => {
+                for (key, value) in table {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&key, value, out);
+                }
+            }
+            _ if prefix.is_empty() => {}
+            _ => {
+                out.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    walk("", value, &mut out);
+    out
+}
+
+/// Highest array index accepted in keys, so that a key like `a[4294967295]` can't make
+/// us allocate a huge array.
+const MAX_INDEX: usize = 4096;
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Split `a.b[0].c` into its segments.
+fn segments(key: &str) -> Option<Vec<Segment<'_>>> {
+    let mut out = Vec::new();
+    for part in key.split('.') {
+        let (name, mut rest) = part
+            .split_once('[')
+            .map_or((part, ""), |(name, rest)| (name, rest));
+        if name.is_empty() {
+            return None;
+        }
+        out.push(Segment::Key(name));
+
+        while !rest.is_empty() {
+            let (index, after) = rest.split_once(']')?;
+            out.push(Segment::Index(index.trim().parse().ok()?));
+            rest = after.strip_prefix('[').unwrap_or(after);
+            if !after.is_empty() && !after.starts_with('[') {
+                return None;
+            }
+        }
+    }
+    Some(out)
+}
+
+/// Insert `value` at the dotted `key` (`a.b[0].c`), creating tables and arrays on the way.
+///
+/// Keys that aren't valid paths are inserted as-is. Indices above 4096 are rejected.
+pub(crate) fn insert_path(
+    root: &mut Map<String, Value>,
+    key: &str,
+    value: Value,
+) -> Result<(), ConfigError> {
+    let Some(segments) = segments(key) else {
+        root.insert(key.to_string(), value);
+        return Ok(());
+    };
+    if let Some(index) = segments.iter().find_map(|segment| match segment {
+        Segment::Index(index) if *index > MAX_INDEX => Some(index),
+        _ => None,
+    }) {
+        return Err(ConfigError::Message(format!(
+            "array index {} in '{}' is above the maximum of {}",
+            index, key, MAX_INDEX
+        )));
+    }
+
+    let mut current = root
+        .entry(match segments[0] {
+            Segment::Key(name) => name.to_string(),
+            Segment::Index(_) => unreachable!("paths start with a key"),
+        })
+        .or_insert_with(|| Value::new(None, ValueKind::Nil));
+
+    for segment in &segments[1..] {
+        current = match segment {
+            Segment::Key(name) => {
+                if !matches!(current.kind, ValueKind::Table(_)) {
+                    current.kind = ValueKind::Table(Map::new());
+                }
+                let ValueKind::Table(table) = &mut current.kind else {
+                    unreachable!()
+                };
+                table
+                    .entry(name.to_string())
+                    .or_insert_with(|| Value::new(None, ValueKind::Nil))
+            }
+            Segment::Index(index) => {
+                if !matches!(current.kind, ValueKind::Array(_)) {
+                    current.kind = ValueKind::Array(Vec::new());
+                }
+                let ValueKind::Array(array) = &mut current.kind else {
+                    unreachable!()
+                };
+                if array.len() <= *index {
+                    array.resize(*index + 1, Value::new(None, ValueKind::Nil));
+                }
+                &mut array[*index]
+            }
+        };
+    }
+
+    *current = value;
+    Ok(())
+}
+
+/// Expand dotted keys of a flat map into nested tables.
+pub(crate) fn expand(
+    flat: impl IntoIterator<Item = (String, Value)>,
+) -> Result<Map<String, Value>, ConfigError> {
+    let mut root = Map::new();
+    for (key, value) in flat {
+        insert_path(&mut root, &key, value)?;
+    }
+    Ok(root)
+}
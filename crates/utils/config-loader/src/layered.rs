@@ -0,0 +1,399 @@
+use config::{Config, ConfigError, Environment, File, Map, Source};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    crypto::{self, EncryptedFile, Keyring},
+    dump::{ConfigDump, DumpFormat},
+    env::{Env, EnvScoped},
+    format::{ConfigFormat, FormatRegistry},
+    keys::flatten,
+    schema::{self, Schema},
+    secret::SecretResolver,
+    validate::Validate,
+};
+
+/// A layer of a [`LayeredConfigLoader`], lowest precedence first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigLayer {
+    /// Defaults embedded in the binary.
+    Defaults,
+    /// `config/base.*`
+    Base(PathBuf),
+    /// `config/{env}.*`
+    Profile(PathBuf),
+    /// `config/local.*`, meant to stay out of version control.
+    Local(PathBuf),
+    /// Prefixed environment variables.
+    Environment,
+    /// Explicit `key=value` overrides, usually from the command line.
+    Override,
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigLayer::Defaults => f.write_str("defaults"),
+            ConfigLayer::Base(path) | ConfigLayer::Profile(path) | ConfigLayer::Local(path) => {
+                write!(f, "{}", path.display())
+            }
+            ConfigLayer::Environment => f.write_str("environment"),
+            ConfigLayer::Override => f.write_str("override"),
+        }
+    }
+}
+
+/// Configuration merged from every layer, with the layer each key came from.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: Config,
+    /// Final dotted keys (e.g. `database.host`) and the layer that supplied them.
+    pub sources: BTreeMap<String, ConfigLayer>,
+}
+
+impl LayeredConfig {
+    /// Layer that supplied the final value of `key`.
+    pub fn source_of(&self, key: &str) -> Option<&ConfigLayer> {
+        self.sources.get(key)
+    }
+
+    /// Replace `${scheme:reference}` secret placeholders using `resolver`.
+    pub async fn resolve_secrets(mut self, resolver: &SecretResolver) -> Result<Self, ConfigError> {
+        self.config = resolver.resolve_config(self.config).await?;
+        Ok(self)
+    }
+
+    /// Decrypt every `ENC[...]` value with `keyring`.
+    pub fn decrypt(mut self, keyring: &Keyring) -> Result<Self, ConfigError> {
+        self.config = keyring.decrypt_config(self.config)?;
+        Ok(self)
+    }
+
+    /// The effective configuration, printable with secrets redacted.
+    pub fn dump(&self) -> ConfigDump<'_> {
+        ConfigDump::new(self)
+    }
+
+    /// Deserialize without validating, see [`LayeredConfigLoader::load`].
+    pub fn try_deserialize<T: DeserializeOwned>(&self) -> Result<T, ConfigError> {
+        self.config.clone().try_deserialize()
+    }
+}
+
+/// Builds configuration from layers, each overriding the ones before it:
+///
+/// 1. embedded defaults
+/// 2. `config/base.*`
+/// 3. `config/{env}.*`, where `{env}` is any alias of the selected [`Env`]
+/// 4. `config/local.*`
+/// 5. environment variables, e.g. `APP_DATABASE__HOST` for `database.host`
+/// 6. `key=value` overrides
+///
+/// Missing files are skipped. Files may be in any format of the [`FormatRegistry`].
+///
+/// Within each layer, sections named after the selected [`Env`] override the top-level
+/// keys, so a single file can hold `[kafka]` and `[production.kafka]`.
+#[derive(Debug, Clone)]
+pub struct LayeredConfigLoader {
+    dir: PathBuf,
+    defaults: Vec<(String, ConfigFormat)>,
+    formats: FormatRegistry,
+    format: Option<ConfigFormat>,
+    env: Option<Env>,
+    env_prefix: Option<String>,
+    env_vars: Option<Map<String, String>>,
+    overrides: Vec<(String, String)>,
+    schema: Option<Schema>,
+    log_format: Option<DumpFormat>,
+    redacted: Vec<String>,
+    keyring: Option<Keyring>,
+    secrets: SecretResolver,
+}
+
+impl Default for LayeredConfigLoader {
+    fn default() -> Self {
+        Self::new("config")
+    }
+}
+
+impl LayeredConfigLoader {
+    /// Loader reading its files from `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            defaults: Vec::new(),
+            formats: FormatRegistry::default(),
+            format: None,
+            env: None,
+            env_prefix: None,
+            env_vars: None,
+            overrides: Vec::new(),
+            schema: None,
+            log_format: None,
+            redacted: Vec::new(),
+            keyring: None,
+            secrets: SecretResolver::default(),
+        }
+    }
+
+    /// Directory the layer files are read from.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Add embedded defaults, typically `include_str!`-ed into the binary.
+    pub fn defaults(mut self, text: impl Into<String>, format: impl Into<ConfigFormat>) -> Self {
+        self.defaults.push((text.into(), format.into()));
+        self
+    }
+
+    /// Detect file formats with `registry` instead of the default one.
+    pub fn formats(mut self, registry: FormatRegistry) -> Self {
+        self.formats = registry;
+        self
+    }
+
+    /// Only look for files in `format`, whatever the registry says.
+    pub fn format(mut self, format: impl Into<ConfigFormat>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    /// Select the `config/{env}.*` profile, and the `[{env}.*]` sections of every layer,
+    /// e.g. `[production.kafka]` over `[kafka]`.
+    pub fn env(mut self, env: impl Into<Env>) -> Self {
+        self.env = Some(env.into());
+        self
+    }
+
+    /// Read environment variables starting with `{prefix}_`, nesting on `__`.
+    pub fn env_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Use `vars` instead of the process environment.
+    pub fn env_vars(mut self, vars: Map<String, String>) -> Self {
+        self.env_vars = Some(vars);
+        self
+    }
+
+    /// Override a single key.
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push((key.into(), value.into()));
+        self
+    }
+
+    /// Add `key=value` overrides, e.g. the trailing command line arguments.
+    pub fn overrides<I, S>(mut self, args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for arg in args {
+            let arg = arg.as_ref();
+            let (key, value) = arg
+                .split_once('=')
+                .filter(|(key, _)| !key.trim().is_empty())
+                .ok_or_else(|| {
+                    ConfigError::Message(format!("invalid override '{}', expected key=value", arg))
+                })?;
+            self.overrides
+                .push((key.trim().to_string(), value.trim().to_string()));
+        }
+        Ok(self)
+    }
+
+    /// Check the merged values against `schema` in [`Self::load`], before deserializing,
+    /// see [`schema::validate_value`].
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Log the effective configuration in `format` from [`Self::load`], see [`ConfigDump`].
+    pub fn log_config(mut self, format: DumpFormat) -> Self {
+        self.log_format = Some(format);
+        self
+    }
+
+    /// Redact `key` in the logged configuration, on top of sensitive names, the secrets
+    /// of the loaded type and those of the [`Self::schema`].
+    pub fn redact(mut self, key: impl Into<String>) -> Self {
+        self.redacted.push(key.into());
+        self
+    }
+
+    /// Decrypt encrypted files and values with `keyring` instead of the keys from the
+    /// environment, see [`Keyring::from_env`].
+    pub fn keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = Some(keyring);
+        self
+    }
+
+    /// Resolve `${scheme:reference}` placeholders in [`Self::load`] with `resolver`
+    /// instead of [`SecretResolver::default`]. Only its synchronous providers can be used.
+    pub fn secrets(mut self, resolver: SecretResolver) -> Self {
+        self.secrets = resolver;
+        self
+    }
+
+    pub(crate) fn secret_resolver(&self) -> &SecretResolver {
+        &self.secrets
+    }
+
+    pub(crate) fn decrypt(&self, config: Config) -> Result<Config, ConfigError> {
+        crypto::decrypt_config(config, self.keyring.as_ref())
+    }
+
+    /// Keys redacted with [`Self::redact`] and by the schema.
+    pub(crate) fn redacted_keys(&self) -> Vec<String> {
+        let mut keys = self.redacted.clone();
+        if let Some(schema) = &self.schema {
+            keys.extend(schema::write_only_keys(schema));
+        }
+        keys
+    }
+
+    /// Merge every layer.
+    pub fn build(&self) -> Result<LayeredConfig, ConfigError> {
+        let layers = self.layers();
+
+        let mut builder = Config::builder();
+        for (_, source) in &layers {
+            builder = builder.add_source(vec![source.clone_into_box()]);
+        }
+        for (key, value) in &self.overrides {
+            builder = builder.set_override(key.as_str(), value.as_str())?;
+        }
+        let config = builder.build()?;
+
+        let mut layer_keys = Vec::with_capacity(layers.len() + 1);
+        for (layer, source) in layers {
+            let config = Config::builder().add_source(vec![source]).build()?;
+            layer_keys.push((layer, flatten(&config.cache)));
+        }
+        if !self.overrides.is_empty() {
+            let mut builder = Config::builder();
+            for (key, value) in &self.overrides {
+                builder = builder.set_override(key.as_str(), value.as_str())?;
+            }
+            layer_keys.push((ConfigLayer::Override, flatten(&builder.build()?.cache)));
+        }
+
+        let sources = flatten(&config.cache)
+            .into_keys()
+            .filter_map(|key| {
+                let (layer, _) = layer_keys
+                    .iter()
+                    .rev()
+                    .find(|(_, keys)| keys.contains_key(&key))?;
+                Some((key, layer.clone()))
+            })
+            .collect();
+
+        Ok(LayeredConfig { config, sources })
+    }
+
+    /// Merge every layer, decrypt, resolve secret placeholders, deserialize and validate
+    /// the result.
+    pub fn load<T: DeserializeOwned + Validate>(&self) -> Result<T, ConfigError> {
+        let layered = self.build()?;
+        if let Some(format) = self.log_format {
+            let mut dump = layered.dump();
+            for key in &self.redacted {
+                dump = dump.redact(key.clone());
+            }
+            if let Some(schema) = &self.schema {
+                dump = dump.redact_schema(schema);
+            }
+            dump = dump.redact_type::<T>();
+            tracing::info!("effective configuration:\n{}", dump.render(format));
+        }
+        let config = self.decrypt(layered.config)?;
+        let config = self.secrets.resolve_config_blocking(config)?;
+        self.deserialize(config)
+    }
+
+    /// Check decrypted and resolved values against the [`Self::schema`], deserialize and
+    /// validate them.
+    pub(crate) fn deserialize<T: DeserializeOwned + Validate>(
+        &self,
+        config: Config,
+    ) -> Result<T, ConfigError> {
+        if let Some(schema) = &self.schema {
+            schema::validate_value(schema, &config.cache)?;
+        }
+        let config: T = config.try_deserialize()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn layers(&self) -> Vec<(ConfigLayer, Box<dyn Source + Send + Sync>)> {
+        let mut layers: Vec<(ConfigLayer, Box<dyn Source + Send + Sync>)> = Vec::new();
+
+        for (text, format) in &self.defaults {
+            layers.push((
+                ConfigLayer::Defaults,
+                Box::new(File::from_str(text, *format)),
+            ));
+        }
+
+        if let Some((path, format)) = self.find_file("base") {
+            layers.push((ConfigLayer::Base(path.clone()), self.file(path, format)));
+        }
+
+        if let Some((path, format)) = self.env.as_ref().and_then(|env| {
+            env.aliases()
+                .into_iter()
+                .find_map(|alias| self.find_file(alias))
+        }) {
+            layers.push((ConfigLayer::Profile(path.clone()), self.file(path, format)));
+        }
+
+        if let Some((path, format)) = self.find_file("local") {
+            layers.push((ConfigLayer::Local(path.clone()), self.file(path, format)));
+        }
+
+        if let Some(prefix) = &self.env_prefix {
+            let environment = Environment::with_prefix(prefix)
+                .prefix_separator("_")
+                .separator("__")
+                .try_parsing(true)
+                .source(self.env_vars.clone());
+            layers.push((ConfigLayer::Environment, Box::new(environment)));
+        }
+
+        let Some(env) = &self.env else {
+            return layers;
+        };
+        layers
+            .into_iter()
+            .map(|(layer, inner)| {
+                let scoped: Box<dyn Source + Send + Sync> = Box::new(EnvScoped {
+                    inner,
+                    env: env.clone(),
+                });
+                (layer, scoped)
+            })
+            .collect()
+    }
+
+    fn file(&self, path: PathBuf, format: ConfigFormat) -> Box<dyn Source + Send + Sync> {
+        Box::new(EncryptedFile {
+            path,
+            format,
+            keyring: self.keyring.clone(),
+        })
+    }
+
+    /// First existing `dir/name.<ext>`, with its format.
+    fn find_file(&self, name: &str) -> Option<(PathBuf, ConfigFormat)> {
+        self.formats.find(&self.dir, name, self.format)
+    }
+}
@@ -1,10 +1,65 @@
+extern crate self as config_loader;
+
 pub mod app_config;
+pub mod async_loader;
+pub mod calendar;
+pub mod crypto;
 pub mod database;
+mod de;
+pub mod diff;
+pub mod dotenv;
+pub mod dump;
 pub mod env;
+pub mod error;
+pub mod features;
+pub mod format;
+pub mod hocon;
+pub mod http;
 pub mod kafka;
+mod keys;
+pub mod layered;
 pub mod loader;
 pub mod logging;
+mod percent;
+pub mod properties;
 pub mod redis;
-pub use config::{Config, ConfigBuilder, ConfigError, Environment, File, FileFormat};
-pub use loader::{HttpSource, load_config, load_config_async};
 pub mod remote;
+pub mod schema;
+pub mod secret;
+pub mod service;
+pub mod spring;
+pub mod timezone;
+pub mod validate;
+pub mod watcher;
+pub use async_loader::{AsyncConfigLoader, ConfigSource, LoadReport, SourceOutcome};
+pub use calendar::TradingCalendar;
+pub use config::{Config, ConfigBuilder, ConfigError, Environment, File, FileFormat};
+pub use crypto::{ConfigKey, Keyring};
+pub use diff::{ConfigDiff, diff_files};
+pub use dotenv::DotenvFile;
+pub use dump::{ConfigDump, DumpFormat};
+pub use error::{
+    CryptoError, HttpClientError, MissingSection, PropertiesError, RemoteError, SecretError,
+    SourceError, SyntaxError, ValidationErrors, Violation,
+};
+pub use features::FeatureFlags;
+pub use format::{ConfigFormat, FormatRegistry};
+pub use hocon::HoconFile;
+pub use http::HttpClientSettings;
+pub use layered::{ConfigLayer, LayeredConfig, LayeredConfigLoader};
+pub use loader::{
+    HttpAuth, HttpSource, SourceStatus, load_config, load_config_async, load_config_async_with,
+    load_config_with_format, load_validated_config, load_validated_config_async,
+    load_validated_config_async_with, load_validated_config_with_format,
+};
+pub use properties::PropertiesFile;
+pub use schema::JsonSchema;
+pub use secret::{
+    AsyncSecretProvider, EnvSecretProvider, FileSecretProvider, Secret, SecretProvider,
+    SecretResolver, VaultSecretProvider,
+};
+pub use service::ServiceConfig;
+pub use spring::{SpringCloudConfigSource, SpringCloudFormat};
+pub use timezone::Timezone;
+pub use validate::Validate;
+pub use watcher::{ConfigHandle, ConfigUpdate, ConfigWatcher};
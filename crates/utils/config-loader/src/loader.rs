@@ -1,22 +1,264 @@
 use async_trait::async_trait;
 use config::{
-    AsyncSource, Config, ConfigBuilder, ConfigError, File, FileFormat, FileStoredFormat, Format,
-    Map, Value, ValueKind, builder::AsyncState,
+    AsyncSource, Config, ConfigBuilder, ConfigError, Format, Map, Value, builder::AsyncState,
 };
-use http_client::{ClientWithMiddleware, HttpClientBuilder};
+use http_client::{ClientWithMiddleware, HttpClientBuilder, RetryConfig};
 
 use serde::de::DeserializeOwned;
+
+pub use crate::properties::PropertiesFile;
+use crate::{
+    crypto::{self, EncryptedFile},
+    error::{HttpClientError, RemoteError},
+    format::{ConfigFormat, FormatRegistry},
+    http::{self, HttpClientSettings},
+    secret::{Secret, SecretResolver},
+    validate::Validate,
+};
 use std::{
     fmt::Debug,
-    io::{Error, ErrorKind},
-    path::PathBuf,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 
+/// Outcome of the latest [`HttpSource`] fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SourceStatus {
+    /// Nothing fetched yet.
+    Pending,
+    /// Fresh configuration was downloaded.
+    Fetched,
+    /// The server answered `304 Not Modified`, the cached configuration was used.
+    NotModified,
+    /// The server was unavailable, the on-disk last-known-good copy was used.
+    Fallback { reason: String },
+}
+
+/// Authentication sent with every [`HttpSource`] request.
+#[derive(Debug, Clone)]
+pub enum HttpAuth {
+    Bearer(Secret<String>),
+    Basic {
+        username: String,
+        password: Option<Secret<String>>,
+    },
+}
+
+#[derive(Debug)]
+struct CachedResponse {
+    etag: Option<String>,
+    body: String,
+}
+
+#[derive(Debug)]
+struct SourceState {
+    cached: Option<CachedResponse>,
+    status: SourceStatus,
+}
+
+/// Configuration fetched over HTTP.
+///
+/// Clones share their ETag cache and [`SourceStatus`], so keep a clone around to
+/// check [`HttpSource::status`] after handing the source to a builder.
 #[derive(Clone)]
 pub struct HttpSource<F: Format> {
     uri: String,
     format: F,
     client: ClientWithMiddleware,
+    auth: Option<HttpAuth>,
+    headers: Vec<(String, String)>,
+    cache_file: Option<PathBuf>,
+    state: Arc<Mutex<SourceState>>,
+}
+
+impl<F: Format> HttpSource<F> {
+    pub fn new(uri: impl Into<String>, format: F) -> Self {
+        Self {
+            uri: uri.into(),
+            format,
+            client: http::default_client(),
+            auth: None,
+            headers: Vec::new(),
+            cache_file: None,
+            state: Arc::new(Mutex::new(SourceState {
+                cached: None,
+                status: SourceStatus::Pending,
+            })),
+        }
+    }
+
+    pub(crate) fn set_uri(&mut self, uri: String) {
+        self.uri = uri;
+    }
+
+    /// Use a custom client, e.g. with tracing middleware.
+    pub fn with_client(mut self, client: ClientWithMiddleware) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Use a client built from `settings`. Relative URIs aren't resolved against
+    /// [`HttpClientSettings::base_url`], see [`HttpClientSettings::url`].
+    pub fn with_settings(self, settings: &HttpClientSettings) -> Result<Self, HttpClientError> {
+        Ok(self.with_client(settings.client()?))
+    }
+
+    /// Retry transient failures, on top of the client set by [`Self::with_settings`] or
+    /// [`Self::with_client`].
+    pub fn retry(mut self, retry: RetryConfig) -> Self {
+        self.client = HttpClientBuilder::from_client(self.client)
+            .with_retry(retry)
+            .build();
+        self
+    }
+
+    pub fn bearer_auth(mut self, token: impl Into<String>) -> Self {
+        self.auth = Some(HttpAuth::Bearer(Secret::new(token.into())));
+        self
+    }
+
+    pub fn basic_auth(mut self, username: impl Into<String>, password: Option<String>) -> Self {
+        self.auth = Some(HttpAuth::Basic {
+            username: username.into(),
+            password: password.map(Secret::new),
+        });
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Keep the last good response at `path` and fall back to it when the server is unavailable.
+    ///
+    /// The ETag is stored next to it, in `<path>.etag`.
+    pub fn cache_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_file = Some(path.into());
+        self
+    }
+
+    /// Outcome of the latest fetch.
+    pub fn status(&self) -> SourceStatus {
+        self.state.lock().unwrap().status.clone()
+    }
+
+    /// Whether the latest fetch fell back to the on-disk cache.
+    pub fn used_fallback(&self) -> bool {
+        matches!(self.status(), SourceStatus::Fallback { .. })
+    }
+
+    /// Fetch the configuration text, or the cached text with the status to report.
+    async fn fetch(&self) -> Result<(String, Option<String>, SourceStatus), RemoteError> {
+        let etag = {
+            let mut state = self.state.lock().unwrap();
+            if state.cached.is_none() {
+                state.cached = self.read_cache_file();
+            }
+            state.cached.as_ref().and_then(|c| c.etag.clone())
+        };
+
+        let mut request = self.client.get(&self.uri);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        request = match &self.auth {
+            Some(HttpAuth::Bearer(token)) => request.bearer_auth(token.expose()),
+            Some(HttpAuth::Basic { username, password }) => {
+                request.basic_auth(username, password.as_ref().map(|p| p.expose()))
+            }
+            None => request,
+        };
+        if let Some(etag) = &etag {
+            request = request.header("If-None-Match", etag.as_str());
+        }
+
+        let response = request.send().await.map_err(|e| RemoteError::Request {
+            uri: self.uri.clone(),
+            source: Box::new(e),
+        })?;
+
+        let status = response.status().as_u16();
+        if status == 304 {
+            let state = self.state.lock().unwrap();
+            let cached = state
+                .cached
+                .as_ref()
+                .ok_or_else(|| RemoteError::NotCached {
+                    uri: self.uri.clone(),
+                })?;
+            return Ok((
+                cached.body.clone(),
+                cached.etag.clone(),
+                SourceStatus::NotModified,
+            ));
+        }
+        if !(200..300).contains(&status) {
+            return Err(RemoteError::Status {
+                uri: self.uri.clone(),
+                status,
+            });
+        }
+
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await.map_err(|e| RemoteError::Request {
+            uri: self.uri.clone(),
+            source: Box::new(e),
+        })?;
+
+        Ok((body, etag, SourceStatus::Fetched))
+    }
+
+    fn read_cache_file(&self) -> Option<CachedResponse> {
+        let path = self.cache_file.as_ref()?;
+        let body = std::fs::read_to_string(path).ok()?;
+        let etag = std::fs::read_to_string(etag_path(path)).ok();
+        Some(CachedResponse { etag, body })
+    }
+
+    /// Remember a response that parsed successfully.
+    fn store(&self, body: String, etag: Option<String>) {
+        if let Some(path) = &self.cache_file {
+            let written = std::fs::write(path, &body).and_then(|_| match &etag {
+                Some(etag) => std::fs::write(etag_path(path), etag),
+                None => match std::fs::remove_file(etag_path(path)) {
+                    Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+                    _ => Ok(()),
+                },
+            });
+            if let Err(e) = written {
+                tracing::warn!(error = %e, path = %path.display(), "failed to write config cache");
+            }
+        }
+        self.state.lock().unwrap().cached = Some(CachedResponse { etag, body });
+    }
+
+    fn set_status(&self, status: SourceStatus) {
+        self.state.lock().unwrap().status = status;
+    }
+}
+
+fn etag_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".etag");
+    PathBuf::from(name)
+}
+
+impl HttpSource<ConfigFormat> {
+    /// Source reading `uri` in the format detected from the extension of its path.
+    pub fn detect(uri: impl Into<String>) -> Result<Self, ConfigError> {
+        let uri = uri.into();
+        let format = FormatRegistry::default().detect_uri(&uri).ok_or_else(|| {
+            ConfigError::Message(format!("unsupported configuration format for '{}'", uri))
+        })?;
+        Ok(Self::new(uri, format))
+    }
 }
 
 impl<F: Format> Debug for HttpSource<F> {
@@ -24,6 +266,8 @@ impl<F: Format> Debug for HttpSource<F> {
         f.debug_struct("HttpSource")
             .field("uri", &self.uri)
             .field("format", &std::any::type_name::<F>())
+            .field("auth", &self.auth)
+            .field("cache_file", &self.cache_file)
             .finish()
     }
 }
@@ -31,101 +275,172 @@ impl<F: Format> Debug for HttpSource<F> {
 #[async_trait]
 impl<F: Format + Send + Sync> AsyncSource for HttpSource<F> {
     async fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
-        self.client
-            .get(&self.uri)
-            .send()
-            .await
-            .map_err(|e| ConfigError::Foreign(Box::new(e)))? // error conversion is possible from custom AsyncSource impls
-            .text()
-            .await
-            .map_err(|e| ConfigError::Foreign(Box::new(e)))
-            .and_then(|text| {
-                self.format
-                    .parse(Some(&self.uri), &text)
-                    .map_err(ConfigError::Foreign)
-            })
+        let (body, etag, status) = match self.fetch().await {
+            Ok(fetched) => fetched,
+            Err(e) if e.is_unavailable() => match self.read_cache_file() {
+                Some(cached) => {
+                    tracing::warn!(uri = %self.uri, error = %e, "config server unavailable, using cached configuration");
+                    let status = SourceStatus::Fallback {
+                        reason: e.to_string(),
+                    };
+                    (cached.body, cached.etag, status)
+                }
+                None => return Err(e.into()),
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        let map = self
+            .format
+            .parse(Some(&self.uri), &body)
+            .map_err(ConfigError::Foreign)?;
+
+        if status == SourceStatus::Fetched {
+            self.store(body, etag);
+        }
+        self.set_status(status);
+        Ok(map)
     }
 }
 
+/// Load configuration from a local file, detecting its format from the extension
+///
+/// `${env:...}` and `${file:...}` placeholders are resolved, see [`SecretResolver`].
+/// Encrypted files and `ENC[...]` values are decrypted with the keys from the environment,
+/// see [`Keyring::from_env`](crate::crypto::Keyring::from_env). A trailing `.age` or `.enc`
+/// extension is skipped when detecting the format.
+///
+/// To also [`Validate`] the result, see [`load_validated_config`].
 pub fn load_config<T>(path: &str) -> Result<T, ConfigError>
 where
     T: serde::de::DeserializeOwned,
 {
-    let mut config_path = PathBuf::from(path);
+    let config_path = canonicalize(path)?;
+    let format = FormatRegistry::default()
+        .detect(&crypto::plaintext_path(&config_path))
+        .ok_or_else(|| {
+            ConfigError::Message(format!(
+                "unsupported configuration format for '{}'",
+                config_path.display()
+            ))
+        })?;
+    load_file(config_path, format)
+}
+
+/// Load configuration from a local file in `format`, whatever its extension
+///
+/// Placeholders and encrypted values are handled as in [`load_config`].
+pub fn load_config_with_format<T>(
+    path: &str,
+    format: impl Into<ConfigFormat>,
+) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned,
+{
+    let config_path = canonicalize(path)?;
+    load_file(config_path, format.into())
+}
+
+/// [`load_config`], then [`Validate`] the result.
+pub fn load_validated_config<T>(path: &str) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned + Validate,
+{
+    validated(load_config(path)?)
+}
+
+/// [`load_config_with_format`], then [`Validate`] the result.
+pub fn load_validated_config_with_format<T>(
+    path: &str,
+    format: impl Into<ConfigFormat>,
+) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned + Validate,
+{
+    validated(load_config_with_format(path, format)?)
+}
 
-    config_path =
-        std::fs::canonicalize(&config_path).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+fn canonicalize(path: &str) -> Result<PathBuf, ConfigError> {
+    std::fs::canonicalize(path).map_err(|e| ConfigError::Foreign(Box::new(e)))
+}
 
-    let settings = Config::builder()
-        .add_source(File::from(config_path))
-        .build()?;
+fn load_file<T>(path: PathBuf, format: ConfigFormat) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned,
+{
+    let source = EncryptedFile {
+        path,
+        format,
+        keyring: None,
+    };
+    let settings = Config::builder().add_source(source).build()?;
+    let settings = crypto::decrypt_config(settings, None)?;
+    let settings = SecretResolver::default().resolve_config_blocking(settings)?;
 
     settings
         .try_deserialize::<T>()
         .map_err(|e| ConfigError::Foreign(Box::new(e)))
 }
 
+fn validated<T: Validate>(config: T) -> Result<T, ConfigError> {
+    config.validate()?;
+    Ok(config)
+}
+
 /// Load configuration asynchronously from a remote HTTP endpoint
-pub async fn load_config_async<T>(uri: &str, format: FileFormat) -> Result<T, ConfigError>
+///
+/// Placeholders and encrypted values are handled as in [`load_config`].
+/// To combine several sources, see [`AsyncConfigLoader`](crate::AsyncConfigLoader).
+pub async fn load_config_async<T>(
+    uri: &str,
+    format: impl Into<ConfigFormat>,
+) -> Result<T, ConfigError>
 where
     T: DeserializeOwned + Send,
 {
-    let client = HttpClientBuilder::new(None).build();
+    load_config_async_with(uri, format, &HttpClientSettings::default()).await
+}
 
+/// Like [`load_config_async`], fetching with a client built from `settings`. A relative
+/// `uri` is resolved against [`HttpClientSettings::base_url`].
+pub async fn load_config_async_with<T>(
+    uri: &str,
+    format: impl Into<ConfigFormat>,
+    settings: &HttpClientSettings,
+) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned + Send,
+{
+    let source = HttpSource::new(settings.url(uri), format.into()).with_settings(settings)?;
     let config = ConfigBuilder::<AsyncState>::default()
-        .add_async_source(HttpSource {
-            uri: uri.into(),
-            format,
-            client,
-        })
+        .add_async_source(source)
         .build()
         .await?;
+    let config = crypto::decrypt_config(config, None)?;
+    let config = SecretResolver::default().resolve_config(config).await?;
 
     config.try_deserialize()
 }
 
-#[derive(Debug, Clone)]
-pub struct PropertiesFile;
-
-impl Format for PropertiesFile {
-    fn parse(
-        &self,
-        uri: Option<&String>,
-        text: &str,
-    ) -> Result<Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
-        let mut result = Map::new();
-
-        for (lineno, line) in text.lines().enumerate() {
-            let line = line.trim();
-
-            // Skip empty lines and comments (# or !)
-            if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
-                continue;
-            }
-
-            // Split key=value
-            let (key, value) = match line.split_once('=') {
-                Some((k, v)) => (k.trim(), v.trim()),
-                None => {
-                    return Err(Box::new(Error::new(
-                        ErrorKind::InvalidData,
-                        format!("Invalid line {}: '{}'", lineno + 1, line),
-                    )));
-                }
-            };
-
-            result.insert(
-                key.to_string(),
-                Value::new(uri, ValueKind::String(value.to_string())),
-            );
-        }
-
-        Ok(result)
-    }
+/// [`load_config_async`], then [`Validate`] the result.
+pub async fn load_validated_config_async<T>(
+    uri: &str,
+    format: impl Into<ConfigFormat>,
+) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned + Validate + Send,
+{
+    validated(load_config_async(uri, format).await?)
 }
 
-impl FileStoredFormat for PropertiesFile {
-    fn file_extensions(&self) -> &'static [&'static str] {
-        &["properties"]
-    }
+/// [`load_config_async_with`], then [`Validate`] the result.
+pub async fn load_validated_config_async_with<T>(
+    uri: &str,
+    format: impl Into<ConfigFormat>,
+    settings: &HttpClientSettings,
+) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned + Validate + Send,
+{
+    validated(load_config_async_with(uri, format, settings).await?)
 }
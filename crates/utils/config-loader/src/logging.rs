@@ -1,10 +1,16 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::{error::MissingSection, validate::Validate};
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
 #[non_exhaustive]
 pub struct LoggerConfig {
+    #[validate(non_empty)]
     pub max_level: String,
+    #[validate(nested)]
     pub file: Option<FileLoggerConfig>,
+    #[validate(nested)]
     pub otel: Option<OtelConfig>,
 }
 
@@ -18,17 +24,36 @@ impl Default for LoggerConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl LoggerConfig {
+    /// The `logger.file` section.
+    pub fn file(&self) -> Result<&FileLoggerConfig, MissingSection> {
+        self.file.as_ref().ok_or(MissingSection {
+            section: "logger.file",
+        })
+    }
+
+    /// The `logger.otel` section.
+    pub fn otel(&self) -> Result<&OtelConfig, MissingSection> {
+        self.otel.as_ref().ok_or(MissingSection {
+            section: "logger.otel",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
 #[non_exhaustive]
 pub struct FileLoggerConfig {
+    #[validate(range(min = 1))]
     pub max_size: u64,
+    #[validate(non_empty)]
     pub path: String,
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
 #[non_exhaustive]
 pub struct OtelConfig {
+    #[validate(url)]
     pub endpoint: String,
     pub enabled: bool,
 }
@@ -0,0 +1,275 @@
+use config::{Config, ConfigError, FileStoredFormat, Format, Map, Value, ValueKind};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{error::PropertiesError, keys::insert_path};
+
+/// Java `.properties` format.
+///
+/// Follows `java.util.Properties::load`: `=`, `:` or whitespace separators, `#`/`!`
+/// comments, `\` line continuations and escapes including `\uXXXX`. Dotted keys are
+/// nested (`database.host`) and `[n]` suffixes build arrays (`kafka.servers[0]`).
+#[derive(Debug, Clone, Default)]
+pub struct PropertiesFile;
+
+impl Format for PropertiesFile {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut result = Map::new();
+        for (key, value) in parse(text)? {
+            insert_path(&mut result, &key, Value::new(uri, ValueKind::String(value)))?;
+        }
+        Ok(result)
+    }
+}
+
+impl FileStoredFormat for PropertiesFile {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["properties"]
+    }
+}
+
+/// A character of a logical line, with where it came from.
+#[derive(Clone, Copy)]
+struct Char {
+    c: char,
+    line: usize,
+    column: usize,
+}
+
+/// Parse `.properties` text into its key/value pairs, in file order.
+pub fn parse(text: &str) -> Result<Vec<(String, String)>, PropertiesError> {
+    let mut entries = Vec::new();
+    let mut logical: Vec<Char> = Vec::new();
+    let mut continued = false;
+
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    for (index, line) in text.split('\n').enumerate() {
+        let chars: Vec<Char> = line
+            .chars()
+            .enumerate()
+            .map(|(column, c)| Char {
+                c,
+                line: index + 1,
+                column: column + 1,
+            })
+            .collect();
+
+        let start = chars
+            .iter()
+            .position(|ch| !is_whitespace(ch.c))
+            .unwrap_or(chars.len());
+        let content = &chars[start..];
+
+        if !continued && (content.is_empty() || matches!(content[0].c, '#' | '!')) {
+            continue;
+        }
+
+        let trailing_backslashes = content.iter().rev().take_while(|ch| ch.c == '\\').count();
+        continued = trailing_backslashes % 2 == 1;
+        let end = content.len() - usize::from(continued);
+        logical.extend_from_slice(&content[..end]);
+
+        if !continued {
+            entries.push(parse_entry(&logical)?);
+            logical.clear();
+        }
+    }
+
+    if !logical.is_empty() {
+        entries.push(parse_entry(&logical)?);
+    }
+
+    Ok(entries)
+}
+
+fn parse_entry(chars: &[Char]) -> Result<(String, String), PropertiesError> {
+    let mut key = String::new();
+    let mut i = 0;
+    let mut separated = false;
+
+    while i < chars.len() {
+        match chars[i].c {
+            '\\' => i = unescape(chars, i, &mut key)?,
+            '=' | ':' => {
+                separated = true;
+                i += 1;
+                break;
+            }
+            c if is_whitespace(c) => break,
+            c => {
+                key.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    while i < chars.len() && is_whitespace(chars[i].c) {
+        i += 1;
+    }
+    if !separated && i < chars.len() && matches!(chars[i].c, '=' | ':') {
+        i += 1;
+        while i < chars.len() && is_whitespace(chars[i].c) {
+            i += 1;
+        }
+    }
+
+    let mut value = String::new();
+    while i < chars.len() {
+        match chars[i].c {
+            '\\' => i = unescape(chars, i, &mut value)?,
+            c => {
+                value.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    Ok((key, value))
+}
+
+/// Decode the escape starting at `chars[i]` into `out`, returning the index after it.
+fn unescape(chars: &[Char], i: usize, out: &mut String) -> Result<usize, PropertiesError> {
+    let Some(next) = chars.get(i + 1) else {
+        // a lone trailing backslash is dropped, as Java does
+        return Ok(i + 1);
+    };
+
+    let decoded = match next.c {
+        't' => '\t',
+        'n' => '\n',
+        'r' => '\r',
+        'f' => '\x0c',
+        'u' => {
+            let (unit, after) = read_unicode(chars, i)?;
+            if (0xD800..0xDC00).contains(&unit) {
+                // high surrogate, must be followed by a low one
+                let low = match chars.get(after).map(|ch| ch.c) {
+                    Some('\\') if chars.get(after + 1).is_some_and(|ch| ch.c == 'u') => {
+                        Some(read_unicode(chars, after)?)
+                    }
+                    _ => None,
+                };
+                let Some((low, end)) = low.filter(|(low, _)| (0xDC00..0xE000).contains(low)) else {
+                    return Err(error_at(&chars[i], "unpaired surrogate in \\u escape"));
+                };
+                let c = char::decode_utf16([unit, low])
+                    .next()
+                    .and_then(Result::ok)
+                    .ok_or_else(|| error_at(&chars[i], "invalid \\u escape"))?;
+                out.push(c);
+                return Ok(end);
+            }
+            let c = char::from_u32(u32::from(unit))
+                .ok_or_else(|| error_at(&chars[i], "unpaired surrogate in \\u escape"))?;
+            out.push(c);
+            return Ok(after);
+        }
+        other => other,
+    };
+
+    out.push(decoded);
+    Ok(i + 2)
+}
+
+/// Read the four hex digits of the `\u` escape at `chars[i]`.
+fn read_unicode(chars: &[Char], i: usize) -> Result<(u16, usize), PropertiesError> {
+    let digits: String = chars.iter().skip(i + 2).take(4).map(|ch| ch.c).collect();
+    if digits.len() != 4 {
+        return Err(error_at(&chars[i], "malformed \\uXXXX escape"));
+    }
+    let unit = u16::from_str_radix(&digits, 16)
+        .map_err(|_| error_at(&chars[i], "malformed \\uXXXX escape"))?;
+    Ok((unit, i + 6))
+}
+
+fn error_at(ch: &Char, message: &str) -> PropertiesError {
+    PropertiesError {
+        line: ch.line,
+        column: ch.column,
+        message: message.to_string(),
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\x0c')
+}
+
+/// Write a configuration table as `.properties`, one sorted `key=value` per line.
+///
+/// Nested tables become dotted keys and arrays `[n]` keys, so the output parses back
+/// into the same structure.
+pub fn write(table: &Map<String, Value>) -> String {
+    fn walk(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+        match &value.kind {
+            ValueKind::Table(table) => {
+                for (key, value) in table {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&key, value, out);
+                }
+            }
+            ValueKind::Array(array) => {
+                for (i, value) in array.iter().enumerate() {
+                    walk(&format!("{}[{}]", prefix, i), value, out);
+                }
+            }
+            ValueKind::Nil => {
+                out.insert(prefix.to_string(), String::new());
+            }
+            _ => {
+                out.insert(prefix.to_string(), value.to_string());
+            }
+        }
+    }
+
+    let mut entries = BTreeMap::new();
+    for (key, value) in table {
+        walk(key, value, &mut entries);
+    }
+
+    let mut text = String::new();
+    for (key, value) in entries {
+        escape(&key, true, &mut text);
+        text.push('=');
+        escape(&value, false, &mut text);
+        text.push('\n');
+    }
+    text
+}
+
+/// Serialize `value` as `.properties`.
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, ConfigError> {
+    let config = Config::try_from(value)?;
+    Ok(write(&config.cache.into_table()?))
+}
+
+pub(crate) fn escape(text: &str, is_key: bool, out: &mut String) {
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\x0c' => out.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' if is_key || i == 0 => out.push_str("\\ "),
+            c if (' '..='~').contains(&c) => out.push(c),
+            c => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    let _ = write!(out, "\\u{:04X}", unit);
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,17 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::validate::Validate;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
 #[non_exhaustive]
 pub struct RemoteConfig {
+    #[validate(nested)]
     pub config: _RemoteConfig,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
 pub struct _RemoteConfig {
+    #[validate(url)]
     pub url: String,
 }
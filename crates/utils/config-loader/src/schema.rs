@@ -0,0 +1,623 @@
+use config::{Value, ValueKind};
+use serde_json::Value as Json;
+use std::fmt::Write;
+
+pub use schemars::{self, JsonSchema, Schema};
+
+use crate::validate::{ValidationErrors, join_key};
+
+/// Deepest chain of `$ref`s or nested sections followed before giving up.
+const MAX_DEPTH: usize = 32;
+
+/// JSON Schema (draft 2020-12) of the configuration type `T`.
+///
+/// Descriptions come from doc comments. User types derive [`JsonSchema`] next to
+/// `Deserialize`, pointing the derive at this re-export:
+///
+/// ```ignore
+/// #[derive(Deserialize, JsonSchema, Validate)]
+/// #[schemars(crate = "config_loader::schema::schemars")]
+/// struct ServiceConfig { ... }
+/// ```
+pub fn schema_for<T: JsonSchema + ?Sized>() -> Schema {
+    schemars::generate::SchemaGenerator::default().into_root_schema_for::<T>()
+}
+
+/// Check a raw configuration value against `schema`, before it is deserialized.
+///
+/// Scalars are checked the way the loaders convert them, so `"1433"` from an
+/// environment variable passes as an integer. `enum` and `const` strings are compared
+/// ignoring case, like the case-insensitive enums such as [`Acks`](crate::kafka::Acks)
+/// parse them; deserializing still rejects the wrong case of a case-sensitive enum.
+/// Supports the keywords `schemars` emits: `type`, `properties`, `required`,
+/// `additionalProperties`, `items`, `enum`, `const`, `anyOf`/`oneOf`/`allOf`,
+/// `minimum`/`maximum` and local `$ref`s.
+pub fn validate_value(schema: &Schema, value: &Value) -> Result<(), ValidationErrors> {
+    let root = schema.as_value();
+    let mut errors = ValidationErrors::default();
+    check(root, root, value, "", 0, &mut errors);
+    errors.into_result()
+}
+
+/// Dotted keys of the `writeOnly` properties, i.e. the [`Secret`](crate::secret::Secret)
+/// fields, across every variant of tagged enums.
+pub(crate) fn write_only_keys(schema: &Schema) -> Vec<String> {
+    fn walk(schema: &Json, root: &Json, path: &str, depth: usize, out: &mut Vec<String>) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let schema = non_null(schema, root);
+        let variants = array(schema, "oneOf").iter().chain(array(schema, "anyOf"));
+        for object in std::iter::once(schema).chain(variants) {
+            for (name, property) in object_parts(object, root).0 {
+                let key = join_key(path, name);
+                let write_only = |schema: &Json| schema.get("writeOnly") == Some(&Json::Bool(true));
+                if write_only(property) || write_only(non_null(property, root)) {
+                    if !out.contains(&key) {
+                        out.push(key);
+                    }
+                } else {
+                    walk(property, root, &key, depth + 1, out);
+                }
+            }
+        }
+    }
+
+    let root = schema.as_value();
+    let mut out = Vec::new();
+    walk(root, root, "", 0, &mut out);
+    out
+}
+
+fn check(
+    schema: &Json,
+    root: &Json,
+    value: &Value,
+    key: &str,
+    depth: usize,
+    errors: &mut ValidationErrors,
+) {
+    if depth > MAX_DEPTH || schema == &Json::Bool(true) {
+        return;
+    }
+    if schema == &Json::Bool(false) {
+        errors.add(key, "is not allowed");
+        return;
+    }
+    // `$ref` applies next to the sibling keywords, as in tagged enum variants
+    if let Some(target) = ref_target(schema, root) {
+        check(target, root, value, key, depth + 1, errors);
+    }
+
+    let allowed = allowed_values(schema, root);
+    if !allowed.is_empty() {
+        if !allowed.iter().any(|allowed| same_scalar(allowed, value)) {
+            let allowed: Vec<_> = allowed.iter().map(scalar_text).collect();
+            errors.add(
+                key,
+                format!("must be one of {}, got '{}'", allowed.join(", "), value),
+            );
+        }
+        return;
+    }
+
+    for branch in array(schema, "allOf") {
+        check(branch, root, value, key, depth + 1, errors);
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        let branches = array(schema, keyword);
+        if branches.is_empty() {
+            continue;
+        }
+        let mut results: Vec<ValidationErrors> = branches
+            .iter()
+            .map(|branch| {
+                let mut branch_errors = ValidationErrors::default();
+                check(branch, root, value, key, depth + 1, &mut branch_errors);
+                branch_errors
+            })
+            .collect();
+        match results.iter().filter(|e| e.is_empty()).count() {
+            0 => {}
+            1 => continue,
+            _ if keyword == "anyOf" => continue,
+            _ => {
+                errors.add(key, "matches more than one of the allowed schemas");
+                return;
+            }
+        }
+        // report the closest branch, the one with the fewest violations
+        results.sort_by_key(|e| e.violations.len());
+        errors.violations.append(&mut results[0].violations);
+        return;
+    }
+
+    let types = types(schema);
+    if !types.is_empty() && !types.iter().any(|ty| matches_type(ty, value)) {
+        errors.add(
+            key,
+            format!("expected {}, got {}", types.join(" or "), kind_name(value)),
+        );
+        return;
+    }
+
+    if let Some(number) = as_number(value) {
+        if let Some(min) = schema.get("minimum").and_then(Json::as_f64)
+            && number < min
+        {
+            errors.add(key, format!("must be at least {}, got {}", min, value));
+        }
+        if let Some(max) = schema.get("maximum").and_then(Json::as_f64)
+            && number > max
+        {
+            errors.add(key, format!("must be at most {}, got {}", max, value));
+        }
+    }
+
+    match &value.kind {
+        ValueKind::Table(table) => {
+            let properties = schema.get("properties").and_then(Json::as_object);
+            for required in array(schema, "required").iter().filter_map(Json::as_str) {
+                if !table.contains_key(required) {
+                    errors.add(join_key(key, required), "is required");
+                }
+            }
+            for (name, child) in table {
+                let child_key = join_key(key, name);
+                match properties.and_then(|p| p.get(name)) {
+                    Some(property) => check(property, root, child, &child_key, depth + 1, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Json::Bool(false)) => errors.add(child_key, "is not a known key"),
+                        Some(additional) => {
+                            check(additional, root, child, &child_key, depth + 1, errors)
+                        }
+                        None => {}
+                    },
+                }
+            }
+        }
+        ValueKind::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    let item_key = format!("{}[{}]", key, i);
+                    check(item_schema, root, item, &item_key, depth + 1, errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn ref_target<'a>(schema: &'a Json, root: &'a Json) -> Option<&'a Json> {
+    schema
+        .get("$ref")
+        .and_then(Json::as_str)
+        .and_then(|r| r.strip_prefix('#'))
+        .and_then(|pointer| root.pointer(pointer))
+}
+
+/// Follow local `$ref`s, unless they only extend an object schema.
+fn resolve<'a>(mut schema: &'a Json, root: &'a Json) -> &'a Json {
+    for _ in 0..MAX_DEPTH {
+        match ref_target(schema, root) {
+            Some(target) if schema.get("properties").is_none() => schema = target,
+            _ => break,
+        }
+    }
+    schema
+}
+
+/// Properties and required keys of an object schema, including those of its `$ref`s.
+fn object_parts<'a>(schema: &'a Json, root: &'a Json) -> (Vec<(&'a str, &'a Json)>, Vec<&'a str>) {
+    let mut properties: Vec<(&str, &Json)> = Vec::new();
+    let mut required = Vec::new();
+    let mut next = Some(schema);
+    for _ in 0..MAX_DEPTH {
+        let Some(schema) = next else {
+            break;
+        };
+        if let Some(own) = schema.get("properties").and_then(Json::as_object) {
+            for (name, property) in own {
+                if !properties.iter().any(|(known, _)| known == name) {
+                    properties.push((name, property));
+                }
+            }
+        }
+        required.extend(array(schema, "required").iter().filter_map(Json::as_str));
+        next = ref_target(schema, root);
+    }
+    (properties, required)
+}
+
+fn array<'a>(schema: &'a Json, keyword: &str) -> &'a [Json] {
+    schema
+        .get(keyword)
+        .and_then(Json::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn types(schema: &Json) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Json::String(ty)) => vec![ty.as_str()],
+        Some(Json::Array(types)) => types.iter().filter_map(Json::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether `value` is, or will be converted to, the JSON Schema type `ty`.
+fn matches_type(ty: &str, value: &Value) -> bool {
+    match (ty, &value.kind) {
+        ("null", ValueKind::Nil) => true,
+        ("object", ValueKind::Table(_)) => true,
+        ("array", ValueKind::Array(_)) => true,
+        ("string", kind) => !matches!(
+            kind,
+            ValueKind::Table(_) | ValueKind::Array(_) | ValueKind::Nil
+        ),
+        ("boolean", ValueKind::Boolean(_)) => true,
+        ("boolean", ValueKind::String(s)) => {
+            matches!(
+                s.to_lowercase().as_str(),
+                "true" | "false" | "on" | "off" | "yes" | "no" | "1" | "0"
+            )
+        }
+        (
+            "integer",
+            ValueKind::I64(_) | ValueKind::I128(_) | ValueKind::U64(_) | ValueKind::U128(_),
+        ) => true,
+        ("integer", ValueKind::Float(f)) => f.fract() == 0.0,
+        ("integer", ValueKind::String(s)) => s.trim().parse::<i128>().is_ok(),
+        ("number", _) => as_number(value).is_some(),
+        _ => false,
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match &value.kind {
+        ValueKind::I64(i) => Some(*i as f64),
+        ValueKind::I128(i) => Some(*i as f64),
+        ValueKind::U64(u) => Some(*u as f64),
+        ValueKind::U128(u) => Some(*u as f64),
+        ValueKind::Float(f) => Some(*f),
+        ValueKind::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn kind_name(value: &Value) -> &'static str {
+    match value.kind {
+        ValueKind::Nil => "null",
+        ValueKind::Boolean(_) => "boolean",
+        ValueKind::I64(_) | ValueKind::I128(_) | ValueKind::U64(_) | ValueKind::U128(_) => {
+            "integer"
+        }
+        ValueKind::Float(_) => "number",
+        ValueKind::String(_) => "string",
+        ValueKind::Table(_) => "object",
+        ValueKind::Array(_) => "array",
+    }
+}
+
+fn scalar_text(json: &Json) -> String {
+    match json {
+        Json::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Compare scalars by their text ignoring case, as the loaders convert between them.
+fn same_scalar(json: &Json, value: &Value) -> bool {
+    match (&value.kind, json) {
+        (ValueKind::Table(_) | ValueKind::Array(_), _) | (_, Json::Object(_) | Json::Array(_)) => {
+            false
+        }
+        (ValueKind::Nil, json) => json.is_null(),
+        _ => scalar_text(json).eq_ignore_ascii_case(&value.to_string()),
+    }
+}
+
+/// A key of a generated example file.
+struct Field {
+    name: String,
+    comments: Vec<String>,
+    required: bool,
+    kind: FieldKind,
+}
+
+enum FieldKind {
+    Section(Vec<Field>),
+    Value(Json),
+}
+
+/// Commented example YAML file for `schema`.
+///
+/// Every key is documented with its description. Optional keys and sections are
+/// commented out, so the file holds the minimal configuration and placeholders.
+pub fn example_yaml(schema: &Schema) -> String {
+    let mut out = header(schema);
+    let root = schema.as_value();
+    write_yaml(&fields(root, root, 0), 0, false, &mut out);
+    out
+}
+
+/// Commented example TOML file for `schema`, see [`example_yaml`].
+pub fn example_toml(schema: &Schema) -> String {
+    let mut out = header(schema);
+    let root = schema.as_value();
+    write_toml(&fields(root, root, 0), &[], false, &mut out);
+    out.trim_end().to_string() + "\n"
+}
+
+fn header(schema: &Schema) -> String {
+    let mut out = String::new();
+    if let Some(title) = schema.get("title").and_then(Json::as_str) {
+        let _ = writeln!(out, "# {}", title);
+    }
+    // the summary only, examples and links in the rest of the doc comment don't fit a file header
+    if let Some(description) = schema.get("description").and_then(Json::as_str) {
+        for line in description
+            .lines()
+            .take_while(|line| !line.trim().is_empty())
+        {
+            let _ = writeln!(out, "# {}", line);
+        }
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Skip the `null` branch of optional values and follow `$ref`s.
+fn non_null<'a>(schema: &'a Json, root: &'a Json) -> &'a Json {
+    let schema = resolve(schema, root);
+    for keyword in ["anyOf", "oneOf"] {
+        let branches: Vec<&Json> = array(schema, keyword)
+            .iter()
+            .filter(|branch| types(branch) != ["null"])
+            .collect();
+        if let [single] = branches.as_slice()
+            && array(schema, keyword).len() == 2
+        {
+            return resolve(single, root);
+        }
+    }
+    schema
+}
+
+/// The object to document: the schema itself, or the first variant of a tagged enum,
+/// with the tag and the values it takes.
+fn object_schema<'a>(schema: &'a Json, root: &'a Json) -> Option<(&'a Json, Option<Tag<'a>>)> {
+    let schema = non_null(schema, root);
+    if !object_parts(schema, root).0.is_empty() {
+        return Some((schema, None));
+    }
+    let variants: Vec<&Json> = array(schema, "oneOf")
+        .iter()
+        .chain(array(schema, "anyOf"))
+        .filter(|variant| !object_parts(variant, root).0.is_empty())
+        .collect();
+    let first = *variants.first()?;
+
+    let tag_of = |variant: &'a Json| {
+        object_parts(variant, root)
+            .0
+            .into_iter()
+            .find_map(|(name, property)| Some((name, property.get("const")?)))
+    };
+    let tag = tag_of(first).map(|(name, _)| Tag {
+        name,
+        values: variants
+            .iter()
+            .filter_map(|variant| tag_of(variant))
+            .filter(|(other, _)| *other == name)
+            .map(|(_, value)| value.clone())
+            .collect(),
+    });
+    Some((first, tag))
+}
+
+/// The discriminator of a tagged enum, e.g. `engine`.
+struct Tag<'a> {
+    name: &'a str,
+    values: Vec<Json>,
+}
+
+fn fields(schema: &Json, root: &Json, depth: usize) -> Vec<Field> {
+    let Some((object, tag)) = object_schema(schema, root) else {
+        return Vec::new();
+    };
+    let (properties, required) = object_parts(object, root);
+
+    properties
+        .into_iter()
+        .map(|(name, property)| {
+            let mut comments: Vec<String> = property
+                .get("description")
+                .or_else(|| non_null(property, root).get("description"))
+                .and_then(Json::as_str)
+                .map(|d| d.lines().map(str::to_string).collect())
+                .unwrap_or_default();
+
+            let kind = match object_schema(property, root) {
+                Some(_) if depth < MAX_DEPTH => {
+                    FieldKind::Section(fields(property, root, depth + 1))
+                }
+                _ => {
+                    let resolved = non_null(property, root);
+                    let allowed = match &tag {
+                        Some(tag) if tag.name == name => tag.values.clone(),
+                        _ => allowed_values(resolved, root),
+                    };
+                    if allowed.len() > 1 {
+                        comments.push(format!(
+                            "One of: {}",
+                            allowed
+                                .iter()
+                                .map(scalar_text)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                    }
+                    FieldKind::Value(example_value(property, resolved, &allowed))
+                }
+            };
+
+            Field {
+                name: name.to_string(),
+                comments,
+                required: required.contains(&name),
+                kind,
+            }
+        })
+        .collect()
+}
+
+/// Values of an enum, given as `enum`, `const` or a `oneOf` of constants. Empty for
+/// anything else.
+fn allowed_values(schema: &Json, root: &Json) -> Vec<Json> {
+    let values = if let Some(values) = schema.get("enum").and_then(Json::as_array) {
+        values.clone()
+    } else if let Some(value) = schema.get("const") {
+        vec![value.clone()]
+    } else {
+        let variants = array(schema, "oneOf");
+        let values: Vec<Vec<Json>> = variants
+            .iter()
+            .map(|variant| allowed_values(resolve(variant, root), root))
+            .collect();
+        if values.iter().any(Vec::is_empty) {
+            return Vec::new();
+        }
+        values.concat()
+    };
+
+    let mut distinct: Vec<Json> = Vec::new();
+    for value in values {
+        if !distinct
+            .iter()
+            .any(|known| scalar_text(known) == scalar_text(&value))
+        {
+            distinct.push(value);
+        }
+    }
+    distinct
+}
+
+fn example_value(property: &Json, resolved: &Json, allowed: &[Json]) -> Json {
+    if let Some(default) = property.get("default").or_else(|| resolved.get("default")) {
+        return default.clone();
+    }
+    if let Some(first) = allowed.first() {
+        return first.clone();
+    }
+    let types = types(resolved);
+    let ty = types.iter().find(|ty| **ty != "null").copied().or_else(|| {
+        array(resolved, "anyOf")
+            .iter()
+            .flat_map(|branch| self::types(branch))
+            .find(|ty| *ty != "null")
+    });
+    match ty {
+        Some("boolean") => Json::Bool(false),
+        Some("integer" | "number") => resolved.get("minimum").cloned().unwrap_or(Json::from(0)),
+        Some("array") => Json::Array(Vec::new()),
+        Some("object") => Json::Object(Default::default()),
+        _ => Json::String(String::new()),
+    }
+}
+
+fn write_comments(comments: &[String], indent: &str, out: &mut String) {
+    for comment in comments {
+        let _ = writeln!(out, "{}# {}", indent, comment);
+    }
+}
+
+/// `"# "` for keys that are optional or inside an optional section.
+fn comment_prefix(field: &Field, commented: bool) -> &'static str {
+    let null = matches!(field.kind, FieldKind::Value(Json::Null));
+    if commented || !field.required || null {
+        "# "
+    } else {
+        ""
+    }
+}
+
+fn write_yaml(fields: &[Field], indent: usize, commented: bool, out: &mut String) {
+    let pad = " ".repeat(indent);
+    for field in fields {
+        write_comments(&field.comments, &pad, out);
+        let prefix = comment_prefix(field, commented);
+        match &field.kind {
+            FieldKind::Section(children) if children.is_empty() => {
+                let _ = writeln!(out, "{}{}{}: {{}}", pad, prefix, field.name);
+            }
+            FieldKind::Section(children) => {
+                let _ = writeln!(out, "{}{}{}:", pad, prefix, field.name);
+                write_yaml(children, indent + 2, !prefix.is_empty(), out);
+            }
+            FieldKind::Value(value) => {
+                let _ = writeln!(out, "{}{}{}: {}", pad, prefix, field.name, value);
+            }
+        }
+    }
+}
+
+fn write_toml(fields: &[Field], path: &[&str], commented: bool, out: &mut String) {
+    for field in fields {
+        if let FieldKind::Value(value) = &field.kind {
+            write_comments(&field.comments, "", out);
+            let prefix = comment_prefix(field, commented);
+            let _ = writeln!(
+                out,
+                "{}{} = {}",
+                prefix,
+                toml_key(&field.name),
+                toml_value(value)
+            );
+        }
+    }
+    for field in fields {
+        if let FieldKind::Section(children) = &field.kind {
+            let mut child_path = path.to_vec();
+            child_path.push(&field.name);
+            let header: Vec<_> = child_path.iter().map(|key| toml_key(key)).collect();
+            let prefix = comment_prefix(field, commented);
+            out.push('\n');
+            write_comments(&field.comments, "", out);
+            let _ = writeln!(out, "{}[{}]", prefix, header.join("."));
+            write_toml(children, &child_path, !prefix.is_empty(), out);
+        }
+    }
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_string()
+    } else {
+        Json::String(key.to_string()).to_string()
+    }
+}
+
+fn toml_value(value: &Json) -> String {
+    match value {
+        Json::Null => "\"\"".to_string(),
+        Json::Array(items) => {
+            let items: Vec<_> = items.iter().map(toml_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Json::Object(table) if table.is_empty() => "{}".to_string(),
+        Json::Object(table) => {
+            let entries: Vec<_> = table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", toml_key(key), toml_value(value)))
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
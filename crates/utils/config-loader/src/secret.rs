@@ -0,0 +1,435 @@
+use async_trait::async_trait;
+use config::{Config, ConfigError, FileFormat, Format, Value, ValueKind};
+use http_client::ClientWithMiddleware;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    borrow::Cow, collections::HashMap, fmt, future::Future, path::PathBuf, pin::Pin, sync::Arc,
+};
+
+use crate::{
+    error::{HttpClientError, SecretError},
+    http::{self, HttpClientSettings},
+};
+
+/// Placeholder printed instead of a secret value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// A value that must never end up in logs or dumps.
+///
+/// Deserializes like the inner value, but prints and serializes as [`REDACTED`].
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Access the secret value.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({})", REDACTED)
+    }
+}
+
+impl<T> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Secret)
+    }
+}
+
+/// The schema of `T`, marked `writeOnly`.
+impl<T: JsonSchema> JsonSchema for Secret<T> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut schema = T::json_schema(generator);
+        schema.insert("writeOnly".to_string(), true.into());
+        schema
+    }
+}
+
+/// Resolves the `reference` part of `${scheme:reference}` placeholders without waiting,
+/// so that the synchronous loaders can use it too.
+pub trait SecretProvider: Send + Sync + 'static {
+    /// Placeholder scheme handled by this provider, e.g. `env` for `${env:DB_PASS}`.
+    fn scheme(&self) -> &str;
+
+    fn resolve(&self, reference: &str) -> Result<String, SecretError>;
+}
+
+/// Resolves placeholders asynchronously, e.g. over the network. Only the asynchronous
+/// loaders and [`SecretResolver::resolve_config`] can use it.
+#[async_trait]
+pub trait AsyncSecretProvider: Send + Sync + 'static {
+    /// Placeholder scheme handled by this provider, e.g. `vault` for `${vault:path#key}`.
+    fn scheme(&self) -> &str;
+
+    async fn resolve(&self, reference: &str) -> Result<String, SecretError>;
+}
+
+/// `${env:NAME}` - reads the environment variable `NAME`.
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretProvider;
+
+impl SecretProvider for EnvSecretProvider {
+    fn scheme(&self) -> &str {
+        "env"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String, SecretError> {
+        std::env::var(reference).map_err(|_| SecretError::NotFound {
+            reference: reference.to_string(),
+        })
+    }
+}
+
+/// `${file:/run/secrets/db}` - reads a file, without its trailing newline.
+#[derive(Debug, Clone, Default)]
+pub struct FileSecretProvider;
+
+impl SecretProvider for FileSecretProvider {
+    fn scheme(&self) -> &str {
+        "file"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String, SecretError> {
+        let path = PathBuf::from(reference);
+        if !path.is_file() {
+            return Err(SecretError::NotFound {
+                reference: reference.to_string(),
+            });
+        }
+
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| SecretError::provider(reference, e))?;
+        Ok(content.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// `${vault:path#key}` - reads `key` from a HashiCorp Vault KV secret.
+///
+/// Both KV v1 and KV v2 responses are understood, so `path` is the full API path
+/// after `/v1/`, e.g. `secret/data/oms#db_password` for a KV v2 mount.
+pub struct VaultSecretProvider {
+    addr: String,
+    token: Secret<String>,
+    client: ClientWithMiddleware,
+}
+
+impl VaultSecretProvider {
+    pub fn new(addr: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into().trim_end_matches('/').to_string(),
+            token: Secret::new(token.into()),
+            client: http::default_client(),
+        }
+    }
+
+    /// Use a client built from `settings`, e.g. to trust Vault's CA.
+    pub fn with_settings(mut self, settings: &HttpClientSettings) -> Result<Self, HttpClientError> {
+        self.client = settings.client()?;
+        Ok(self)
+    }
+
+    /// Provider configured from `VAULT_ADDR` and `VAULT_TOKEN`, if both are set.
+    pub fn from_env() -> Option<Self> {
+        let addr = std::env::var("VAULT_ADDR").ok()?;
+        let token = std::env::var("VAULT_TOKEN").ok()?;
+        Some(Self::new(addr, token))
+    }
+}
+
+impl fmt::Debug for VaultSecretProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VaultSecretProvider")
+            .field("addr", &self.addr)
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl AsyncSecretProvider for VaultSecretProvider {
+    fn scheme(&self) -> &str {
+        "vault"
+    }
+
+    async fn resolve(&self, reference: &str) -> Result<String, SecretError> {
+        let (path, key) = reference
+            .split_once('#')
+            .ok_or_else(|| SecretError::provider(reference, "expected 'path#key'"))?;
+
+        let response = self
+            .client
+            .get(format!("{}/v1/{}", self.addr, path.trim_start_matches('/')))
+            .header("X-Vault-Token", self.token.expose())
+            .send()
+            .await
+            .map_err(|e| SecretError::provider(reference, e))?;
+
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(SecretError::NotFound {
+                reference: reference.to_string(),
+            });
+        }
+        if !status.is_success() {
+            return Err(SecretError::provider(
+                reference,
+                format!("vault responded with {}", status),
+            ));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SecretError::provider(reference, e))?;
+        let mut document = FileFormat::Json
+            .parse(None, &body)
+            .map_err(|e| SecretError::provider(reference, e))?;
+
+        let not_found = || SecretError::NotFound {
+            reference: reference.to_string(),
+        };
+        let mut data = document
+            .remove("data")
+            .and_then(|data| data.into_table().ok())
+            .ok_or_else(not_found)?;
+        // KV v2 nests the secret under data.data
+        if let Some(ValueKind::Table(inner)) = data.get("data").map(|v| &v.kind) {
+            data = inner.clone();
+        }
+
+        data.remove(key)
+            .ok_or_else(not_found)?
+            .into_string()
+            .map_err(|e| SecretError::provider(reference, e))
+    }
+}
+
+/// Replaces `${scheme:reference}` placeholders in configuration strings.
+///
+/// `env` and `file` providers are registered by default. Placeholders may make up
+/// the whole value or be embedded in it, e.g. `mssql://oms:${env:DB_PASS}@db:1433`.
+///
+/// The synchronous loaders resolve with the `*_blocking` methods, which reject the
+/// placeholders of [`AsyncSecretProvider`]s such as the [`VaultSecretProvider`].
+#[derive(Clone)]
+pub struct SecretResolver {
+    providers: HashMap<String, Provider>,
+}
+
+#[derive(Clone)]
+enum Provider {
+    Sync(Arc<dyn SecretProvider>),
+    Async(Arc<dyn AsyncSecretProvider>),
+}
+
+/// A piece of a string holding placeholders.
+enum Part<'a> {
+    Text(&'a str),
+    Secret { scheme: &'a str, reference: &'a str },
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        Self::empty()
+            .with_provider(EnvSecretProvider)
+            .with_provider(FileSecretProvider)
+    }
+}
+
+impl fmt::Debug for SecretResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretResolver")
+            .field("schemes", &self.providers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SecretResolver {
+    /// Resolver with the `env` and `file` providers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolver without any provider.
+    pub fn empty() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Register `provider`, replacing any provider for the same scheme.
+    pub fn with_provider(mut self, provider: impl SecretProvider) -> Self {
+        self.providers.insert(
+            provider.scheme().to_string(),
+            Provider::Sync(Arc::new(provider)),
+        );
+        self
+    }
+
+    /// Register an asynchronous `provider`, replacing any provider for the same scheme.
+    pub fn with_async_provider(mut self, provider: impl AsyncSecretProvider) -> Self {
+        self.providers.insert(
+            provider.scheme().to_string(),
+            Provider::Async(Arc::new(provider)),
+        );
+        self
+    }
+
+    /// Resolve every placeholder in `text`.
+    ///
+    /// `${...}` without a scheme is left untouched.
+    pub async fn resolve_str(&self, text: &str) -> Result<String, SecretError> {
+        let mut out = String::with_capacity(text.len());
+        for part in parts(text)? {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Secret { scheme, reference } => match self.provider(scheme)? {
+                    Provider::Sync(provider) => out.push_str(&provider.resolve(reference)?),
+                    Provider::Async(provider) => out.push_str(&provider.resolve(reference).await?),
+                },
+            }
+        }
+        Ok(out)
+    }
+
+    /// [`Self::resolve_str`] without waiting: placeholders of asynchronous providers are
+    /// an error.
+    pub fn resolve_str_blocking(&self, text: &str) -> Result<String, SecretError> {
+        let mut out = String::with_capacity(text.len());
+        for part in parts(text)? {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Secret { scheme, reference } => match self.provider(scheme)? {
+                    Provider::Sync(provider) => out.push_str(&provider.resolve(reference)?),
+                    Provider::Async(_) => {
+                        return Err(SecretError::AsyncProvider {
+                            scheme: scheme.to_string(),
+                        });
+                    }
+                },
+            }
+        }
+        Ok(out)
+    }
+
+    fn provider(&self, scheme: &str) -> Result<&Provider, SecretError> {
+        self.providers
+            .get(scheme)
+            .ok_or_else(|| SecretError::UnknownProvider {
+                scheme: scheme.to_string(),
+            })
+    }
+
+    /// Resolve every placeholder in `value` and its children.
+    pub fn resolve_value<'a>(
+        &'a self,
+        value: &'a mut Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), SecretError>> + Send + 'a>> {
+        Box::pin(async move {
+            match &mut value.kind {
+                ValueKind::String(text) if text.contains("${") => {
+                    *text = self.resolve_str(text).await?;
+                }
+                ValueKind::Table(table) => {
+                    for child in table.values_mut() {
+                        self.resolve_value(child).await?;
+                    }
+                }
+                ValueKind::Array(array) => {
+                    for child in array.iter_mut() {
+                        self.resolve_value(child).await?;
+                    }
+                }
+                _ => {}
+            }
+            Ok(())
+        })
+    }
+
+    /// [`Self::resolve_value`] without waiting, see [`Self::resolve_str_blocking`].
+    pub fn resolve_value_blocking(&self, value: &mut Value) -> Result<(), SecretError> {
+        match &mut value.kind {
+            ValueKind::String(text) if text.contains("${") => {
+                *text = self.resolve_str_blocking(text)?;
+            }
+            ValueKind::Table(table) => {
+                for child in table.values_mut() {
+                    self.resolve_value_blocking(child)?;
+                }
+            }
+            ValueKind::Array(array) => {
+                for child in array.iter_mut() {
+                    self.resolve_value_blocking(child)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Resolve every placeholder in a built configuration.
+    pub async fn resolve_config(&self, mut config: Config) -> Result<Config, ConfigError> {
+        self.resolve_value(&mut config.cache).await?;
+        Ok(config)
+    }
+
+    /// [`Self::resolve_config`] without waiting, see [`Self::resolve_str_blocking`].
+    pub fn resolve_config_blocking(&self, mut config: Config) -> Result<Config, ConfigError> {
+        self.resolve_value_blocking(&mut config.cache)?;
+        Ok(config)
+    }
+}
+
+/// Split `text` into literal text and placeholders with a scheme.
+fn parts(text: &str) -> Result<Vec<Part<'_>>, SecretError> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        parts.push(Part::Text(&rest[..start]));
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or_else(|| SecretError::Unterminated {
+            value: text.to_string(),
+        })?;
+        parts.push(match after[..end].split_once(':') {
+            Some((scheme, reference)) => Part::Secret { scheme, reference },
+            None => Part::Text(&rest[start..start + end + 3]),
+        });
+        rest = &after[end + 1..];
+    }
+
+    parts.push(Part::Text(rest));
+    Ok(parts)
+}
@@ -0,0 +1,96 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_config::BaseAppConfig, calendar::TradingCalendar, database::DatabaseConfig,
+    error::MissingSection, features::FeatureFlags, http::HttpClientSettings, kafka::KafkaConfig,
+    logging::LoggerConfig, redis::RedisConfig, validate::Validate,
+};
+
+/// Root configuration of a service: the standard sections, and the service's own
+/// settings `Ext` flattened next to them.
+///
+/// ```toml
+/// max_open_orders = 500
+///
+/// [app]
+/// name = "oms"
+///
+/// [redis]
+/// mode = "single"
+/// host = "cache.internal"
+/// ```
+///
+/// Only `app` is required, `logger`, `http` and `features` fall back to their defaults.
+/// Read the other sections through their accessors, which fail with [`MissingSection`]
+/// when they aren't configured.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[non_exhaustive]
+pub struct ServiceConfig<Ext> {
+    #[validate(nested)]
+    pub app: BaseAppConfig,
+    #[serde(default)]
+    #[validate(nested)]
+    pub logger: LoggerConfig,
+    /// Settings of the service's HTTP clients.
+    #[serde(default)]
+    #[validate(nested)]
+    pub http: HttpClientSettings,
+    #[validate(nested)]
+    pub redis: Option<RedisConfig>,
+    #[validate(nested)]
+    pub kafka: Option<KafkaConfig>,
+    #[validate(nested)]
+    pub database: Option<DatabaseConfig>,
+    #[validate(nested)]
+    pub calendar: Option<TradingCalendar>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub features: FeatureFlags,
+    /// Settings specific to the service.
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub ext: Ext,
+}
+
+impl<Ext> ServiceConfig<Ext> {
+    /// The `redis` section.
+    pub fn redis(&self) -> Result<&RedisConfig, MissingSection> {
+        self.redis
+            .as_ref()
+            .ok_or(MissingSection { section: "redis" })
+    }
+
+    /// The `kafka` section.
+    pub fn kafka(&self) -> Result<&KafkaConfig, MissingSection> {
+        self.kafka
+            .as_ref()
+            .ok_or(MissingSection { section: "kafka" })
+    }
+
+    /// Whether the feature flag `name` is fully on in `app.env`, see [`FeatureFlags::is_enabled`].
+    pub fn feature_enabled(&self, name: &str) -> bool {
+        self.features.is_enabled(name, self.app.env.as_ref())
+    }
+
+    /// Whether the feature flag `name` is on in `app.env` for `key`, see
+    /// [`FeatureFlags::is_enabled_for`].
+    pub fn feature_enabled_for(&self, name: &str, key: &str) -> bool {
+        self.features
+            .is_enabled_for(name, self.app.env.as_ref(), key)
+    }
+
+    /// The `database` section.
+    pub fn database(&self) -> Result<&DatabaseConfig, MissingSection> {
+        self.database.as_ref().ok_or(MissingSection {
+            section: "database",
+        })
+    }
+
+    /// The `calendar` section.
+    pub fn calendar(&self) -> Result<&TradingCalendar, MissingSection> {
+        self.calendar.as_ref().ok_or(MissingSection {
+            section: "calendar",
+        })
+    }
+}
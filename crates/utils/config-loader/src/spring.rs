@@ -0,0 +1,147 @@
+use async_trait::async_trait;
+use config::{AsyncSource, ConfigError, FileFormat, Format, Map, Value, ValueKind};
+use std::fmt::Debug;
+
+use crate::{
+    HttpSource, SourceStatus, app_config::BaseAppConfig, keys::expand, remote::RemoteConfig,
+};
+
+/// Parses a Spring Cloud Config Server environment response.
+///
+/// `propertySources` are listed highest precedence first; they are merged so that
+/// earlier sources win, with dotted keys (`database.host`, `kafka.servers[0]`)
+/// expanded into nested tables. As in Spring, a list replaces the whole list of lower
+/// precedence sources rather than merging with it element by element.
+#[derive(Debug, Clone, Default)]
+pub struct SpringCloudFormat;
+
+impl Format for SpringCloudFormat {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut document = FileFormat::Json.parse(uri, text)?;
+
+        let sources = match document.remove("propertySources").map(|v| v.kind) {
+            Some(ValueKind::Array(sources)) => sources,
+            Some(ValueKind::Nil) | None => Vec::new(),
+            Some(_) => return Err("'propertySources' must be an array".into()),
+        };
+
+        let mut flat: Vec<(String, Value)> = Vec::new();
+        for property_source in sources.into_iter().rev() {
+            let mut property_source = property_source.into_table()?;
+            if let Some(source) = property_source.remove("source") {
+                let source = source.into_table()?;
+                let lists: Vec<String> = source
+                    .keys()
+                    .filter_map(|key| key.find('[').map(|i| key[..i].to_string()))
+                    .collect();
+                flat.retain(|(key, _)| {
+                    !lists.iter().any(|list| {
+                        key.strip_prefix(list.as_str())
+                            .is_some_and(|rest| rest.is_empty() || rest.starts_with('['))
+                    })
+                });
+                flat.extend(source);
+            }
+        }
+
+        Ok(expand(flat)?)
+    }
+}
+
+/// Configuration from a Spring Cloud Config Server, fetched from
+/// `{url}/{application}/{profile}[/{label}]`.
+///
+/// The profile is used as written, e.g. `prod` fetches `oms-prod.yml`.
+///
+/// Auth, retries, ETag revalidation and the on-disk fallback are those of the
+/// underlying [`HttpSource`], see [`Self::with_http`].
+#[derive(Debug, Clone)]
+pub struct SpringCloudConfigSource {
+    url: String,
+    application: String,
+    profile: String,
+    label: Option<String>,
+    http: HttpSource<SpringCloudFormat>,
+}
+
+impl SpringCloudConfigSource {
+    pub fn new(
+        url: impl Into<String>,
+        application: impl Into<String>,
+        profile: impl Into<String>,
+    ) -> Self {
+        let mut source = Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            application: application.into(),
+            profile: profile.into(),
+            label: None,
+            http: HttpSource::new(String::new(), SpringCloudFormat),
+        };
+        source.update_uri();
+        source
+    }
+
+    /// Source for the server in `remote`, the application named in `app` and its
+    /// environment as configured, or the `default` profile when no environment is set.
+    pub fn from_config(remote: &RemoteConfig, app: &BaseAppConfig) -> Self {
+        let profile = app.env_name().unwrap_or("default");
+        Self::new(&remote.config.url, &app.name, profile)
+    }
+
+    /// Fetch several profiles at once, e.g. `["prod", "eu"]`. Later profiles win.
+    pub fn profiles<I, S>(mut self, profiles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.profile = profiles
+            .into_iter()
+            .map(|p| p.as_ref().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.update_uri();
+        self
+    }
+
+    /// Git branch, tag or commit to read from.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self.update_uri();
+        self
+    }
+
+    /// Configure the underlying HTTP source.
+    pub fn with_http<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(HttpSource<SpringCloudFormat>) -> HttpSource<SpringCloudFormat>,
+    {
+        self.http = f(self.http);
+        self
+    }
+
+    /// Outcome of the latest fetch.
+    pub fn status(&self) -> SourceStatus {
+        self.http.status()
+    }
+
+    fn update_uri(&mut self) {
+        let mut uri = format!("{}/{}/{}", self.url, self.application, self.profile);
+        if let Some(label) = &self.label {
+            // the server expects slashes in labels as "(_)"
+            uri.push('/');
+            uri.push_str(&label.replace('/', "(_)"));
+        }
+        self.http.set_uri(uri);
+    }
+}
+
+#[async_trait]
+impl AsyncSource for SpringCloudConfigSource {
+    async fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        self.http.collect().await
+    }
+}
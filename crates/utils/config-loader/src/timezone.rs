@@ -0,0 +1,172 @@
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::{borrow::Cow, fmt, str::FromStr};
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use time_tz::{
+    Offset, OffsetDateTimeExt, OffsetResult, PrimitiveDateTimeExt, TimeZone, Tz, timezones,
+};
+
+/// A timezone: an IANA name resolved through the bundled tz database, or a fixed offset.
+///
+/// Accepts `America/New_York`, `UTC`, whole hours (`7`, `-3`) and `+05:30`-style offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Timezone {
+    Fixed(UtcOffset),
+    Named(&'static Tz),
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::Fixed(UtcOffset::UTC)
+    }
+}
+
+impl Timezone {
+    /// Offset from UTC at the instant `at`, which changes with DST for named zones.
+    pub fn offset_at(&self, at: OffsetDateTime) -> UtcOffset {
+        self.to_local(at).offset()
+    }
+
+    /// `at` in this timezone.
+    pub fn to_local(&self, at: OffsetDateTime) -> OffsetDateTime {
+        match self {
+            Self::Fixed(offset) => at.to_offset(*offset),
+            Self::Named(tz) => at.to_timezone(*tz),
+        }
+    }
+
+    /// The instant of the local wall-clock time `local`.
+    ///
+    /// Times repeated when clocks go back resolve to the first occurrence. Times skipped
+    /// when they go forward are moved later by the length of the jump, so `02:30` on a
+    /// spring-forward night is `03:30` in New York and Berlin alike.
+    pub fn from_local(&self, local: PrimitiveDateTime) -> OffsetDateTime {
+        match self {
+            Self::Fixed(offset) => local.assume_offset(*offset),
+            Self::Named(tz) => match local.assume_timezone(*tz) {
+                OffsetResult::Some(at) | OffsetResult::Ambiguous(at, _) => at,
+                OffsetResult::None => {
+                    // in the gap: the offset from before the jump moves it past the gap,
+                    // a day earlier is before the jump whichever side of UTC the zone is
+                    let before = tz
+                        .get_offset_utc(&(local - Duration::DAY).assume_utc())
+                        .to_utc();
+                    local.assume_offset(before).to_timezone(*tz)
+                }
+            },
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("utc") || s == "Z" {
+            return Ok(Self::Fixed(UtcOffset::UTC));
+        }
+        if let Some(tz) = timezones::get_by_name(s) {
+            return Ok(Self::Named(tz));
+        }
+        parse_offset(s).map(Self::Fixed).ok_or_else(|| {
+            format!(
+                "unknown timezone '{}', expected an IANA name or an offset such as +05:30",
+                s
+            )
+        })
+    }
+}
+
+/// `7`, `-3`, `+0530` or `+05:30`.
+fn parse_offset(s: &str) -> Option<UtcOffset> {
+    let (sign, digits) = match s.as_bytes().first()? {
+        b'-' => (-1, &s[1..]),
+        b'+' => (1, &s[1..]),
+        _ => (1, s),
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    if hours.is_empty() || !(hours.len() <= 2 && minutes.len() <= 2) {
+        return None;
+    }
+    let hours: i8 = hours.parse().ok()?;
+    let minutes: i8 = minutes.parse().ok()?;
+    if !(0..60).contains(&minutes) || hours > 14 || (hours == 14 && minutes > 0) {
+        return None;
+    }
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(offset) if offset.is_utc() => f.write_str("UTC"),
+            Self::Fixed(offset) => {
+                let (hours, minutes, _) = offset.as_hms();
+                let sign = if offset.is_negative() { '-' } else { '+' };
+                write!(f, "{}{:02}:{:02}", sign, hours.abs(), minutes.abs())
+            }
+            Self::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+impl Serialize for Timezone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timezone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TimezoneVisitor;
+
+        impl de::Visitor<'_> for TimezoneVisitor {
+            type Value = Timezone;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an IANA timezone name, or a UTC offset in hours or as +05:30")
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Timezone, E> {
+                i8::try_from(v)
+                    .ok()
+                    .filter(|hours| (-14..=14).contains(hours))
+                    .and_then(|hours| UtcOffset::from_hms(hours, 0, 0).ok())
+                    .map(Timezone::Fixed)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Signed(v), &self))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Timezone, E> {
+                match i64::try_from(v) {
+                    Ok(v) => self.visit_i64(v),
+                    Err(_) => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Timezone, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(TimezoneVisitor)
+    }
+}
+
+impl JsonSchema for Timezone {
+    fn schema_name() -> Cow<'static, str> {
+        "Timezone".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "IANA timezone name, e.g. America/New_York, or a UTC offset in hours or as +05:30.",
+            "type": ["string", "integer"]
+        })
+    }
+}
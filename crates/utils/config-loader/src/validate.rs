@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, HashMap};
+
+pub use crate::error::{ValidationErrors, Violation};
+pub use config_loader_derive::Validate;
+
+/// Checks a loaded configuration beyond what deserialization guarantees.
+///
+/// Usually derived, see [`macro@Validate`]. Every loader validates the value it returns.
+pub trait Validate {
+    /// Check `self`, found at the dotted key `path`, and record every violation in `errors`.
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors);
+
+    /// Check `self` as the configuration root.
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        self.validate_at("", &mut errors);
+        errors.into_result()
+    }
+
+    /// Record the dotted keys below `path` whose values must not be logged: the
+    /// [`Secret`](crate::secret::Secret) fields and those marked `#[validate(redact)]`.
+    ///
+    /// `*` stands for any list index or map key, e.g. `accounts[*].password`.
+    fn redacted_keys(path: &str, keys: &mut Vec<String>)
+    where
+        Self: Sized,
+    {
+        let _ = (path, keys);
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        if let Some(value) = self {
+            value.validate_at(path, errors);
+        }
+    }
+
+    fn redacted_keys(path: &str, keys: &mut Vec<String>) {
+        T::redacted_keys(path, keys);
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        for (i, value) in self.iter().enumerate() {
+            value.validate_at(&format!("{}[{}]", path, i), errors);
+        }
+    }
+
+    fn redacted_keys(path: &str, keys: &mut Vec<String>) {
+        T::redacted_keys(&format!("{}[*]", path), keys);
+    }
+}
+
+impl<T: Validate> Validate for HashMap<String, T> {
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        for (key, value) in self {
+            value.validate_at(&join_key(path, key), errors);
+        }
+    }
+
+    fn redacted_keys(path: &str, keys: &mut Vec<String>) {
+        T::redacted_keys(&join_key(path, "*"), keys);
+    }
+}
+
+impl<T: Validate> Validate for BTreeMap<String, T> {
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        for (key, value) in self {
+            value.validate_at(&join_key(path, key), errors);
+        }
+    }
+
+    fn redacted_keys(path: &str, keys: &mut Vec<String>) {
+        T::redacted_keys(&join_key(path, "*"), keys);
+    }
+}
+
+/// `path.field`, or just `field` at the root.
+pub fn join_key(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+/// Rules available to `#[validate(...)]`.
+pub mod rules {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        fmt::Display,
+    };
+
+    use crate::secret::Secret;
+
+    pub fn range<T: PartialOrd + Display>(
+        value: &T,
+        min: Option<T>,
+        max: Option<T>,
+    ) -> Result<(), String> {
+        match (min, max) {
+            (Some(min), Some(max)) if *value < min || *value > max => Err(format!(
+                "must be between {} and {}, got {}",
+                min, max, value
+            )),
+            (Some(min), None) if *value < min => {
+                Err(format!("must be at least {}, got {}", min, value))
+            }
+            (None, Some(max)) if *value > max => {
+                Err(format!("must be at most {}, got {}", max, value))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Values that can be empty.
+    pub trait IsEmpty {
+        fn is_empty_value(&self) -> bool;
+    }
+
+    impl IsEmpty for str {
+        fn is_empty_value(&self) -> bool {
+            self.trim().is_empty()
+        }
+    }
+
+    impl IsEmpty for String {
+        fn is_empty_value(&self) -> bool {
+            self.as_str().is_empty_value()
+        }
+    }
+
+    impl<T> IsEmpty for Vec<T> {
+        fn is_empty_value(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    impl<K, V> IsEmpty for HashMap<K, V> {
+        fn is_empty_value(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    impl<K, V> IsEmpty for BTreeMap<K, V> {
+        fn is_empty_value(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    impl<T: IsEmpty> IsEmpty for Secret<T> {
+        fn is_empty_value(&self) -> bool {
+            self.expose().is_empty_value()
+        }
+    }
+
+    pub fn non_empty<T: IsEmpty + ?Sized>(value: &T) -> Result<(), String> {
+        if value.is_empty_value() {
+            Err("must not be empty".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// `scheme://host[:port][/path]`
+    pub fn url(value: &str) -> Result<(), String> {
+        let invalid = || Err(format!("must be a URL like scheme://host, got '{}'", value));
+
+        let Some((scheme, rest)) = value.split_once("://") else {
+            return invalid();
+        };
+        let scheme_ok = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+
+        if !scheme_ok || host.is_empty() || host.contains(char::is_whitespace) {
+            return invalid();
+        }
+        Ok(())
+    }
+
+    /// Values made of `host:port` entries.
+    pub trait HostPorts {
+        fn host_ports(&self) -> Vec<&str>;
+    }
+
+    impl HostPorts for str {
+        /// Comma-separated entries.
+        fn host_ports(&self) -> Vec<&str> {
+            if self.trim().is_empty() {
+                return Vec::new();
+            }
+            self.split(',').map(str::trim).collect()
+        }
+    }
+
+    impl HostPorts for String {
+        fn host_ports(&self) -> Vec<&str> {
+            self.as_str().host_ports()
+        }
+    }
+
+    impl HostPorts for Vec<String> {
+        fn host_ports(&self) -> Vec<&str> {
+            self.iter().map(|entry| entry.trim()).collect()
+        }
+    }
+
+    /// `host:port` entries, e.g. Kafka bootstrap servers, as a list or comma-separated.
+    pub fn host_port<T: HostPorts + ?Sized>(value: &T) -> Result<(), String> {
+        let entries = value.host_ports();
+        if entries.is_empty() {
+            return Err("must not be empty".to_string());
+        }
+
+        for entry in entries {
+            let valid = entry.rsplit_once(':').is_some_and(|(host, port)| {
+                let host = host.trim_start_matches('[').trim_end_matches(']');
+                !host.is_empty()
+                    && !host.contains(char::is_whitespace)
+                    && port.parse::<u16>().is_ok_and(|port| port > 0)
+            });
+            if !valid {
+                return Err(format!("expected host:port, got '{}'", entry));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use async_trait::async_trait;
+use config::{AsyncSource, ConfigBuilder, ConfigError, Format, Map, Value, builder::AsyncState};
+use notify::{Event, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt::Debug,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, watch},
+    time::MissedTickBehavior,
+};
+
+use crate::{
+    HttpSource, LayeredConfigLoader, SecretResolver,
+    diff::{self, ConfigDiff},
+    validate::Validate,
+};
+
+type Validator<T> = Arc<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+/// A configuration value published by a [`ConfigWatcher`].
+#[derive(Debug)]
+pub struct ConfigUpdate<T> {
+    /// Value replaced by this reload, `None` for the initial load.
+    pub previous: Option<Arc<T>>,
+    pub current: Arc<T>,
+}
+
+impl<T> Clone for ConfigUpdate<T> {
+    fn clone(&self) -> Self {
+        Self {
+            previous: self.previous.clone(),
+            current: self.current.clone(),
+        }
+    }
+}
+
+/// Receiving side of a [`ConfigWatcher`].
+///
+/// The watcher stops once every handle is dropped.
+pub struct ConfigHandle<T> {
+    rx: watch::Receiver<ConfigUpdate<T>>,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl<T> Clone for ConfigHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rx: self.rx.clone(),
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+impl<T> ConfigHandle<T> {
+    /// Latest valid configuration.
+    pub fn current(&self) -> Arc<T> {
+        self.rx.borrow().current.clone()
+    }
+
+    /// Latest update, with the value it replaced.
+    pub fn update(&self) -> ConfigUpdate<T> {
+        self.rx.borrow().clone()
+    }
+
+    /// Wait for the next accepted reload. Returns `None` once the watcher has stopped.
+    pub async fn changed(&mut self) -> Option<ConfigUpdate<T>> {
+        self.rx.changed().await.ok()?;
+        Some(self.rx.borrow_and_update().clone())
+    }
+
+    /// Why the latest reload was rejected, cleared by the next accepted one.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+}
+
+/// Reloads configuration when its files change or on a remote polling interval.
+///
+/// Files are watched through the OS (inotify on Linux), falling back to polling when
+/// native events aren't available. Every reload goes through the same steps as
+/// [`LayeredConfigLoader::load`], including its schema check; reloads that fail any of them
+/// or the extra validator are rejected and the last good configuration is kept.
+pub struct ConfigWatcher<T> {
+    loader: LayeredConfigLoader,
+    remote: Option<(SharedSource, Duration)>,
+    secrets: Option<SecretResolver>,
+    validator: Option<Validator<T>>,
+    poll_interval: Duration,
+    debounce: Duration,
+}
+
+impl<T> ConfigWatcher<T>
+where
+    T: DeserializeOwned + Validate + Send + Sync + 'static,
+{
+    pub fn new(loader: LayeredConfigLoader) -> Self {
+        Self {
+            loader,
+            remote: None,
+            secrets: None,
+            validator: None,
+            poll_interval: Duration::from_secs(2),
+            debounce: Duration::from_millis(100),
+        }
+    }
+
+    /// Layer `source` on top of the files and re-fetch it every `interval`.
+    pub fn http_source<F>(mut self, source: HttpSource<F>, interval: Duration) -> Self
+    where
+        F: Format + Send + Sync + 'static,
+    {
+        self.remote = Some((SharedSource(Arc::new(source)), interval));
+        self
+    }
+
+    /// Resolve secret placeholders with `resolver` instead of the one of the loader, see
+    /// [`LayeredConfigLoader::secrets`]. Asynchronous providers can be used.
+    pub fn secrets(mut self, resolver: SecretResolver) -> Self {
+        self.secrets = Some(resolver);
+        self
+    }
+
+    /// Reject reloads for which `validator` returns an error.
+    pub fn validate<V>(mut self, validator: V) -> Self
+    where
+        V: Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// How often files are checked when native file events aren't available.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// How long to wait for a burst of file events to settle before reloading.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Load the configuration and start watching it.
+    ///
+    /// Fails if the initial configuration can't be loaded. Must be called within a tokio runtime.
+    pub async fn start(self) -> Result<ConfigHandle<T>, ConfigError> {
+        let (mut raw, value, _) = self.load().await?;
+
+        let (events_tx, mut events) = mpsc::unbounded_channel();
+        let file_watcher = if self.loader.dir().is_dir() {
+            let handler = move |event: notify::Result<Event>| {
+                if event.is_ok_and(|e| !e.kind.is_access()) {
+                    let _ = events_tx.send(());
+                }
+            };
+            Some(
+                watch_dir(self.loader.dir(), handler, self.poll_interval)
+                    .map_err(|e| ConfigError::Foreign(Box::new(e)))?,
+            )
+        } else {
+            None
+        };
+
+        let (tx, rx) = watch::channel(ConfigUpdate {
+            previous: None,
+            current: Arc::new(value),
+        });
+        let last_error = Arc::new(Mutex::new(None));
+        let handle = ConfigHandle {
+            rx,
+            last_error: last_error.clone(),
+        };
+
+        let mut remote_poll = self.remote.as_ref().map(|(_, interval)| {
+            let mut interval = tokio::time::interval(*interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            interval.reset();
+            interval
+        });
+
+        tokio::spawn(async move {
+            // keep the file watcher alive as long as the task runs
+            let _file_watcher = file_watcher;
+
+            loop {
+                tokio::select! {
+                    Some(()) = events.recv() => {
+                        tokio::time::sleep(self.debounce).await;
+                        while events.try_recv().is_ok() {}
+                    }
+                    _ = tick(&mut remote_poll) => {}
+                    _ = tx.closed() => break,
+                }
+
+                match self.load().await {
+                    Ok((new_raw, value, redacted)) => {
+                        *last_error.lock().unwrap() = None;
+                        if new_raw == raw {
+                            continue;
+                        }
+                        let mut diff = ConfigDiff::between_values(&raw, &new_raw);
+                        diff.redact_keys(&redacted);
+                        raw = new_raw;
+                        tx.send_modify(|update| {
+                            let previous = std::mem::replace(&mut update.current, Arc::new(value));
+                            update.previous = Some(previous);
+                        });
+                        tracing::info!(
+                            added = diff.added().count(),
+                            removed = diff.removed().count(),
+                            changed = diff.changed().count(),
+                            diff = %serde_json::to_string(&diff.changes).unwrap_or_default(),
+                            "configuration reloaded"
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "configuration reload rejected, keeping last good configuration");
+                        *last_error.lock().unwrap() = Some(e.to_string());
+                    }
+                }
+            }
+        });
+
+        Ok(handle)
+    }
+
+    /// The resolved raw values, the configuration, and the keys to redact in diffs.
+    async fn load(&self) -> Result<(Value, T, Vec<String>), ConfigError> {
+        let mut config = self.loader.build()?.config;
+        if let Some((source, _)) = &self.remote {
+            config = ConfigBuilder::<AsyncState>::default()
+                .add_source(config)
+                .add_async_source(source.clone())
+                .build()
+                .await?;
+        }
+
+        let mut redacted = self.loader.redacted_keys();
+        T::redacted_keys("", &mut redacted);
+        redacted.extend(diff::templated_keys(&config.cache));
+        let config = self.loader.decrypt(config)?;
+        let resolver = self
+            .secrets
+            .as_ref()
+            .unwrap_or(self.loader.secret_resolver());
+        let config = resolver.resolve_config(config).await?;
+
+        let raw = config.cache.clone();
+        let value: T = self.loader.deserialize(config)?;
+        if let Some(validator) = &self.validator {
+            validator(&value)
+                .map_err(|e| ConfigError::Message(format!("invalid configuration: {}", e)))?;
+        }
+
+        Ok((raw, value, redacted))
+    }
+}
+
+/// Watch `dir` with native file events, or by polling if they aren't available.
+fn watch_dir<H>(
+    dir: &Path,
+    handler: H,
+    poll_interval: Duration,
+) -> notify::Result<Box<dyn Watcher + Send>>
+where
+    H: notify::EventHandler + Clone,
+{
+    let native =
+        RecommendedWatcher::new(handler.clone(), notify::Config::default()).and_then(|mut w| {
+            w.watch(dir, RecursiveMode::NonRecursive)?;
+            Ok(w)
+        });
+
+    match native {
+        Ok(watcher) => Ok(Box::new(watcher)),
+        Err(e) => {
+            tracing::warn!(error = %e, dir = %dir.display(), "native file watching unavailable, polling instead");
+            let mut watcher = PollWatcher::new(
+                handler,
+                notify::Config::default().with_poll_interval(poll_interval),
+            )?;
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            Ok(Box::new(watcher))
+        }
+    }
+}
+
+async fn tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Cheaply clonable handle to a remote source.
+#[derive(Clone)]
+struct SharedSource(Arc<dyn AsyncSource + Send + Sync>);
+
+impl Debug for SharedSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[async_trait]
+impl AsyncSource for SharedSource {
+    async fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        self.0.collect().await
+    }
+}
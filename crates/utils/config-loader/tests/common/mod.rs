@@ -0,0 +1,108 @@
+//! Helpers shared by the test binaries, each using a subset of them.
+#![allow(dead_code)]
+
+use config_loader::{ConfigError, FileFormat, LayeredConfigLoader, Validate, ValidationErrors};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt::Debug,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// Fresh, empty temporary directory for the configuration files of `test`.
+pub fn config_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("config-loader-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Load `T` from TOML defaults alone.
+pub fn load<T: DeserializeOwned + Validate>(text: &str) -> Result<T, ConfigError> {
+    LayeredConfigLoader::new("does-not-exist")
+        .defaults(text, FileFormat::Toml)
+        .load()
+}
+
+/// The violations a load failed with.
+pub fn validation_errors<T: Debug>(result: Result<T, ConfigError>) -> ValidationErrors {
+    match result {
+        Err(ConfigError::Foreign(e)) => *e.downcast::<ValidationErrors>().unwrap(),
+        other => panic!("expected validation to fail, got {:?}", other),
+    }
+}
+
+/// The keys a load failed validation on, sorted.
+pub fn violated_keys<T: Debug>(result: Result<T, ConfigError>) -> Vec<String> {
+    let mut keys: Vec<_> = validation_errors(result)
+        .violations
+        .into_iter()
+        .map(|v| v.key)
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Every violation as `key: message`, sorted.
+pub fn violation_messages(errors: &ValidationErrors) -> Vec<String> {
+    let mut violations: Vec<_> = errors.violations.iter().map(|v| v.to_string()).collect();
+    violations.sort();
+    violations
+}
+
+/// `(status, headers, body)`
+pub type MockResponse = (u16, Vec<(&'static str, &'static str)>, &'static str);
+
+/// Minimal HTTP server answering each connection with the next canned response.
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    /// Serve `responses` in order, one per connection.
+    pub async fn start(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            for (status, headers, body) in responses {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = vec![0u8; 8192];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+
+                let mut response = format!(
+                    "HTTP/1.1 {} Mock\r\ncontent-length: {}\r\nconnection: close\r\n",
+                    status,
+                    body.len()
+                );
+                for (name, value) in headers {
+                    response.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                response.push_str("\r\n");
+                response.push_str(body);
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Raw requests received so far, lowercased.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
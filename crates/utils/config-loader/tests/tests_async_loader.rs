@@ -0,0 +1,217 @@
+use config::{AsyncSource, Map, Value};
+use config_loader::{
+    AsyncConfigLoader, ConfigError, ConfigSource, FileFormat, HttpSource, SourceError,
+    SourceOutcome, Validate,
+};
+use serde::Deserialize;
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+use tokio::net::TcpListener;
+
+mod common;
+use common::MockServer;
+
+#[derive(Debug, Deserialize, Validate)]
+struct Settings {
+    name: String,
+    max_order_qty: u32,
+    venue: String,
+}
+
+/// Server accepting connections and never answering.
+async fn hanging_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn test_sources_merge_by_priority() {
+    let path = std::env::temp_dir().join(format!("async-loader-{}.toml", std::process::id()));
+    fs::write(
+        &path,
+        "name = \"oms\"\nmax_order_qty = 10\nvenue = \"XNAS\"\n",
+    )
+    .unwrap();
+    let org = MockServer::start(vec![(
+        200,
+        vec![],
+        r#"{"max_order_qty": 100, "venue": "XNYS"}"#,
+    )])
+    .await;
+    let oms = MockServer::start(vec![(200, vec![], r#"{"max_order_qty": 500}"#)]).await;
+
+    // added out of order: priorities decide
+    let (settings, report) = AsyncConfigLoader::new()
+        .source(
+            ConfigSource::remote("oms", HttpSource::new(&oms.url, FileFormat::Json)).priority(20),
+        )
+        .source(ConfigSource::file(&path))
+        .source(
+            ConfigSource::remote("org", HttpSource::new(&org.url, FileFormat::Json)).priority(10),
+        )
+        .load::<Settings>()
+        .await
+        .unwrap();
+
+    assert_eq!(settings.name, "oms");
+    assert_eq!(settings.max_order_qty, 500);
+    assert_eq!(settings.venue, "XNYS");
+
+    let file = path.display().to_string();
+    assert_eq!(report.contributors(), [file.as_str(), "org", "oms"]);
+    assert_eq!(report.source_of("name"), Some(file.as_str()));
+    assert_eq!(report.source_of("venue"), Some("org"));
+    assert_eq!(report.source_of("max_order_qty"), Some("oms"));
+    assert_eq!(report.sources[2].outcome, SourceOutcome::Loaded { keys: 1 });
+    assert_eq!(org.requests().len(), 1);
+    assert_eq!(oms.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_optional_sources_are_skipped_and_required_ones_fail() {
+    let org = MockServer::start(vec![
+        (
+            200,
+            vec![],
+            r#"{"name": "oms", "max_order_qty": 100, "venue": "XNYS"}"#,
+        ),
+        (
+            200,
+            vec![],
+            r#"{"name": "oms", "max_order_qty": 100, "venue": "XNYS"}"#,
+        ),
+    ])
+    .await;
+    let missing =
+        MockServer::start(vec![(404, vec![], "not found"), (404, vec![], "not found")]).await;
+
+    let (settings, report) = AsyncConfigLoader::new()
+        .source(ConfigSource::remote(
+            "org",
+            HttpSource::new(&org.url, FileFormat::Json),
+        ))
+        .source(
+            ConfigSource::remote("oms", HttpSource::new(&missing.url, FileFormat::Json))
+                .priority(10)
+                .optional(),
+        )
+        .load::<Settings>()
+        .await
+        .unwrap();
+    assert_eq!(settings.max_order_qty, 100);
+    assert_eq!(report.contributors(), ["org"]);
+    assert!(matches!(
+        &report.sources[1].outcome,
+        SourceOutcome::Skipped { reason } if reason.contains("404")
+    ));
+
+    let Err(ConfigError::Foreign(e)) = AsyncConfigLoader::new()
+        .source(ConfigSource::remote(
+            "org",
+            HttpSource::new(&org.url, FileFormat::Json),
+        ))
+        .source(ConfigSource::remote(
+            "oms",
+            HttpSource::new(&missing.url, FileFormat::Json),
+        ))
+        .build()
+        .await
+    else {
+        panic!("expected the required source to fail the load");
+    };
+    let error = e.downcast_ref::<SourceError>().unwrap();
+    assert_eq!(error.name, "oms");
+}
+
+#[tokio::test]
+async fn test_panicking_optional_source_is_skipped() {
+    #[derive(Debug)]
+    struct Panicking;
+
+    #[async_trait::async_trait]
+    impl AsyncSource for Panicking {
+        async fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+            panic!("broken source");
+        }
+    }
+
+    let org = MockServer::start(vec![(
+        200,
+        vec![],
+        r#"{"name": "oms", "max_order_qty": 100, "venue": "XNYS"}"#,
+    )])
+    .await;
+    let (settings, report) = AsyncConfigLoader::new()
+        .source(ConfigSource::remote(
+            "org",
+            HttpSource::new(&org.url, FileFormat::Json),
+        ))
+        .source(ConfigSource::remote("broken", Panicking).optional())
+        .load::<Settings>()
+        .await
+        .unwrap();
+    assert_eq!(settings.max_order_qty, 100);
+    assert_eq!(org.requests().len(), 1);
+    assert!(matches!(
+        &report.sources[1].outcome,
+        SourceOutcome::Skipped { reason } if reason.contains("panicked")
+    ));
+}
+
+#[tokio::test]
+async fn test_fetches_share_one_timeout() {
+    let org = MockServer::start(vec![(
+        200,
+        vec![],
+        r#"{"name": "oms", "max_order_qty": 1, "venue": "XNYS"}"#,
+    )])
+    .await;
+    let slow = hanging_server().await;
+    let slower = hanging_server().await;
+
+    let started = Instant::now();
+    let (_, report) = AsyncConfigLoader::new()
+        .source(ConfigSource::remote(
+            "org",
+            HttpSource::new(&org.url, FileFormat::Json),
+        ))
+        .source(ConfigSource::remote("slow", HttpSource::new(&slow, FileFormat::Json)).optional())
+        .source(
+            ConfigSource::remote("slower", HttpSource::new(&slower, FileFormat::Json)).optional(),
+        )
+        .timeout(Duration::from_millis(300))
+        .build()
+        .await
+        .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(report.contributors(), ["org"]);
+    assert!(matches!(
+        &report.sources[2].outcome,
+        SourceOutcome::Skipped { reason } if reason.starts_with("timed out")
+    ));
+
+    let Err(ConfigError::Foreign(e)) = AsyncConfigLoader::new()
+        .source(ConfigSource::remote(
+            "slow",
+            HttpSource::new(&slow, FileFormat::Json),
+        ))
+        .timeout(Duration::from_millis(100))
+        .build()
+        .await
+    else {
+        panic!("expected the timeout to fail the load");
+    };
+    assert_eq!(
+        e.to_string(),
+        "required configuration source 'slow' failed: timed out after 100ms"
+    );
+}
@@ -0,0 +1,154 @@
+use config_loader::{
+    ConfigError, FileFormat, LayeredConfigLoader, ServiceConfig, Timezone, Validate,
+};
+use serde::Deserialize;
+use time::{
+    UtcOffset,
+    macros::{datetime, offset},
+};
+
+mod common;
+use common::validation_errors;
+
+#[derive(Debug, Deserialize, Validate)]
+struct Orders {
+    max_open_orders: u32,
+}
+
+const CONFIG: &str = r#"
+max_open_orders = 500
+
+[app]
+name = "oms"
+timezone = "Asia/Kolkata"
+
+[calendar]
+timezone = "America/New_York"
+sessions = [
+    { name = "pre", open = "04:00", close = "09:30" },
+    { name = "regular", open = "09:30", close = "16:00" },
+]
+holidays = "2026-11-26, 2026-12-25"
+half_days = [{ date = "2026-11-27", close = "13:00" }]
+"#;
+
+fn load(overrides: &[(&str, &str)]) -> Result<ServiceConfig<Orders>, ConfigError> {
+    let mut loader = LayeredConfigLoader::new("does-not-exist").defaults(CONFIG, FileFormat::Toml);
+    for (key, value) in overrides {
+        loader = loader.set(*key, *value);
+    }
+    loader.load()
+}
+
+#[test]
+fn test_timezone_accepts_names_and_offsets() {
+    let zone: Timezone = "America/New_York".parse().unwrap();
+    assert_eq!(zone.to_string(), "America/New_York");
+    assert_eq!(
+        "+05:30".parse::<Timezone>().unwrap(),
+        Timezone::Fixed(offset!(+5:30))
+    );
+    assert_eq!(
+        "-0330".parse::<Timezone>().unwrap(),
+        Timezone::Fixed(offset!(-3:30))
+    );
+    assert_eq!(
+        "7".parse::<Timezone>().unwrap(),
+        Timezone::Fixed(offset!(+7))
+    );
+    assert_eq!("utc".parse::<Timezone>().unwrap(), Timezone::default());
+    assert!("Mars/Olympus_Mons".parse::<Timezone>().is_err());
+    assert!("+15:00".parse::<Timezone>().is_err());
+    for hours in ["-14", "-13", "14"] {
+        assert_eq!(
+            serde_json::from_str::<Timezone>(hours).unwrap(),
+            hours.parse::<Timezone>().unwrap()
+        );
+    }
+    assert!(serde_json::from_str::<Timezone>("-15").is_err());
+
+    let config = load(&[]).unwrap();
+    assert_eq!(config.ext.max_open_orders, 500);
+    assert_eq!(config.app.timezone.unwrap().to_string(), "Asia/Kolkata");
+    let config = load(&[("app.timezone", "-3")]).unwrap();
+    assert_eq!(config.app.timezone, Some(Timezone::Fixed(offset!(-3))));
+}
+
+#[test]
+fn test_named_timezones_follow_dst() {
+    let zone: Timezone = "America/New_York".parse().unwrap();
+    assert_eq!(zone.offset_at(datetime!(2026-01-15 12:00 UTC)), offset!(-5));
+    assert_eq!(zone.offset_at(datetime!(2026-07-01 12:00 UTC)), offset!(-4));
+
+    // clocks jump from 02:00 to 03:00 on 2026-03-08
+    let skipped = zone.from_local(datetime!(2026-03-08 02:30));
+    assert_eq!(skipped, datetime!(2026-03-08 07:30 UTC));
+    assert_eq!(skipped.offset(), offset!(-4));
+    assert_eq!(Timezone::default().offset_at(skipped), UtcOffset::UTC);
+
+    // east of UTC: clocks jump from 02:00 to 03:00 on 2026-03-29
+    let berlin: Timezone = "Europe/Berlin".parse().unwrap();
+    let skipped = berlin.from_local(datetime!(2026-03-29 02:30));
+    assert_eq!(skipped, datetime!(2026-03-29 01:30 UTC));
+    assert_eq!(skipped.offset(), offset!(+2));
+    assert_eq!(
+        berlin.from_local(datetime!(2026-03-29 01:30)),
+        datetime!(2026-03-29 00:30 UTC)
+    );
+}
+
+#[test]
+fn test_market_hours_follow_sessions_holidays_and_half_days() {
+    let config = load(&[]).unwrap();
+    let calendar = config.calendar().unwrap();
+
+    let name = |now| {
+        calendar
+            .session_at(now)
+            .and_then(|session| session.name.as_deref())
+    };
+    assert_eq!(name(datetime!(2026-11-30 14:00 UTC)), Some("pre"));
+    assert_eq!(name(datetime!(2026-11-30 14:30 UTC)), Some("regular"));
+    assert!(!calendar.is_market_open(datetime!(2026-11-30 21:00 UTC)));
+    assert!(!calendar.is_market_open(datetime!(2026-11-28 15:00 UTC)));
+    // Thanksgiving, then the early close on the day after
+    assert!(!calendar.is_market_open(datetime!(2026-11-26 15:00 UTC)));
+    assert!(calendar.is_market_open(datetime!(2026-11-27 17:00 UTC)));
+    assert!(!calendar.is_market_open(datetime!(2026-11-27 18:30 UTC)));
+
+    assert_eq!(
+        calendar.next_open(datetime!(2026-11-26 15:00 UTC)),
+        Some(datetime!(2026-11-27 09:00 UTC))
+    );
+    assert_eq!(
+        calendar.next_open(datetime!(2026-11-27 18:30 UTC)),
+        Some(datetime!(2026-11-30 09:00 UTC))
+    );
+}
+
+#[test]
+fn test_overnight_sessions_belong_to_the_day_they_open() {
+    let config = load(&[
+        ("calendar.timezone", "+05:30"),
+        ("calendar.sessions[0].open", "22:00"),
+        ("calendar.sessions[0].close", "02:00"),
+    ])
+    .unwrap();
+    let calendar = config.calendar().unwrap();
+
+    // Monday night into Tuesday, and Friday night into Saturday
+    assert!(calendar.is_market_open(datetime!(2026-11-30 23:00 +05:30)));
+    assert!(calendar.is_market_open(datetime!(2026-12-01 01:00 +05:30)));
+    assert!(calendar.is_market_open(datetime!(2026-12-05 01:00 +05:30)));
+    assert!(!calendar.is_market_open(datetime!(2026-11-30 01:00 +05:30)));
+}
+
+#[test]
+fn test_calendar_is_validated() {
+    let errors = validation_errors(load(&[
+        ("calendar.sessions[0].close", "04:00"),
+        ("calendar.half_days[0].date", "2026-12-25"),
+    ]));
+    let keys: Vec<_> = errors.violations.iter().map(|v| v.key.as_str()).collect();
+    assert_eq!(keys, ["calendar.sessions[0]", "calendar.half_days[0].date"]);
+}
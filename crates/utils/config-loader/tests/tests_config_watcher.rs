@@ -0,0 +1,108 @@
+use config_loader::{ConfigWatcher, LayeredConfigLoader, Validate, schema::schemars::json_schema};
+use serde::Deserialize;
+use std::{fs, path::PathBuf, time::Duration};
+
+mod common;
+use common::config_dir;
+
+#[derive(Debug, Deserialize, Validate)]
+struct RiskLimits {
+    max_order_qty: u64,
+}
+
+fn watcher(dir: &PathBuf) -> ConfigWatcher<RiskLimits> {
+    ConfigWatcher::new(LayeredConfigLoader::new(dir))
+        .poll_interval(Duration::from_millis(50))
+        .debounce(Duration::from_millis(50))
+        .validate(|limits: &RiskLimits| {
+            if limits.max_order_qty == 0 {
+                return Err("max_order_qty must be positive".into());
+            }
+            Ok(())
+        })
+}
+
+#[tokio::test]
+async fn test_reload_publishes_previous_and_current() {
+    let dir = config_dir("reload");
+    fs::write(dir.join("base.toml"), "max_order_qty = 100\n").unwrap();
+
+    let mut handle = watcher(&dir).start().await.unwrap();
+    assert_eq!(handle.current().max_order_qty, 100);
+
+    fs::write(dir.join("base.toml"), "max_order_qty = 250\n").unwrap();
+    let update = tokio::time::timeout(Duration::from_secs(5), handle.changed())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(update.previous.unwrap().max_order_qty, 100);
+    assert_eq!(update.current.max_order_qty, 250);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_reload_keeps_last_good_config() {
+    let dir = config_dir("invalid");
+    fs::write(dir.join("base.toml"), "max_order_qty = 100\n").unwrap();
+
+    let mut handle = watcher(&dir).start().await.unwrap();
+
+    fs::write(dir.join("base.toml"), "max_order_qty = 0\n").unwrap();
+    let changed = tokio::time::timeout(Duration::from_millis(500), handle.changed()).await;
+
+    assert!(changed.is_err(), "invalid config must not be published");
+    assert_eq!(handle.current().max_order_qty, 100);
+    assert!(
+        handle
+            .last_error()
+            .unwrap()
+            .contains("max_order_qty must be positive")
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_is_checked_against_the_schema() {
+    let dir = config_dir("schema");
+    fs::write(dir.join("base.toml"), "max_order_qty = 100\n").unwrap();
+
+    let loader = LayeredConfigLoader::new(&dir).schema(json_schema!({
+        "properties": { "max_order_qty": { "maximum": 1000 } }
+    }));
+    let mut handle = ConfigWatcher::<RiskLimits>::new(loader)
+        .poll_interval(Duration::from_millis(50))
+        .debounce(Duration::from_millis(50))
+        .start()
+        .await
+        .unwrap();
+
+    fs::write(dir.join("base.toml"), "max_order_qty = 5000\n").unwrap();
+    let changed = tokio::time::timeout(Duration::from_millis(500), handle.changed()).await;
+
+    assert!(
+        changed.is_err(),
+        "config rejected by the schema must not be published"
+    );
+    assert_eq!(handle.current().max_order_qty, 100);
+    assert!(
+        handle
+            .last_error()
+            .unwrap()
+            .contains("max_order_qty: must be at most 1000, got 5000")
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_start_fails_on_invalid_initial_config() {
+    let dir = config_dir("initial");
+    fs::write(dir.join("base.toml"), "max_order_qty = 0\n").unwrap();
+
+    assert!(watcher(&dir).start().await.is_err());
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,207 @@
+use config_loader::{
+    ConfigError, ConfigFormat, ConfigKey, CryptoError, FileFormat, Keyring, LayeredConfigLoader,
+    Validate, load_config,
+};
+use serde::Deserialize;
+use std::fs;
+
+mod common;
+use common::config_dir;
+
+#[derive(Debug, Deserialize, Validate)]
+struct Settings {
+    name: String,
+    database: Database,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Database {
+    host: String,
+    password: String,
+}
+
+#[test]
+fn test_values_round_trip_with_either_key() {
+    for key in [ConfigKey::generate_aes(), ConfigKey::generate_age()] {
+        let keyring = Keyring::new().with_key(key.clone());
+        let encrypted = keyring.encrypt("database.password", "hunter2").unwrap();
+        assert!(encrypted.starts_with("ENC["));
+        assert_ne!(
+            keyring.encrypt("database.password", "hunter2").unwrap(),
+            encrypted
+        );
+        assert_eq!(
+            keyring.decrypt("database.password", &encrypted).unwrap(),
+            "hunter2"
+        );
+
+        let reloaded = Keyring::new().with_key(key.expose().parse().unwrap());
+        assert_eq!(
+            reloaded.decrypt("database.password", &encrypted).unwrap(),
+            "hunter2"
+        );
+    }
+
+    let other = Keyring::new().with_key(ConfigKey::generate_aes());
+    let encrypted = other.encrypt("database.password", "hunter2").unwrap();
+    assert!(matches!(
+        Keyring::new()
+            .with_key(ConfigKey::generate_aes())
+            .decrypt("database.password", &encrypted),
+        Err(CryptoError::Decrypt { .. })
+    ));
+    assert!(matches!(
+        Keyring::new()
+            .with_key(ConfigKey::generate_age())
+            .decrypt("database.password", &encrypted),
+        Err(CryptoError::MissingKey { .. })
+    ));
+    assert!(matches!(
+        other.decrypt("database.password", "ENC[AES256_GCM,data:AAAA]"),
+        Err(CryptoError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn test_aes_values_are_bound_to_their_key() {
+    let keyring = Keyring::new().with_key(ConfigKey::generate_aes());
+    let password = keyring.encrypt("database.password", "hunter2").unwrap();
+    assert!(matches!(
+        keyring.decrypt("name", &password),
+        Err(CryptoError::Decrypt { .. })
+    ));
+
+    // copying the password to another key doesn't decrypt it there
+    let dir = config_dir("moved-value");
+    let path = dir.join("base.yaml");
+    let text = format!(
+        "name: {}
+database:
+  host: db.internal
+  password: {}
+",
+        password, password
+    );
+    fs::write(&path, text).unwrap();
+    let Err(ConfigError::Foreign(e)) = LayeredConfigLoader::new(&dir)
+        .keyring(keyring)
+        .load::<Settings>()
+    else {
+        panic!("expected decryption to fail");
+    };
+    assert!(e.to_string().starts_with("cannot decrypt 'name'"));
+}
+
+#[test]
+fn test_rotate_reencrypts_values_and_keeps_the_rest() {
+    let old = ConfigKey::generate_aes();
+    let new = ConfigKey::generate_age();
+    let encrypted = Keyring::new()
+        .with_key(old.clone())
+        .encrypt("database.password", "s3cr3t")
+        .unwrap();
+    let text = format!("database:\n  password: {}\n  port: 1433\n", encrypted);
+
+    let rotated = Keyring::new()
+        .with_key(new.clone())
+        .with_key(old)
+        .rotate(text.as_bytes(), FileFormat::Yaml.into())
+        .unwrap();
+    let rotated = String::from_utf8(rotated).unwrap();
+
+    let (head, tail) = rotated.split_once("ENC[AGE,").unwrap();
+    assert_eq!(head, "database:\n  password: ");
+    assert!(tail.ends_with("]\n  port: 1433\n"));
+    let value = rotated
+        .lines()
+        .nth(1)
+        .unwrap()
+        .trim_start_matches("  password: ");
+    assert_eq!(
+        Keyring::new()
+            .with_key(new)
+            .decrypt("database.password", value)
+            .unwrap(),
+        "s3cr3t"
+    );
+
+    assert!(matches!(
+        Keyring::new().with_key(ConfigKey::generate_aes()).rotate(
+            format!("# {}\n", encrypted).as_bytes(),
+            FileFormat::Yaml.into()
+        ),
+        Err(CryptoError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn test_layered_loader_decrypts_files_and_values() {
+    let dir = config_dir("encrypted-layers");
+    let keyring = Keyring::new()
+        .with_key(ConfigKey::generate_age())
+        .with_key(ConfigKey::generate_aes());
+    let password = Keyring::new()
+        .with_key(keyring.keys()[1].clone())
+        .encrypt("database.password", "hunter2")
+        .unwrap();
+    let base = keyring
+        .encrypt_file(b"[database]\nhost = \"db.internal\"\n")
+        .unwrap();
+    assert!(String::from_utf8_lossy(&base).starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+    fs::write(dir.join("base.toml"), base).unwrap();
+
+    let settings: Settings = LayeredConfigLoader::new(&dir)
+        .defaults(
+            format!("name=oms\ndatabase.password={}\n", password),
+            ConfigFormat::Properties,
+        )
+        .keyring(keyring)
+        .load()
+        .unwrap();
+    assert_eq!(settings.name, "oms");
+    assert_eq!(settings.database.host, "db.internal");
+    assert_eq!(settings.database.password, "hunter2");
+}
+
+#[test]
+fn test_missing_key_fails_clearly() {
+    let dir = config_dir("missing-key");
+    let keyring = Keyring::new().with_key(ConfigKey::generate_aes());
+
+    let path = dir.join("oms.yaml");
+    let text = format!(
+        "name: oms\ndatabase:\n  host: db.internal\n  password: {}\n",
+        keyring.encrypt("database.password", "hunter2").unwrap()
+    );
+    fs::write(&path, text).unwrap();
+    let Err(ConfigError::Foreign(e)) = load_config::<Settings>(path.to_str().unwrap()) else {
+        panic!("expected decryption to fail");
+    };
+    assert_eq!(
+        e.to_string(),
+        "cannot decrypt 'database.password': no AES256_GCM key to decrypt with, \
+         set CONFIG_AGE_KEY, CONFIG_AES_KEY or CONFIG_KEY_FILE"
+    );
+
+    let path = dir.join("oms.yaml.enc");
+    fs::write(&path, keyring.encrypt_file(b"name: oms\n").unwrap()).unwrap();
+    let Err(ConfigError::Foreign(e)) = load_config::<Settings>(path.to_str().unwrap()) else {
+        panic!("expected decryption to fail");
+    };
+    assert!(matches!(
+        e.downcast_ref::<CryptoError>(),
+        Some(CryptoError::Value { key, .. }) if key.ends_with("oms.yaml.enc")
+    ));
+
+    let Err(ConfigError::Foreign(e)) = LayeredConfigLoader::new(&dir)
+        .defaults("name = \"ENC[AGE,data:AAAA]\"", FileFormat::Toml)
+        .keyring(Keyring::new())
+        .load::<Settings>()
+    else {
+        panic!("expected decryption to fail");
+    };
+    assert!(
+        e.to_string()
+            .starts_with("cannot decrypt 'name': no AGE key")
+    );
+}
@@ -0,0 +1,189 @@
+use config_loader::{
+    Config, ConfigDiff, ConfigKey, File, FileFormat, Keyring, Secret, diff::Change, diff_files,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::fs;
+
+fn config(text: &str) -> Config {
+    Config::builder()
+        .add_source(File::from_str(text, FileFormat::Toml))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_diff_lists_added_removed_and_changed_keys() {
+    let old = config(
+        r#"
+        name = "oms"
+        brokers = ["k1:9092", "k2:9092"]
+        [database]
+        host = "db1"
+        password = "hunter2"
+        pool_size = 10
+        "#,
+    );
+    let new = config(
+        r#"
+        name = "oms"
+        brokers = ["k1:9092"]
+        [database]
+        host = "db2"
+        password = "hunter3"
+        [risk]
+        max_order_qty = 500
+        "#,
+    );
+
+    let diff = ConfigDiff::between(&old, &new).redact("database.host");
+    assert_eq!(
+        diff.changes,
+        [
+            Change::Removed {
+                key: "brokers[1]".into(),
+                value: json!("k2:9092"),
+            },
+            Change::Changed {
+                key: "database.host".into(),
+                old: json!("[REDACTED]"),
+                new: json!("[REDACTED]"),
+            },
+            Change::Changed {
+                key: "database.password".into(),
+                old: json!("[REDACTED]"),
+                new: json!("[REDACTED]"),
+            },
+            Change::Removed {
+                key: "database.pool_size".into(),
+                value: json!(10),
+            },
+            Change::Added {
+                key: "risk.max_order_qty".into(),
+                value: json!(500),
+            },
+        ]
+    );
+    assert_eq!((diff.added().count(), diff.removed().count()), (1, 2));
+    assert_eq!(
+        diff.to_string().lines().next(),
+        Some("- brokers[1] = \"k2:9092\"")
+    );
+    assert_eq!(
+        serde_json::to_value(&diff.changes[4]).unwrap(),
+        json!({"change": "added", "key": "risk.max_order_qty", "value": 500})
+    );
+    assert!(ConfigDiff::between(&old, &old).is_empty());
+}
+
+#[test]
+fn test_diff_files_redacts_encrypted_values() {
+    let dir = std::env::temp_dir().join(format!("config-diff-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let keyring = Keyring::new().with_key(ConfigKey::generate_aes());
+    let old = dir.join("oms.yaml");
+    let new = dir.join("oms.json");
+    fs::write(
+        &old,
+        format!(
+            "venue: XNAS\napi_key: {}\n",
+            keyring.encrypt("api_key", "k1").unwrap()
+        ),
+    )
+    .unwrap();
+    fs::write(
+        &new,
+        format!(
+            r#"{{"venue": "XNYS", "api_key": "{}"}}"#,
+            keyring.encrypt("api_key", "k2").unwrap()
+        ),
+    )
+    .unwrap();
+
+    let diff = diff_files(&old, &new).unwrap();
+    assert_eq!(
+        diff.to_string(),
+        "~ api_key: \"[REDACTED]\" -> \"[REDACTED]\"\n~ venue: \"XNAS\" -> \"XNYS\"\n"
+    );
+    assert!(diff_files(&old, dir.join("oms.txt")).is_err());
+}
+
+#[test]
+fn test_diff_files_does_not_decrypt_whole_files() {
+    let dir = std::env::temp_dir().join(format!("config-diff-age-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let keyring = Keyring::new().with_key(ConfigKey::generate_age());
+    let plain = dir.join("oms.yaml");
+    let old = dir.join("oms.yaml.age");
+    let new = dir.join("oms.next.yaml.age");
+    fs::write(
+        &plain,
+        "api_key: k1
+",
+    )
+    .unwrap();
+    fs::write(
+        &old,
+        keyring
+            .encrypt_file(
+                b"api_key: k1
+",
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    fs::write(
+        &new,
+        keyring
+            .encrypt_file(
+                b"api_key: k2
+",
+            )
+            .unwrap(),
+    )
+    .unwrap();
+
+    // no keys in the environment, and none needed
+    assert!(diff_files(&old, &old).unwrap().is_empty());
+    for (from, to) in [(&old, &new), (&plain, &new)] {
+        let diff = diff_files(from, to).unwrap();
+        assert_eq!(
+            diff.changes,
+            [Change::Changed {
+                key: "".into(),
+                old: json!("[REDACTED]"),
+                new: json!("[REDACTED]"),
+            }]
+        );
+        assert_eq!(diff.to_string(), "~ encrypted file\n");
+    }
+}
+
+#[test]
+fn test_typed_diff_hides_secrets() {
+    #[derive(Serialize)]
+    struct Settings {
+        max_order_qty: u32,
+        token: Secret<String>,
+    }
+
+    let diff = ConfigDiff::between_typed(
+        &Settings {
+            max_order_qty: 10,
+            token: Secret::new("a".to_string()),
+        },
+        &Settings {
+            max_order_qty: 20,
+            token: Secret::new("b".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        diff.changes,
+        [Change::Changed {
+            key: "max_order_qty".into(),
+            old: json!(10),
+            new: json!(20),
+        }]
+    );
+}
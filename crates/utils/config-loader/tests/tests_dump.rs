@@ -0,0 +1,144 @@
+use config_loader::{
+    DumpFormat, FileFormat, LayeredConfigLoader, Secret, Validate, properties,
+    schema::{self, JsonSchema},
+    secret::REDACTED,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fs};
+
+mod common;
+use common::config_dir;
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[schemars(crate = "config_loader::schema::schemars")]
+struct Settings {
+    name: String,
+    dsn: Secret<String>,
+    database: Database,
+}
+
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[schemars(crate = "config_loader::schema::schemars")]
+struct Database {
+    host: String,
+    password: String,
+}
+
+const DEFAULTS: &str = r#"
+name = "oms"
+dsn = "mssql://oms:hunter2@db"
+venues = ["XNAS", "XNYS"]
+
+[database]
+host = "localhost"
+password = "hunter2"
+
+[auth]
+api_token = "t0ken"
+signing_key = "k3y"
+"#;
+
+#[test]
+fn test_dump_formats_with_sources_and_redaction() {
+    let dir = config_dir("dump");
+    fs::write(
+        dir.join("base.toml"),
+        "[database]\nhost = \"db.internal\"\n",
+    )
+    .unwrap();
+
+    let layered = LayeredConfigLoader::new(&dir)
+        .defaults(DEFAULTS, FileFormat::Toml)
+        .env_prefix("OMS")
+        .env_vars(HashMap::from([(
+            "OMS_NAME".to_string(),
+            "oms-canary".to_string(),
+        )]))
+        .build()
+        .unwrap();
+    let dump = layered
+        .dump()
+        .redact("auth.signing_key")
+        .redact_schema(&schema::schema_for::<Settings>());
+    let base = dir.join("base.toml").display().to_string();
+
+    assert_eq!(
+        dump.render(DumpFormat::Yaml),
+        format!(
+            "auth:\n  \
+               api_token: \"{REDACTED}\"  # defaults\n  \
+               signing_key: \"{REDACTED}\"  # defaults\n\
+             database:\n  \
+               host: \"db.internal\"  # {base}\n  \
+               password: \"{REDACTED}\"  # defaults\n\
+             dsn: \"{REDACTED}\"  # defaults\n\
+             name: \"oms-canary\"  # environment\n\
+             venues:  # defaults\n  \
+               - \"XNAS\"\n  \
+               - \"XNYS\"\n"
+        )
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&dump.render(DumpFormat::Json)).unwrap();
+    assert_eq!(json["config"]["database"]["password"], REDACTED);
+    assert_eq!(json["config"]["venues"][1], "XNYS");
+    assert_eq!(json["sources"]["database.host"], base.as_str());
+
+    let text = dump.render(DumpFormat::Properties);
+    assert!(text.starts_with("# defaults\nauth.api_token=[REDACTED]\n"));
+    assert!(text.contains(&format!("\n# {}\ndatabase.host=db.internal\n", base)));
+    let entries: HashMap<_, _> = properties::parse(&text).unwrap().into_iter().collect();
+    assert_eq!(entries["venues[0]"], "XNAS");
+    assert_eq!(entries["dsn"], REDACTED);
+}
+
+#[test]
+fn test_dump_redacts_secrets_of_the_type_without_a_schema() {
+    #[allow(dead_code)]
+    #[derive(Validate)]
+    struct Settings {
+        dsn: Secret<String>,
+        #[validate(nested)]
+        accounts: Vec<Account>,
+        #[validate(nested)]
+        venues: HashMap<String, Venue>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Validate)]
+    struct Account {
+        #[validate(redact, non_empty)]
+        number: String,
+        name: String,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, Validate)]
+    struct Venue {
+        #[serde(rename = "key")]
+        api_key: Option<Secret<String>>,
+    }
+
+    let mut keys = Vec::new();
+    Settings::redacted_keys("", &mut keys);
+    assert_eq!(keys, ["dsn", "accounts[*].number", "venues.*.key"]);
+
+    let layered = LayeredConfigLoader::new("does-not-exist")
+        .defaults(
+            r#"
+            dsn = "mssql://oms:hunter2@db"
+            accounts = [{ number = "12-345", name = "prop" }]
+            venues.xnas.key = "k3y"
+            "#,
+            FileFormat::Toml,
+        )
+        .build()
+        .unwrap();
+    let json = layered.dump().redact_type::<Settings>().to_json();
+    assert_eq!(json["dsn"], REDACTED);
+    assert_eq!(json["accounts"][0]["number"], REDACTED);
+    assert_eq!(json["accounts"][0]["name"], "prop");
+    assert_eq!(json["venues"]["xnas"]["key"], REDACTED);
+}
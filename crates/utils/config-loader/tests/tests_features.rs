@@ -0,0 +1,132 @@
+use config_loader::{
+    ConfigError, FileFormat, LayeredConfigLoader, ServiceConfig, Validate, env::Env,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+mod common;
+use common::validation_errors;
+
+#[derive(Debug, Deserialize, Validate)]
+struct Orders {
+    max_open_orders: u32,
+}
+
+const CONFIG: &str = r#"
+max_open_orders = 500
+
+[app]
+name = "oms"
+env = "PROD"
+
+[features]
+new_router = true
+legacy_fix = false
+smart_routing = { percentage = 25 }
+dark_pool = { envs = "staging, prod" }
+
+[staging.features]
+smart_routing = { percentage = 100 }
+
+[production]
+max_open_orders = 50
+"#;
+
+fn load(env: Env) -> Result<ServiceConfig<Orders>, ConfigError> {
+    LayeredConfigLoader::new("does-not-exist")
+        .defaults(CONFIG, FileFormat::Toml)
+        .env(env)
+        .env_prefix("OMS")
+        .env_vars(HashMap::from([(
+            "OMS_FEATURES__LEGACY_FIX".to_string(),
+            "true".to_string(),
+        )]))
+        .load()
+}
+
+#[test]
+fn test_env_deserializes_with_aliases() {
+    let config = load(Env::Production).unwrap();
+    assert_eq!(config.app.env, Some(Env::Production));
+
+    let config: ServiceConfig<Orders> = LayeredConfigLoader::new("does-not-exist")
+        .defaults(CONFIG, FileFormat::Toml)
+        .set("app.env", "uat")
+        .load()
+        .unwrap();
+    assert_eq!(config.app.env, Some(Env::Unknown("uat".to_string())));
+}
+
+#[test]
+fn test_env_scoped_sections_override_top_level_keys() {
+    let production = load(Env::Production).unwrap();
+    assert_eq!(production.ext.max_open_orders, 50);
+
+    let staging = load(Env::Staging).unwrap();
+    assert_eq!(staging.ext.max_open_orders, 500);
+    assert!(
+        staging
+            .features
+            .is_enabled("smart_routing", Some(&Env::Staging))
+    );
+}
+
+#[test]
+fn test_keys_named_after_an_env_are_kept_unless_sections() {
+    #[derive(Debug, Deserialize, Validate)]
+    struct Settings {
+        dev: bool,
+        max_open_orders: u32,
+    }
+
+    let settings: Settings = LayeredConfigLoader::new("does-not-exist")
+        .defaults(
+            "dev = true\nmax_open_orders = 500\n\n[prod]\nmax_open_orders = 50\n",
+            FileFormat::Toml,
+        )
+        .env(Env::Production)
+        .load()
+        .unwrap();
+    assert!(settings.dev);
+    assert_eq!(settings.max_open_orders, 50);
+}
+
+#[test]
+fn test_flags_are_evaluated_per_env_and_key() {
+    let config = load(Env::Production).unwrap();
+
+    assert!(config.feature_enabled("new_router"));
+    assert!(config.feature_enabled("legacy_fix"));
+    assert!(config.feature_enabled("dark_pool"));
+    assert!(!config.feature_enabled("smart_routing"));
+    assert!(!config.feature_enabled("unknown"));
+    assert!(
+        !config
+            .features
+            .is_enabled("dark_pool", Some(&Env::Development))
+    );
+    assert!(!config.features.is_enabled("dark_pool", None));
+
+    let enabled = (0..10_000)
+        .filter(|i| config.feature_enabled_for("smart_routing", &format!("account-{}", i)))
+        .count();
+    assert!((2_300..2_700).contains(&enabled), "{} of 10000", enabled);
+    assert_eq!(
+        config.feature_enabled_for("smart_routing", "account-42"),
+        config.feature_enabled_for("smart_routing", "account-42")
+    );
+}
+
+#[test]
+fn test_rollout_percentage_is_validated() {
+    let errors = validation_errors(
+        LayeredConfigLoader::new("does-not-exist")
+            .defaults(CONFIG, FileFormat::Toml)
+            .set("features.smart_routing.percentage", "150")
+            .load::<ServiceConfig<Orders>>(),
+    );
+    assert_eq!(
+        errors.violations[0].key,
+        "features.smart_routing.percentage"
+    );
+}
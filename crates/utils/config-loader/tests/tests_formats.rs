@@ -0,0 +1,214 @@
+use config_loader::{
+    Config, ConfigFormat, ConfigLayer, File, FileFormat, FormatRegistry, HoconFile, HttpSource,
+    LayeredConfigLoader, SyntaxError, Validate, dotenv, env::Env, load_config,
+    load_config_with_format,
+};
+use serde::Deserialize;
+use std::fs;
+
+mod common;
+use common::{MockServer, config_dir};
+
+#[derive(Debug, Deserialize, Validate)]
+struct Settings {
+    name: String,
+    database: Database,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Database {
+    host: String,
+    port: u16,
+    #[serde(default)]
+    servers: Vec<String>,
+}
+
+#[test]
+fn test_registry_detects_by_extension() {
+    let registry = FormatRegistry::default();
+
+    assert_eq!(
+        registry.detect("config/oms.properties".as_ref()),
+        Some(ConfigFormat::Properties)
+    );
+    assert_eq!(
+        registry.detect("config/oms.CONF".as_ref()),
+        Some(ConfigFormat::Hocon)
+    );
+    assert_eq!(
+        registry.detect(".env.local".as_ref()),
+        Some(ConfigFormat::Dotenv)
+    );
+    assert_eq!(
+        registry.detect_uri("http://cfg:8888/oms/prod.yml?label=main"),
+        Some(ConfigFormat::File(FileFormat::Yaml))
+    );
+    assert_eq!(registry.detect("oms.cfg".as_ref()), None);
+
+    let registry = registry.register("cfg", ConfigFormat::Properties);
+    assert_eq!(
+        registry.detect("oms.cfg".as_ref()),
+        Some(ConfigFormat::Properties)
+    );
+}
+
+#[test]
+fn test_load_config_detects_and_overrides_format() {
+    let dir = config_dir("load");
+    let path = dir.join("oms.properties");
+    fs::write(
+        &path,
+        "name=oms\ndatabase.host=db.internal\ndatabase.port=1433\n",
+    )
+    .unwrap();
+
+    let settings: Settings = load_config(path.to_str().unwrap()).unwrap();
+    assert_eq!(settings.database.host, "db.internal");
+    assert_eq!(settings.database.port, 1433);
+
+    let path = dir.join("oms.txt");
+    fs::write(&path, "NAME=oms\nDATABASE__HOST=db\nDATABASE__PORT=1533\n").unwrap();
+
+    assert!(load_config::<Settings>(path.to_str().unwrap()).is_err());
+    let settings: Settings =
+        load_config_with_format(path.to_str().unwrap(), ConfigFormat::Dotenv).unwrap();
+    assert_eq!(settings.name, "oms");
+    assert_eq!(settings.database.port, 1533);
+}
+
+#[test]
+fn test_dotenv_quoting_and_comments() {
+    let entries = dotenv::parse(
+        "# service\nexport NAME=oms # trailing\nGREETING=\"line1\\nline2\"\nRAW='a\\nb #c'\nURL=http://x/#anchor\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        entries,
+        [
+            ("NAME".to_string(), "oms".to_string()),
+            ("GREETING".to_string(), "line1\nline2".to_string()),
+            ("RAW".to_string(), "a\\nb #c".to_string()),
+            ("URL".to_string(), "http://x/#anchor".to_string()),
+        ]
+    );
+
+    let err = dotenv::parse("NAME=oms\nGREETING=\"unterminated\n").unwrap_err();
+    assert_eq!(
+        err,
+        SyntaxError {
+            format: "dotenv",
+            line: 2,
+            column: 10,
+            message: "unterminated quoted value".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_hocon_objects_substitutions_and_errors() {
+    let text = r#"
+        // defaults
+        name = oms
+        database {
+            host = "db.internal"
+            port: 1433
+            servers = [ "kafka-1:9092", kafka-2":"9092 ]
+        }
+        database.port = 1533
+        database { pool_size = 8 }
+        jdbc = "jdbc:sqlserver://"${database.host}":"${database.port}
+        password = "${env:DB_PASS}"
+        optional = ${?DOES_NOT_EXIST_ANYWHERE}
+    "#;
+    let config = Config::builder()
+        .add_source(File::from_str(text, HoconFile))
+        .build()
+        .unwrap();
+
+    let settings: Settings = config.clone().try_deserialize().unwrap();
+    assert_eq!(settings.name, "oms");
+    assert_eq!(settings.database.port, 1533);
+    assert_eq!(settings.database.servers, ["kafka-1:9092", "kafka-2:9092"]);
+    assert_eq!(config.get_int("database.pool_size").unwrap(), 8);
+    assert_eq!(
+        config.get_string("jdbc").unwrap(),
+        "jdbc:sqlserver://db.internal:1533"
+    );
+    assert_eq!(config.get_string("password").unwrap(), "${env:DB_PASS}");
+    assert!(config.get_string("optional").is_err());
+
+    let err = Config::builder()
+        .add_source(File::from_str("a {\n  b = ${missing}\n}\n", HoconFile))
+        .build()
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("unresolved substitution ${missing}")
+    );
+
+    let err = Config::builder()
+        .add_source(File::from_str("a = 1\nb { c = 2\n", HoconFile))
+        .build()
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("HOCON line 3, column 1: expected '}'")
+    );
+}
+
+#[test]
+fn test_layered_loader_uses_registry() {
+    let dir = config_dir("layered");
+    fs::write(
+        dir.join("base.conf"),
+        "name = oms\ndatabase { host = localhost, port = 1433 }\n",
+    )
+    .unwrap();
+    fs::write(dir.join("prod.properties"), "database.host=db.prod\n").unwrap();
+    fs::write(dir.join("local.env"), "DATABASE__PORT=1533\n").unwrap();
+
+    let layered = LayeredConfigLoader::new(&dir)
+        .env(Env::from("prod"))
+        .build()
+        .unwrap();
+    let settings: Settings = layered.try_deserialize().unwrap();
+    assert_eq!(settings.database.host, "db.prod");
+    assert_eq!(settings.database.port, 1533);
+    assert_eq!(
+        layered.source_of("database.port"),
+        Some(&ConfigLayer::Local(dir.join("local.env")))
+    );
+
+    // with an explicit format, files in other formats are ignored
+    let settings: Settings = LayeredConfigLoader::new(&dir)
+        .env(Env::from("prod"))
+        .format(ConfigFormat::Hocon)
+        .load()
+        .unwrap();
+    assert_eq!(settings.database.host, "localhost");
+    assert_eq!(settings.database.port, 1433);
+}
+
+#[tokio::test]
+async fn test_http_source_detects_format_from_uri() {
+    let server = MockServer::start(vec![(
+        200,
+        vec![],
+        "name=oms\ndatabase.host=db\ndatabase.port=1433\n",
+    )])
+    .await;
+
+    let source = HttpSource::detect(format!("{}/oms-prod.properties", server.url)).unwrap();
+    let settings: Settings = Config::builder()
+        .add_async_source(source)
+        .build()
+        .await
+        .unwrap()
+        .try_deserialize()
+        .unwrap();
+    assert_eq!(settings.database.host, "db");
+    assert!(server.requests()[0].starts_with("get /oms-prod.properties "));
+
+    assert!(HttpSource::detect(format!("{}/oms", server.url)).is_err());
+}
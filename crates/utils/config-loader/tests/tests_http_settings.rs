@@ -0,0 +1,109 @@
+use config_loader::{
+    Config, ConfigError, File, FileFormat, HttpClientError, HttpClientSettings, Validate,
+    load_config_async_with,
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+mod common;
+use common::{MockServer, validation_errors};
+
+#[derive(Debug, Deserialize, Validate)]
+struct Risk {
+    max_order_qty: u32,
+}
+
+fn settings(text: &str) -> Result<HttpClientSettings, ConfigError> {
+    let settings: HttpClientSettings = Config::builder()
+        .add_source(File::from_str(text, FileFormat::Toml))
+        .build()?
+        .try_deserialize()?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+#[test]
+fn test_settings_parse_durations_and_default_the_rest() {
+    let http = settings(
+        r#"
+        timeout = "1m30s"
+        connect_timeout = 2
+        [retry]
+        min_backoff = "250ms"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(http.timeout, Duration::from_secs(90));
+    assert_eq!(http.connect_timeout, Duration::from_secs(2));
+    assert_eq!(http.pool_max_idle_per_host, 8);
+    let retry = http.retry.as_ref().unwrap();
+    assert_eq!(retry.max_retries, 3);
+    assert_eq!(retry.min_backoff, Duration::from_millis(250));
+    assert_eq!(
+        serde_json::to_value(&http).unwrap()["timeout"],
+        serde_json::json!("1m30s")
+    );
+
+    let Err(e) = settings("timeout = \"5 seconds\"") else {
+        panic!("expected the duration to be rejected");
+    };
+    assert!(e.to_string().contains("invalid duration '5 seconds'"));
+
+    let errors = validation_errors(settings(
+        r#"
+        proxy = "proxy.internal:3128"
+        [tls]
+        key_file = "client.key"
+        "#,
+    ));
+    let keys: Vec<_> = errors.violations.iter().map(|v| v.key.as_str()).collect();
+    assert_eq!(keys, ["proxy", "tls.cert_file"]);
+}
+
+#[tokio::test]
+async fn test_load_config_async_uses_settings() {
+    let server = MockServer::start(vec![(200, vec![], r#"{"max_order_qty": 500}"#)]).await;
+    let http = settings(&format!(
+        r#"
+        base_url = "{}/config/"
+        [headers]
+        X-Client = "oms"
+        "#,
+        server.url
+    ))
+    .unwrap();
+    assert_eq!(
+        http.url("/risk.json"),
+        format!("{}/config/risk.json", server.url)
+    );
+
+    let risk: Risk = load_config_async_with("risk.json", FileFormat::Json, &http)
+        .await
+        .unwrap();
+    assert_eq!(risk.max_order_qty, 500);
+    let requests = server.requests();
+    assert!(requests[0].starts_with("get /config/risk.json "));
+    assert!(requests[0].contains("x-client: oms"));
+    assert!(requests[0].contains("accept: application/json"));
+}
+
+#[test]
+fn test_unreadable_tls_files_fail_the_conversion() {
+    let http = settings(
+        r#"
+        [tls]
+        ca_file = "/does/not/exist.pem"
+        "#,
+    )
+    .unwrap();
+    assert!(matches!(
+        http.client(),
+        Err(HttpClientError::Read { path, .. }) if path == "/does/not/exist.pem"
+    ));
+
+    let http = settings("[headers]\n\"bad header\" = \"x\"").unwrap();
+    assert_eq!(
+        http.client().unwrap_err().to_string(),
+        "invalid default header 'bad header'"
+    );
+}
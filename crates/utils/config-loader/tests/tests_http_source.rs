@@ -0,0 +1,119 @@
+use config_loader::{Config, FileFormat, HttpClientSettings, HttpSource, SourceStatus};
+use http_client::RetryConfig;
+use std::{fs, path::PathBuf, time::Duration};
+
+mod common;
+use common::MockServer;
+
+const CONFIG: &str = r#"{"risk": {"max_order_qty": 500}}"#;
+
+fn cache_path(test: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("http-source-{}-{}.json", test, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+async fn max_order_qty(source: &HttpSource<FileFormat>) -> Result<i64, config_loader::ConfigError> {
+    Config::builder()
+        .add_async_source(source.clone())
+        .build()
+        .await?
+        .get_int("risk.max_order_qty")
+}
+
+#[tokio::test]
+async fn test_auth_headers_and_etag_revalidation() {
+    let server = MockServer::start(vec![
+        (200, vec![("etag", "\"v1\"")], CONFIG),
+        (304, vec![], ""),
+    ])
+    .await;
+
+    let source = HttpSource::new(format!("{}/oms.json", server.url), FileFormat::Json)
+        .bearer_auth("t0ken")
+        .header("X-Service", "oms");
+
+    assert_eq!(max_order_qty(&source).await.unwrap(), 500);
+    assert_eq!(source.status(), SourceStatus::Fetched);
+
+    assert_eq!(max_order_qty(&source).await.unwrap(), 500);
+    assert_eq!(source.status(), SourceStatus::NotModified);
+
+    let requests = server.requests();
+    assert!(requests[0].contains("authorization: bearer t0ken"));
+    assert!(requests[0].contains("x-service: oms"));
+    assert!(requests[1].contains("if-none-match: \"v1\""));
+}
+
+#[tokio::test]
+async fn test_error_status_is_not_parsed_as_config() {
+    let server = MockServer::start(vec![(404, vec![], r#"{"error": "not found"}"#)]).await;
+    let source = HttpSource::new(&server.url, FileFormat::Json);
+
+    let err = max_order_qty(&source).await.unwrap_err();
+    assert!(err.to_string().contains("404"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_retries_then_falls_back_to_disk_cache() {
+    let cache = cache_path("fallback");
+    let server = MockServer::start(vec![
+        (200, vec![], CONFIG),
+        (503, vec![], "unavailable"),
+        (503, vec![], "unavailable"),
+    ])
+    .await;
+
+    let source = HttpSource::new(&server.url, FileFormat::Json)
+        .cache_file(&cache)
+        .retry(RetryConfig {
+            max_retries: 1,
+            min_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        });
+
+    assert_eq!(max_order_qty(&source).await.unwrap(), 500);
+    assert!(!source.used_fallback());
+
+    // a fresh process only has the disk cache
+    let restarted = HttpSource::new(&server.url, FileFormat::Json)
+        .cache_file(&cache)
+        .retry(RetryConfig {
+            max_retries: 1,
+            min_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        });
+    assert_eq!(max_order_qty(&restarted).await.unwrap(), 500);
+    assert!(restarted.used_fallback());
+    assert_eq!(server.requests().len(), 3);
+
+    fs::remove_file(cache).unwrap();
+}
+
+#[tokio::test]
+async fn test_retry_keeps_the_client_settings() {
+    let server = MockServer::start(vec![(503, vec![], "unavailable"), (200, vec![], CONFIG)]).await;
+    let mut settings = HttpClientSettings::default();
+    settings
+        .headers
+        .insert("X-Client".to_string(), "oms".to_string());
+
+    let source = HttpSource::new(&server.url, FileFormat::Json)
+        .with_settings(&settings)
+        .unwrap()
+        .retry(RetryConfig {
+            max_retries: 1,
+            min_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        });
+    assert_eq!(max_order_qty(&source).await.unwrap(), 500);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(
+        requests
+            .iter()
+            .all(|request| request.contains("x-client: oms"))
+    );
+}
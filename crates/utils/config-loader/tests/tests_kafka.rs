@@ -0,0 +1,108 @@
+use config_loader::kafka::{Acks, KafkaConfig, SecurityProtocol};
+
+mod common;
+use common::{load, violated_keys};
+
+const KAFKA: &str = r#"
+enabled = true
+client_id = "oms"
+servers = ["kafka-1:9093", "kafka-2:9093"]
+
+[security]
+protocol = "SASL_SSL"
+
+[security.sasl]
+mechanism = "SCRAM-SHA-512"
+username = "oms"
+password = "s3cret"
+
+[security.tls]
+ca_location = "/etc/kafka/ca.pem"
+verify_hostname = false
+
+[producer]
+topic = "orders"
+acks = -1
+linger_ms = 5
+compression = "zstd"
+enable_idempotence = true
+
+[consumer]
+group_id = "oms-executions"
+topics = "executions, fills"
+auto_offset_reset = "earliest"
+enable_auto_commit = false
+
+[properties]
+"statistics.interval.ms" = "60000"
+"#;
+
+#[test]
+fn test_full_config_converts_to_librdkafka_properties() {
+    let kafka: KafkaConfig = load(KAFKA).unwrap();
+    assert_eq!(kafka.security.protocol, SecurityProtocol::SaslSsl);
+    assert_eq!(kafka.producer.as_ref().unwrap().acks, Acks::All);
+    assert_eq!(
+        kafka.consumer.as_ref().unwrap().topics,
+        ["executions", "fills"]
+    );
+
+    let producer = kafka.producer_properties();
+    for (key, value) in [
+        ("bootstrap.servers", "kafka-1:9093,kafka-2:9093"),
+        ("client.id", "oms"),
+        ("security.protocol", "sasl_ssl"),
+        ("sasl.mechanism", "SCRAM-SHA-512"),
+        ("sasl.username", "oms"),
+        ("sasl.password", "s3cret"),
+        ("ssl.ca.location", "/etc/kafka/ca.pem"),
+        ("ssl.endpoint.identification.algorithm", "none"),
+        ("acks", "all"),
+        ("linger.ms", "5"),
+        ("compression.type", "zstd"),
+        ("enable.idempotence", "true"),
+        ("statistics.interval.ms", "60000"),
+    ] {
+        assert_eq!(
+            producer.get(key).map(String::as_str),
+            Some(value),
+            "{}",
+            key
+        );
+    }
+    assert!(!producer.contains_key("group.id"));
+
+    let consumer = kafka.consumer_properties();
+    assert_eq!(consumer["group.id"], "oms-executions");
+    assert_eq!(consumer["auto.offset.reset"], "earliest");
+    assert_eq!(consumer["enable.auto.commit"], "false");
+    assert!(!consumer.contains_key("acks"));
+}
+
+#[test]
+fn test_inconsistent_settings_are_rejected() {
+    let invalid = KAFKA
+        .replace("[security.sasl]", "[unused]")
+        .replace("acks = -1", "acks = 1")
+        .replace("topics = \"executions, fills\"", "topics = []");
+
+    assert_eq!(
+        violated_keys(load::<KafkaConfig>(&invalid)),
+        ["consumer.topics", "producer.acks", "security.sasl"]
+    );
+}
+
+#[test]
+fn test_servers_are_only_checked_when_enabled() {
+    let placeholder = KAFKA.replace(
+        "servers = [\"kafka-1:9093\", \"kafka-2:9093\"]",
+        "servers = \"\"",
+    );
+    assert_eq!(
+        violated_keys(load::<KafkaConfig>(&placeholder)),
+        ["servers"]
+    );
+
+    let disabled = placeholder.replace("enabled = true", "enabled = false");
+    assert!(load::<KafkaConfig>(&disabled).unwrap().servers.is_empty());
+}
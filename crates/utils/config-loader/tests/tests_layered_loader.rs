@@ -0,0 +1,111 @@
+use config_loader::{ConfigLayer, FileFormat, LayeredConfigLoader, env::Env};
+use serde::Deserialize;
+use std::{collections::HashMap, fs};
+
+mod common;
+use common::config_dir;
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    name: String,
+    log_level: String,
+    database: Database,
+}
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    host: String,
+    port: u16,
+    pool_size: u32,
+}
+
+const DEFAULTS: &str = r#"
+name = "oms"
+log_level = "info"
+
+[database]
+host = "localhost"
+port = 1433
+pool_size = 4
+"#;
+
+#[test]
+fn test_layers_override_in_order() {
+    let dir = config_dir("order");
+    fs::write(
+        dir.join("base.toml"),
+        "[database]\nhost = \"db.internal\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("prod.yaml"), "database:\n  pool_size: 32\n").unwrap();
+    fs::write(dir.join("local.json"), r#"{ "log_level": "debug" }"#).unwrap();
+
+    let env_vars = HashMap::from([("OMS_DATABASE__PORT".to_string(), "1533".to_string())]);
+
+    let layered = LayeredConfigLoader::new(&dir)
+        .defaults(DEFAULTS, FileFormat::Toml)
+        .env(Env::from("prod"))
+        .env_prefix("OMS")
+        .env_vars(env_vars)
+        .overrides(["name=oms-canary"])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let settings: Settings = layered.try_deserialize().unwrap();
+    assert_eq!(settings.name, "oms-canary");
+    assert_eq!(settings.log_level, "debug");
+    assert_eq!(settings.database.host, "db.internal");
+    assert_eq!(settings.database.port, 1533);
+    assert_eq!(settings.database.pool_size, 32);
+
+    assert_eq!(layered.source_of("name"), Some(&ConfigLayer::Override));
+    assert_eq!(
+        layered.source_of("log_level"),
+        Some(&ConfigLayer::Local(dir.join("local.json")))
+    );
+    assert_eq!(
+        layered.source_of("database.host"),
+        Some(&ConfigLayer::Base(dir.join("base.toml")))
+    );
+    assert_eq!(
+        layered.source_of("database.pool_size"),
+        Some(&ConfigLayer::Profile(dir.join("prod.yaml")))
+    );
+    assert_eq!(
+        layered.source_of("database.port"),
+        Some(&ConfigLayer::Environment)
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_profile_file_found_by_alias() {
+    let dir = config_dir("alias");
+    fs::write(dir.join("stg.toml"), "log_level = \"warn\"\n").unwrap();
+
+    let layered = LayeredConfigLoader::new(&dir)
+        .defaults(DEFAULTS, FileFormat::Toml)
+        .env(Env::Staging)
+        .build()
+        .unwrap();
+
+    let settings: Settings = layered.try_deserialize().unwrap();
+    assert_eq!(settings.log_level, "warn");
+    assert_eq!(
+        layered.source_of("database.host"),
+        Some(&ConfigLayer::Defaults)
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_invalid_override_is_rejected() {
+    assert!(
+        LayeredConfigLoader::default()
+            .overrides(["log_level"])
+            .is_err()
+    );
+}
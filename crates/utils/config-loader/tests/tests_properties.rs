@@ -0,0 +1,120 @@
+use config_loader::{
+    Config, File, PropertiesError, Validate, database::MssqlConfig, properties,
+    properties::PropertiesFile,
+};
+use serde::Deserialize;
+
+const DATABASE: &str = r#"
+# MSSQL connection
+! legacy comment style
+database.host = db.internal
+database.port: 1433
+database.username   oms
+database.password = p\=ss\:w0rd\u00e9
+database.database = orders_\
+                    archive
+kafka.servers[0] = kafka-1:9092
+kafka.servers[1] = kafka-2:9092
+"#;
+
+#[derive(Debug, Deserialize, Validate)]
+struct Settings {
+    database: MssqlConfig,
+    kafka: Kafka,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Kafka {
+    servers: Vec<String>,
+}
+
+fn load(text: &str) -> Settings {
+    Config::builder()
+        .add_source(File::from_str(text, PropertiesFile))
+        .build()
+        .unwrap()
+        .try_deserialize()
+        .unwrap()
+}
+
+#[test]
+fn test_separators_escapes_and_nesting() {
+    let settings = load(DATABASE);
+
+    assert_eq!(settings.database.host, "db.internal");
+    assert_eq!(settings.database.port, 1433);
+    assert_eq!(settings.database.username, "oms");
+    assert_eq!(settings.database.password.expose(), "p=ss:w0rdé");
+    assert_eq!(settings.database.database, "orders_archive");
+    assert_eq!(settings.kafka.servers, ["kafka-1:9092", "kafka-2:9092"]);
+}
+
+#[test]
+fn test_escaped_key_characters_and_surrogates() {
+    let entries = properties::parse("key\\ with\\:colon = \\uD83D\\uDE80 \\t\n").unwrap();
+    assert_eq!(
+        entries,
+        [("key with:colon".to_string(), "🚀 \t".to_string())]
+    );
+}
+
+#[test]
+fn test_errors_carry_line_and_column() {
+    let err = properties::parse("a=1\nb = \\u12G4\n").unwrap_err();
+    assert_eq!(
+        err,
+        PropertiesError {
+            line: 2,
+            column: 5,
+            message: "malformed \\uXXXX escape".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_huge_array_indices_are_rejected() {
+    for key in [
+        "servers[4097]",
+        "servers[4294967295]",
+        "servers[18446744073709551615]",
+    ] {
+        let err = Config::builder()
+            .add_source(File::from_str(&format!("{} = x", key), PropertiesFile))
+            .build()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("above the maximum of 4096"),
+            "{}",
+            err
+        );
+    }
+
+    let config = Config::builder()
+        .add_source(File::from_str(
+            "servers[2] = c\nservers[0] = a",
+            PropertiesFile,
+        ))
+        .build()
+        .unwrap();
+    let servers: Vec<Option<String>> = config.get("servers").unwrap();
+    assert_eq!(
+        servers,
+        [Some("a".to_string()), None, Some("c".to_string())]
+    );
+}
+
+#[test]
+fn test_writer_round_trips() {
+    let config = Config::builder()
+        .add_source(File::from_str(DATABASE, PropertiesFile))
+        .build()
+        .unwrap();
+    let written = properties::write(&config.cache.clone().into_table().unwrap());
+
+    assert!(written.contains("database.password=p\\=ss\\:w0rd\\u00E9\n"));
+    assert!(written.contains("kafka.servers[1]=kafka-2\\:9092\n"));
+
+    let reloaded = load(&written);
+    assert_eq!(reloaded.database.password.expose(), "p=ss:w0rdé");
+    assert_eq!(reloaded.kafka.servers.len(), 2);
+}
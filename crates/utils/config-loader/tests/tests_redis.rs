@@ -0,0 +1,76 @@
+use config_loader::redis::RedisConfig;
+
+mod common;
+use common::{load, violated_keys};
+
+fn violations(text: &str) -> Vec<String> {
+    violated_keys(load::<RedisConfig>(text))
+}
+
+#[test]
+fn test_urls_per_topology() {
+    let single: RedisConfig = load(
+        r#"
+        mode = "single"
+        host = "cache.internal"
+        database = 2
+        password = "p@ss/w:rd"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        single.url(),
+        "redis://:p%40ss%2Fw%3Ard@cache.internal:6379/2"
+    );
+
+    let sentinel: RedisConfig = load(
+        r#"
+        mode = "sentinel"
+        nodes = "s1:26379, s2:26379"
+        master_name = "oms master"
+        username = "oms"
+        password = "secret"
+
+        [tls]
+        insecure = true
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        sentinel.url(),
+        "rediss+sentinel://oms:secret@s1:26379,s2:26379/oms%20master/0#insecure"
+    );
+
+    let cluster: RedisConfig = load(
+        r#"
+        mode = "cluster"
+        nodes = ["n1:7000", "n2:7001"]
+        username = "oms"
+
+        [tls]
+        ca_file = "/etc/redis/ca.pem"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(cluster.url(), "rediss://oms@n1:7000,n2:7001");
+    assert_eq!(
+        cluster.node_urls(),
+        ["rediss://oms@n1:7000", "rediss://oms@n2:7001"]
+    );
+}
+
+#[test]
+fn test_mode_dependent_fields_are_validated() {
+    assert_eq!(
+        violations("mode = \"sentinel\"\nhost = \"cache\"\n"),
+        ["host", "master_name", "nodes"]
+    );
+    assert_eq!(
+        violations("mode = \"cluster\"\nnodes = [\"n1\"]\ndatabase = 3\n"),
+        ["database", "nodes"]
+    );
+    assert_eq!(
+        violations("mode = \"single\"\nnodes = \"n1:7000\"\npool_size = 0\n"),
+        ["host", "nodes", "pool_size"]
+    );
+}
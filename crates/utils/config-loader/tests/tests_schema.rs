@@ -0,0 +1,188 @@
+use config_loader::{
+    Config, File, FileFormat, LayeredConfigLoader, Validate,
+    app_config::BaseAppConfig,
+    database::DatabaseConfig,
+    kafka::{Acks, KafkaConfig, SecurityProtocol},
+    schema::{self, JsonSchema, schemars::json_schema},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+mod common;
+use common::{validation_errors, violation_messages};
+
+/// Order service settings.
+#[derive(Debug, Deserialize, JsonSchema, Validate)]
+#[schemars(crate = "config_loader::schema::schemars")]
+struct Settings {
+    #[validate(nested)]
+    app: BaseAppConfig,
+    #[validate(nested)]
+    database: DatabaseConfig,
+    #[validate(nested)]
+    kafka: Option<KafkaConfig>,
+    /// Orders accepted per second.
+    #[serde(default)]
+    max_rate: u32,
+}
+
+#[test]
+fn test_example_files_are_documented_and_match_the_schema() {
+    let schema = schema::schema_for::<Settings>();
+    let toml = schema::example_toml(&schema);
+
+    assert!(toml.starts_with("# Settings\n# Order service settings.\n\n"));
+    assert!(toml.contains("# Orders accepted per second.\n# max_rate = 0\n"));
+    assert!(
+        toml.contains(
+            "\n[database]\n# One of: mssql, postgres, mysql, sqlite\nengine = \"mssql\"\n"
+        )
+    );
+    assert!(toml.contains("\n# [kafka]\n"));
+    assert!(toml.contains("\n# [kafka.security.sasl]\n"));
+
+    let yaml = schema::example_yaml(&schema);
+    assert!(yaml.contains("\napp:\n  name: \"\"\n"));
+    assert!(yaml.contains("\n# kafka:\n"));
+    assert!(yaml.contains(
+        "  # Bootstrap servers, as a list or a comma-separated string.\n  # servers: \"\"\n"
+    ));
+
+    for (text, format) in [(toml, FileFormat::Toml), (yaml, FileFormat::Yaml)] {
+        let config = Config::builder()
+            .add_source(File::from_str(&text, format))
+            .build()
+            .unwrap();
+        schema::validate_value(&schema, &config.cache).unwrap();
+    }
+}
+
+#[test]
+fn test_raw_values_are_checked_against_the_schema_before_deserializing() {
+    let errors = validation_errors(
+        LayeredConfigLoader::new("does-not-exist")
+            .defaults(
+                r#"
+            max_rate = -5
+
+            [app]
+            version = 3
+
+            [database]
+            engine = "postgres"
+            host = "positions-db"
+            port = "not a port"
+            username = "positions"
+            password = "secret"
+            database = "positions"
+            ssl_mode = "strict"
+
+            [kafka]
+            client_id = "oms"
+            enabled = true
+            servers = ["kafka-1:9092"]
+
+            [kafka.producer]
+            acks = 2
+            "#,
+                FileFormat::Toml,
+            )
+            .schema(schema::schema_for::<Settings>())
+            .load::<Settings>(),
+    );
+
+    assert_eq!(
+        violation_messages(&errors),
+        [
+            "app.name: is required",
+            "database.port: expected integer, got string",
+            "database.ssl_mode: must be one of disable, prefer, require, verify-ca, verify-full, got 'strict'",
+            "kafka.producer.acks: must be one of 0, 1, -1, none, leader, all, got '2'",
+            "max_rate: must be at least 0, got -5",
+        ]
+    );
+}
+
+#[test]
+fn test_environment_strings_pass_as_numbers() {
+    let settings: Settings = LayeredConfigLoader::new("does-not-exist")
+        .defaults(
+            "[app]\nname = \"oms\"\n\n[database]\nengine = \"sqlite\"\npath = \"oms.db\"\n",
+            FileFormat::Toml,
+        )
+        .env_prefix("APP")
+        .env_vars(HashMap::from([
+            ("APP_MAX_RATE".to_string(), "250".to_string()),
+            ("APP_APP__TIMEZONE".to_string(), "7".to_string()),
+            ("APP_DATABASE__READ_ONLY".to_string(), "true".to_string()),
+        ]))
+        .schema(schema::schema_for::<Settings>())
+        .load()
+        .unwrap();
+
+    assert_eq!(settings.max_rate, 250);
+    assert_eq!(settings.app.timezone, Some("+07:00".parse().unwrap()));
+}
+
+#[test]
+fn test_aliases_and_case_variants_pass_the_schema() {
+    let settings: Settings = LayeredConfigLoader::new("does-not-exist")
+        .defaults(
+            r#"
+            [app]
+            name = "oms"
+
+            [database]
+            engine = "sqlite"
+            path = "oms.db"
+
+            [kafka]
+            client_id = "oms"
+            enabled = true
+            servers = "kafka-1:9092"
+            security.protocol = "sasl_ssl"
+            security.sasl = { mechanism = "PLAIN", username = "oms", password = "secret" }
+            producer.acks = "Leader"
+            "#,
+            FileFormat::Toml,
+        )
+        .schema(schema::schema_for::<Settings>())
+        .load()
+        .unwrap();
+
+    let kafka = settings.kafka.unwrap();
+    assert_eq!(kafka.security.protocol, SecurityProtocol::SaslSsl);
+    assert_eq!(kafka.producer.unwrap().acks, Acks::Leader);
+
+    let protocols = schema::schema_for::<SecurityProtocol>();
+    assert!(
+        protocols
+            .get("enum")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .contains(&"sasl_ssl".into())
+    );
+}
+
+#[test]
+fn test_one_of_requires_exactly_one_match() {
+    let schema = json_schema!({
+        "properties": {
+            "qty": { "oneOf": [{ "type": "integer" }, { "type": "string" }] }
+        }
+    });
+    let config = |text: &str| {
+        Config::builder()
+            .add_source(File::from_str(text, FileFormat::Toml))
+            .build()
+            .unwrap()
+    };
+
+    let errors = schema::validate_value(&schema, &config("qty = \"5\"").cache).unwrap_err();
+    assert_eq!(
+        violation_messages(&errors),
+        ["qty: matches more than one of the allowed schemas"]
+    );
+    schema::validate_value(&schema, &config("qty = \"five\"").cache).unwrap();
+}
@@ -0,0 +1,190 @@
+use config_loader::{
+    AsyncConfigLoader, ConfigError, ConfigSource, FileFormat, HttpClientSettings,
+    LayeredConfigLoader, SecretError, SecretResolver, VaultSecretProvider, database::MssqlConfig,
+    load_config,
+};
+use std::fs;
+
+mod common;
+use common::MockServer;
+
+#[tokio::test]
+async fn test_vault_provider_reads_kv_v2_secret() {
+    let server = MockServer::start(vec![(
+        200,
+        vec![("content-type", "application/json")],
+        r#"{"data": {"data": {"db_password": "s3cr3t"}, "metadata": {"version": 3}}}"#,
+    )])
+    .await;
+    let mut settings = HttpClientSettings::default();
+    settings
+        .headers
+        .insert("X-Vault-Namespace".to_string(), "trading".to_string());
+    let vault = VaultSecretProvider::new(&server.url, "test-token")
+        .with_settings(&settings)
+        .unwrap();
+    let resolver = SecretResolver::new().with_async_provider(vault);
+
+    let resolved = resolver
+        .resolve_str("${vault:secret/data/oms#db_password}")
+        .await
+        .unwrap();
+    assert_eq!(resolved, "s3cr3t");
+
+    let request = &server.requests()[0];
+    assert!(request.starts_with("get /v1/secret/data/oms "));
+    assert!(request.contains("x-vault-token: test-token"));
+    assert!(request.contains("x-vault-namespace: trading"));
+}
+
+#[tokio::test]
+async fn test_resolved_password_is_redacted() {
+    let dir = std::env::temp_dir().join(format!("config-secrets-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let secret_file = dir.join("db_password");
+    fs::write(&secret_file, "hunter2\n").unwrap();
+
+    let defaults = format!(
+        r#"
+host = "db.internal"
+port = 1433
+username = "${{env:CARGO_PKG_NAME}}"
+password = "${{file:{}}}"
+database = "oms"
+"#,
+        secret_file.display()
+    );
+
+    let config: MssqlConfig = LayeredConfigLoader::new(&dir)
+        .defaults(defaults, FileFormat::Toml)
+        .build()
+        .unwrap()
+        .resolve_secrets(&SecretResolver::new())
+        .await
+        .unwrap()
+        .try_deserialize()
+        .unwrap();
+
+    assert_eq!(config.username, "config-loader");
+    assert_eq!(config.password.expose(), "hunter2");
+    assert!(!format!("{:?}", config).contains("hunter2"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_unknown_scheme_is_an_error() {
+    let err = SecretResolver::new()
+        .resolve_str("${aws:db/password}")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SecretError::UnknownProvider { scheme } if scheme == "aws"));
+
+    let config = config_loader::Config::builder()
+        .set_default("password", "${aws:db/password}")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(matches!(
+        SecretResolver::new().resolve_config(config).await,
+        Err(ConfigError::Foreign(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_loaders_resolve_placeholders() {
+    const DB: &str = r#"
+host = "db.internal"
+username = "${env:CARGO_PKG_NAME}"
+password = "${vault:secret/data/oms#db_password}"
+database = "oms"
+"#;
+    let server = MockServer::start(vec![(
+        200,
+        vec![("content-type", "application/json")],
+        r#"{"data": {"data": {"db_password": "s3cr3t"}}}"#,
+    )])
+    .await;
+    let vault = SecretResolver::new()
+        .with_async_provider(VaultSecretProvider::new(&server.url, "test-token"));
+
+    let (config, _) = AsyncConfigLoader::new()
+        .source(ConfigSource::layered(
+            LayeredConfigLoader::new("does-not-exist").defaults(DB, FileFormat::Toml),
+        ))
+        .secrets(vault.clone())
+        .load::<MssqlConfig>()
+        .await
+        .unwrap();
+    assert_eq!(config.username, "config-loader");
+    assert_eq!(config.password.expose(), "s3cr3t");
+
+    let loader = LayeredConfigLoader::new("does-not-exist")
+        .defaults(DB, FileFormat::Toml)
+        .set("password", "${file:does-not-exist}-${env:CARGO_PKG_NAME}");
+    let Err(ConfigError::Foreign(e)) = loader.load::<MssqlConfig>() else {
+        panic!("expected the missing secret file to fail the load");
+    };
+    assert!(matches!(
+        e.downcast_ref::<SecretError>(),
+        Some(SecretError::NotFound { reference }) if reference == "does-not-exist"
+    ));
+    let config: MssqlConfig = loader
+        .set("password", "${env:CARGO_PKG_NAME}")
+        .load()
+        .unwrap();
+    assert_eq!(config.username, "config-loader");
+    assert_eq!(config.password.expose(), "config-loader");
+
+    let Err(ConfigError::Foreign(e)) = LayeredConfigLoader::new("does-not-exist")
+        .defaults(DB, FileFormat::Toml)
+        .secrets(vault)
+        .load::<MssqlConfig>()
+    else {
+        panic!("expected the synchronous load to reject the vault placeholder");
+    };
+    assert!(matches!(
+        e.downcast_ref::<SecretError>(),
+        Some(SecretError::AsyncProvider { scheme }) if scheme == "vault"
+    ));
+}
+
+#[test]
+fn test_blocking_resolution_rejects_async_providers() {
+    let resolver = SecretResolver::new()
+        .with_async_provider(VaultSecretProvider::new("http://127.0.0.1:1", "test-token"));
+    assert_eq!(
+        resolver
+            .resolve_str_blocking("${env:CARGO_PKG_NAME}:${port}")
+            .unwrap(),
+        "config-loader:${port}"
+    );
+    let err = resolver
+        .resolve_str_blocking("${vault:secret/data/oms#db_password}")
+        .unwrap_err();
+    assert!(matches!(err, SecretError::AsyncProvider { scheme } if scheme == "vault"));
+}
+
+#[test]
+fn test_load_config_resolves_env_and_file_placeholders() {
+    let dir = std::env::temp_dir().join(format!("config-secrets-load-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let secret_file = dir.join("db_password");
+    fs::write(&secret_file, "hunter2\n").unwrap();
+    let path = dir.join("db.toml");
+    fs::write(
+        &path,
+        format!(
+            "host = \"db.internal\"\nusername = \"${{env:CARGO_PKG_NAME}}\"\n\
+             password = \"${{file:{}}}\"\ndatabase = \"oms\"\n",
+            secret_file.display()
+        ),
+    )
+    .unwrap();
+
+    let config: MssqlConfig = load_config(path.to_str().unwrap()).unwrap();
+    assert_eq!(config.username, "config-loader");
+    assert_eq!(config.password.expose(), "hunter2");
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,62 @@
+use config_loader::{MissingSection, ServiceConfig, Validate};
+use serde::Deserialize;
+
+mod common;
+use common::{load, violated_keys};
+
+#[derive(Debug, Deserialize, Validate)]
+struct Orders {
+    #[validate(range(min = 1))]
+    max_open_orders: u32,
+    #[serde(default)]
+    venues: Vec<String>,
+}
+
+#[test]
+fn test_standard_sections_and_extension() {
+    let config: ServiceConfig<Orders> = load(
+        r#"
+        max_open_orders = 500
+        venues = ["XNAS", "XNYS"]
+
+        [app]
+        name = "oms"
+
+        [redis]
+        mode = "single"
+        host = "cache.internal"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.app.name, "oms");
+    assert_eq!(config.logger.max_level, "INFO");
+    assert_eq!(config.ext.max_open_orders, 500);
+    assert_eq!(config.ext.venues, ["XNAS", "XNYS"]);
+    assert_eq!(config.redis().unwrap().url(), "redis://cache.internal:6379");
+
+    assert_eq!(
+        config.kafka().unwrap_err(),
+        MissingSection { section: "kafka" }
+    );
+    assert_eq!(
+        config.logger.otel().unwrap_err().to_string(),
+        "missing configuration section 'logger.otel'"
+    );
+}
+
+#[test]
+fn test_sections_and_extension_are_validated() {
+    let result = load::<ServiceConfig<Orders>>(
+        r#"
+        max_open_orders = 0
+
+        [app]
+        name = "oms"
+
+        [redis]
+        mode = "cluster"
+        "#,
+    );
+    assert_eq!(violated_keys(result), ["max_open_orders", "redis.nodes"]);
+}
@@ -0,0 +1,99 @@
+use config_loader::{
+    Config, SpringCloudConfigSource, app_config::BaseAppConfig, env::Env, remote::RemoteConfig,
+};
+use serde::Deserialize;
+
+mod common;
+use common::MockServer;
+
+const RESPONSE: &str = r#"{
+  "name": "oms",
+  "profiles": ["production"],
+  "label": "release/1.2",
+  "version": "8f2c1d",
+  "propertySources": [
+    {
+      "name": "git:oms-production.yml",
+      "source": {
+        "database.host": "db-prod.internal",
+        "kafka.servers[0]": "kafka-1:9092",
+        "kafka.servers[1]": "kafka-2:9092"
+      }
+    },
+    {
+      "name": "git:oms.yml",
+      "source": {
+        "database.host": "localhost",
+        "database.port": 1433,
+        "kafka.servers[0]": "localhost:9092",
+        "kafka.servers[1]": "localhost:9093",
+        "kafka.servers[2]": "localhost:9094"
+      }
+    }
+  ]
+}"#;
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    database: Database,
+    kafka: Kafka,
+}
+
+#[derive(Debug, Deserialize)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct Kafka {
+    servers: Vec<String>,
+}
+
+#[tokio::test]
+async fn test_property_sources_merge_in_precedence_order() {
+    let server = MockServer::start(vec![(200, vec![], RESPONSE)]).await;
+
+    let source = SpringCloudConfigSource::new(&server.url, "oms", "prod").label("release/1.2");
+    let settings: Settings = Config::builder()
+        .add_async_source(source)
+        .build()
+        .await
+        .unwrap()
+        .try_deserialize()
+        .unwrap();
+
+    assert_eq!(settings.database.host, "db-prod.internal");
+    assert_eq!(settings.database.port, 1433);
+    assert_eq!(settings.kafka.servers, ["kafka-1:9092", "kafka-2:9092"]);
+
+    assert!(server.requests()[0].starts_with("get /oms/prod/release(_)1.2 "));
+}
+
+#[tokio::test]
+async fn test_source_from_app_and_remote_config() {
+    let server = MockServer::start(vec![(200, vec![], r#"{"propertySources": []}"#)]).await;
+
+    let config = Config::builder()
+        .set_default("name", "risk-engine")
+        .unwrap()
+        .set_default("env", "stg")
+        .unwrap()
+        .set_default("config.url", server.url.as_str())
+        .unwrap()
+        .build()
+        .unwrap();
+    let app: BaseAppConfig = config.clone().try_deserialize().unwrap();
+    let remote: RemoteConfig = config.try_deserialize().unwrap();
+    assert_eq!(app.env, Some(Env::Staging));
+    assert_eq!(app.env_name(), Some("stg"));
+
+    let source = SpringCloudConfigSource::from_config(&remote, &app);
+    Config::builder()
+        .add_async_source(source)
+        .build()
+        .await
+        .unwrap();
+
+    assert!(server.requests()[0].starts_with("get /risk-engine/stg "));
+}
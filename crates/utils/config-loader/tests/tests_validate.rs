@@ -0,0 +1,127 @@
+use config_loader::{
+    Secret, Validate, database::MssqlConfig, kafka::KafkaConfig, load_config,
+    load_validated_config, logging::LoggerConfig,
+};
+use serde::Deserialize;
+use std::fs;
+
+mod common;
+use common::{config_dir, load, validation_errors, violated_keys};
+
+#[derive(Debug, Deserialize, Validate)]
+struct ServiceConfig {
+    #[validate(nested)]
+    database: MssqlConfig,
+    #[validate(nested)]
+    kafka: KafkaConfig,
+    #[validate(nested)]
+    logger: Option<LoggerConfig>,
+    #[serde(rename = "max-orders")]
+    #[validate(range(min = 1, max = 10_000))]
+    max_orders: u32,
+}
+
+const INVALID: &str = r#"
+max-orders = 0
+
+[database]
+host = "db.internal"
+port = 0
+username = "oms"
+password = "secret"
+database = "oms"
+pool_size = 2
+min_idle = 4
+
+[kafka]
+enabled = true
+client_id = "oms"
+servers = ""
+
+[logger]
+max_level = "INFO"
+
+[logger.otel]
+endpoint = "collector:4317"
+enabled = true
+"#;
+
+#[test]
+fn test_all_violations_are_reported_with_key_paths() {
+    let errors = validation_errors(load::<ServiceConfig>(INVALID));
+
+    let mut keys: Vec<_> = errors.violations.iter().map(|v| v.key.as_str()).collect();
+    keys.sort();
+    assert_eq!(
+        keys,
+        [
+            "database.pool_size",
+            "database.port",
+            "kafka.servers",
+            "logger.otel.endpoint",
+            "max-orders",
+        ]
+    );
+    assert!(
+        errors
+            .to_string()
+            .contains("database.port: must be at least 1, got 0")
+    );
+}
+
+#[test]
+fn test_valid_config_passes() {
+    let valid = INVALID
+        .replace("max-orders = 0", "max-orders = 100")
+        .replace("port = 0", "port = 1433")
+        .replace("min_idle = 4", "min_idle = 1")
+        .replace("servers = \"\"", "servers = \"kafka-1:9092, kafka-2:9092\"")
+        .replace("collector:4317", "http://collector:4317");
+
+    let config: ServiceConfig = load(&valid).unwrap();
+    assert_eq!(config.max_orders, 100);
+}
+
+#[test]
+fn test_load_config_only_validates_on_request() {
+    /// Doesn't implement [`Validate`].
+    #[derive(Debug, Deserialize)]
+    struct Orders {
+        #[serde(rename = "max-orders")]
+        max_orders: u32,
+    }
+
+    let dir = config_dir("load-validated");
+    let path = dir.join("oms.toml");
+    fs::write(&path, INVALID).unwrap();
+    let path = path.to_str().unwrap();
+
+    assert_eq!(load_config::<Orders>(path).unwrap().max_orders, 0);
+    assert_eq!(load_config::<ServiceConfig>(path).unwrap().max_orders, 0);
+    let errors = validation_errors(load_validated_config::<ServiceConfig>(path));
+    assert_eq!(errors.violations.len(), 5);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_keys_follow_serde_rename_all() {
+    #[allow(dead_code)]
+    #[derive(Debug, Deserialize, Validate)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    struct Venue {
+        #[validate(range(min = 1))]
+        max_order_qty: u32,
+        #[serde(rename(serialize = "id", deserialize = "venue-id"))]
+        #[validate(non_empty)]
+        venue_id: String,
+        api_key: Secret<String>,
+    }
+
+    let result = load::<Venue>("maxOrderQty = 0\nvenue-id = \"\"\napiKey = \"k\"\n");
+    assert_eq!(violated_keys(result), ["maxOrderQty", "venue-id"]);
+
+    let mut keys = Vec::new();
+    Venue::redacted_keys("venue", &mut keys);
+    assert_eq!(keys, ["venue.apiKey"]);
+}
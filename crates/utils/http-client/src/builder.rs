@@ -1,7 +1,9 @@
 #[cfg(feature = "tracing")]
 use crate::middleware::tracing_middleware;
-use reqwest::Client;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest::{Certificate, Client, Identity, Proxy};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware};
+use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use std::{sync::Arc, time::Duration};
 
 #[derive(Debug, Clone)]
 pub struct HttpClientBuilderConfig {
@@ -28,66 +30,201 @@ impl Default for HttpClientBuilderConfig {
         }
     }
 }
+
+/// Retry of transient failures: connection errors, timeouts, 408, 429 and 5xx responses.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on every attempt.
+    pub min_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            min_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
 pub struct HttpClientBuilder {
     inner: ClientBuilder,
+    /// Settings of the base client, `None` if it was built elsewhere.
+    base: Option<BaseClient>,
+    middleware: Vec<Arc<dyn Middleware>>,
 }
 
-impl HttpClientBuilder {
-    pub fn new(config: Option<HttpClientBuilderConfig>) -> Self {
-        let mut merged = HttpClientBuilderConfig::default();
-
-        if let Some(custom) = config {
-            merged.timeout = custom.timeout;
-            merged.connect_timeout = custom.connect_timeout;
-            merged.max_idle_per_host = custom.max_idle_per_host;
-            merged.default_headers = custom.default_headers;
-        }
+/// Settings the base client is rebuilt with when transport settings are added.
+#[derive(Default)]
+struct BaseClient {
+    config: HttpClientBuilderConfig,
+    proxy: Option<Proxy>,
+    root_certificates: Vec<Certificate>,
+    identity: Option<Identity>,
+    /// Whether transport settings were added since the client was built.
+    stale: bool,
+}
 
+impl BaseClient {
+    fn build(&self) -> Result<Client, reqwest::Error> {
+        let config = &self.config;
         let mut base = Client::builder();
 
-        if let Some(timeout) = merged.timeout {
+        if let Some(timeout) = config.timeout {
             base = base.timeout(timeout);
         }
 
-        if let Some(default_headers) = merged.default_headers {
-            base = base.default_headers(default_headers);
+        if let Some(default_headers) = &config.default_headers {
+            base = base.default_headers(default_headers.clone());
         }
 
-        if let Some(max_idle) = merged.max_idle_per_host {
+        if let Some(max_idle) = config.max_idle_per_host {
             base = base.pool_max_idle_per_host(max_idle);
         }
 
-        if let Some(connect_timeout) = merged.connect_timeout {
+        if let Some(connect_timeout) = config.connect_timeout {
             base = base.connect_timeout(connect_timeout);
         }
 
-        let client = base.build().expect("Failed to create base reqwest client");
-        Self {
+        if let Some(proxy) = &self.proxy {
+            base = base.proxy(proxy.clone());
+        }
+
+        for certificate in &self.root_certificates {
+            base = base.add_root_certificate(certificate.clone());
+        }
+
+        if let Some(identity) = &self.identity {
+            base = base.identity(identity.clone());
+        }
+
+        base.build()
+    }
+}
+
+impl HttpClientBuilder {
+    pub fn new(config: Option<HttpClientBuilderConfig>) -> Self {
+        Self::try_new(config).expect("Failed to create base reqwest client")
+    }
+
+    /// Like [`Self::new`], failing instead of panicking if the client can't be created.
+    pub fn try_new(config: Option<HttpClientBuilderConfig>) -> Result<Self, reqwest::Error> {
+        let mut merged = HttpClientBuilderConfig::default();
+
+        if let Some(custom) = config {
+            merged.timeout = custom.timeout;
+            merged.connect_timeout = custom.connect_timeout;
+            merged.max_idle_per_host = custom.max_idle_per_host;
+            merged.default_headers = custom.default_headers;
+        }
+
+        let base = BaseClient {
+            config: merged,
+            ..Default::default()
+        };
+        let client = base.build()?;
+        Ok(Self {
             inner: ClientBuilder::new(client),
+            base: Some(base),
+            middleware: Vec::new(),
+        })
+    }
+
+    /// Add middleware to an already built client, keeping its settings and middleware.
+    ///
+    /// The client can't be rebuilt, so transport settings such as [`Self::proxy`] can't be
+    /// added to it.
+    pub fn from_client(client: ClientWithMiddleware) -> Self {
+        Self {
+            inner: ClientBuilder::from_client(client),
+            base: None,
+            middleware: Vec::new(),
         }
     }
 
-    #[cfg(feature = "tracing")]
-    pub fn with_tracing(mut self) -> Self {
-        self.inner = self.inner.with(tracing_middleware());
+    /// Send every request through `proxy`, instead of the system proxy.
+    ///
+    /// # Panics
+    ///
+    /// If the builder was made with [`Self::from_client`], like the other transport settings.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.base_mut().proxy = Some(proxy);
+        self
+    }
+
+    /// Trust `certificate` in addition to the system roots.
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.base_mut().root_certificates.push(certificate);
         self
     }
 
+    /// Client certificate, for mutual TLS.
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.base_mut().identity = Some(identity);
+        self
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn with_tracing(self) -> Self {
+        self.push(Arc::new(tracing_middleware()))
+    }
+
+    /// Retry transient failures with exponential backoff
+    pub fn with_retry(self, retry: RetryConfig) -> Self {
+        let policy = ExponentialBackoff::builder()
+            .retry_bounds(retry.min_backoff, retry.max_backoff.max(retry.min_backoff))
+            .build_with_max_retries(retry.max_retries);
+        self.push(Arc::new(RetryTransientMiddleware::new_with_policy(policy)))
+    }
+
     // custom middleware
-    pub fn with_middleware<M>(mut self, middleware: M) -> Self
+    pub fn with_middleware<M>(self, middleware: M) -> Self
     where
         M: reqwest_middleware::Middleware + Send + Sync + 'static,
     {
-        self.inner = self.inner.with(middleware);
-        self
+        self.push(Arc::new(middleware))
     }
 
     /// build the final reqwest client with middleware
     pub fn build(self) -> ClientWithMiddleware {
-        self.inner.build()
+        self.try_build()
+            .expect("Failed to create base reqwest client")
     }
 
+    /// Like [`Self::build`], failing instead of panicking if the client can't be created
+    /// with the transport settings, e.g. with an unusable certificate.
+    pub fn try_build(self) -> Result<ClientWithMiddleware, reqwest::Error> {
+        match self.base {
+            Some(base) if base.stale => Ok(self
+                .middleware
+                .into_iter()
+                .fold(ClientBuilder::new(base.build()?), ClientBuilder::with_arc)
+                .build()),
+            _ => Ok(self.inner.build()),
+        }
+    }
+
+    /// Middleware builder, on a client without the transport settings, which are only
+    /// applied by [`Self::build`].
     pub fn inner(&self) -> &ClientBuilder {
         &self.inner
     }
+
+    fn push(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.inner = self.inner.with_arc(middleware.clone());
+        self.middleware.push(middleware);
+        self
+    }
+
+    fn base_mut(&mut self) -> &mut BaseClient {
+        let base = self
+            .base
+            .as_mut()
+            .expect("transport settings can't be added to a client built elsewhere");
+        base.stale = true;
+        base
+    }
 }
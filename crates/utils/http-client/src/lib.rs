@@ -1,6 +1,7 @@
 pub mod builder;
 pub mod middleware;
-pub use builder::HttpClientBuilder;
+pub use builder::{HttpClientBuilder, HttpClientBuilderConfig, RetryConfig};
 
 // Re-exports
+pub use reqwest::{Certificate, Identity, Proxy, header};
 pub use reqwest_middleware::ClientWithMiddleware;
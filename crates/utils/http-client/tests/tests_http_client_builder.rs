@@ -1,6 +1,6 @@
 use http::Extensions;
 use http_client::{
-    HttpClientBuilder,
+    HttpClientBuilder, Proxy,
     middleware::{tracing::TimeTrace, tracing_middleware},
 };
 use reqwest::{Request, Response};
@@ -97,3 +97,29 @@ async fn test_timetrace_lifecycle_no_panic() {
 
     TimeTrace::on_request_end(&span, &result, &mut ext);
 }
+
+#[tokio::test]
+async fn test_transport_settings_keep_middleware() {
+    let called = Arc::new(Mutex::new(false));
+    let dummy = DummyMiddleware {
+        called: called.clone(),
+    };
+
+    // nothing listens on the discard port, so requests fail if they go through the proxy
+    let client = HttpClientBuilder::new(None)
+        .with_middleware(dummy)
+        .proxy(Proxy::all("http://127.0.0.1:9").unwrap())
+        .try_build()
+        .unwrap();
+
+    let req = client.get("http://example.com").build().unwrap();
+    assert!(client.execute(req).await.is_err());
+    assert!(*called.lock().unwrap(), "Dummy middleware did not run");
+}
+
+#[test]
+#[should_panic(expected = "client built elsewhere")]
+fn test_transport_settings_need_a_configured_client() {
+    let client = HttpClientBuilder::new(None).build();
+    let _ = HttpClientBuilder::from_client(client).proxy(Proxy::all("http://127.0.0.1:9").unwrap());
+}
@@ -8,8 +8,8 @@ pub mod util;
 use crate::file::setup_file_appender;
 #[cfg(feature = "otel")]
 use crate::otel::setup_otel;
-pub use crate::util::{utc_offset_hms, utc_offset_hours};
-use config_loader::{app_config::BaseAppConfig, logging::LoggerConfig};
+pub use crate::util::{ZonedTime, utc_offset_hms, utc_offset_hours};
+use config_loader::{MissingSection, app_config::BaseAppConfig, logging::LoggerConfig};
 pub use time::UtcOffset;
 use time::{format_description::BorrowedFormatItem, macros::format_description};
 pub use tracing::{
@@ -19,7 +19,7 @@ pub use tracing::{
 
 #[cfg(feature = "otel")]
 use tracing_opentelemetry::OpenTelemetryLayer;
-use tracing_subscriber::{EnvFilter, Registry, fmt::time::OffsetTime, layer::SubscriberExt};
+use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt};
 
 #[cfg(feature = "sysinfo")]
 pub mod sysinfo;
@@ -34,8 +34,8 @@ pub enum LoggingError {
     },
     #[error("Failed to build otel exporter: {0}")]
     OtelExporterBuilderError(String),
-    #[error("Missing configuration: {0}")]
-    MissingConfigurationError(String),
+    #[error(transparent)]
+    MissingSection(#[from] MissingSection),
     #[error("Failed to get pid: {0}")]
     MissingPid(String),
 }
@@ -103,11 +103,7 @@ pub fn setup_logging(
         format_description!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]")
     };
 
-    let timezone = match app_config.timezone {
-        Some(offset) => utc_offset_hours(offset),
-        None => UtcOffset::UTC,
-    };
-    let timer = OffsetTime::new(timezone, fmt);
+    let timer = ZonedTime::new(app_config.timezone.unwrap_or_default(), fmt);
 
     let max_level = logger_config
         .max_level
@@ -128,11 +124,7 @@ pub fn setup_logging(
 
     #[cfg(feature = "otel")]
     let (registry, tracer_provider, logger_provider, meter_provider) = {
-        let otel_config = logger_config.otel.as_ref().ok_or_else(|| {
-            LoggingError::MissingConfigurationError(
-                "otel logger configuration is missing".to_string(),
-            )
-        })?;
+        let otel_config = logger_config.otel()?;
 
         let base = Registry::default();
         let (otel_layer, tracer_provider, logger_provider, meter_provider) =
@@ -155,11 +147,7 @@ pub fn setup_logging(
 
     #[cfg(feature = "file")]
     let (registry, file_guard) = {
-        let file_config = logger_config.file.as_ref().ok_or_else(|| {
-            LoggingError::MissingConfigurationError(
-                "file logger configuration is missing".to_string(),
-            )
-        })?;
+        let file_config = logger_config.file()?;
 
         let (non_blocking, guard) = setup_file_appender(app_config.clone(), file_config.clone())?;
         let file_layer = tracing_subscriber::fmt::Layer::default()
@@ -1,4 +1,7 @@
-use time::UtcOffset;
+use config_loader::timezone::Timezone;
+use std::fmt;
+use time::{OffsetDateTime, UtcOffset, format_description::BorrowedFormatItem};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
 
 /// Helper function to create a UtcOffset from hours
 ///
@@ -29,3 +32,36 @@ pub fn utc_offset_hours(hours: i8) -> UtcOffset {
 pub fn utc_offset_hms(hours: i8, minutes: i8, seconds: i8) -> UtcOffset {
     UtcOffset::from_hms(hours, minutes, seconds).expect("Invalid UTC offset")
 }
+
+/// Event timestamps in a [`Timezone`], following its DST changes for named zones.
+///
+/// # Examples
+///
+/// ```
+/// use logger::util::ZonedTime;
+/// use time::macros::format_description;
+///
+/// let timer = ZonedTime::new(
+///     "America/New_York".parse().unwrap(),
+///     format_description!("[hour]:[minute]:[second]"),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZonedTime<'a> {
+    zone: Timezone,
+    format: &'a [BorrowedFormatItem<'a>],
+}
+
+impl<'a> ZonedTime<'a> {
+    pub fn new(zone: Timezone, format: &'a [BorrowedFormatItem<'a>]) -> Self {
+        Self { zone, format }
+    }
+}
+
+impl FormatTime for ZonedTime<'_> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = self.zone.to_local(OffsetDateTime::now_utc());
+        let formatted = now.format(self.format).map_err(|_| fmt::Error)?;
+        w.write_str(&formatted)
+    }
+}
@@ -0,0 +1,206 @@
+use std::{any::Any, borrow::Cow, panic::AssertUnwindSafe, sync::Arc};
+
+use async_trait::async_trait;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use crate::{RunnableTask, TaskError, TaskErrorKind, TaskResult, core_allocator::assigned_core};
+
+/// Trait for tasks that block the calling thread (CPU-bound work, synchronous client libraries).
+///
+/// Wrap the task in a [`BlockingTaskAdapter`] and register the adapter like any other task.
+pub trait BlockingTask: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    /// Main execution - runs off the async executor.
+    ///
+    /// Blocking code can't be interrupted, so check `token.is_cancelled()` regularly
+    /// to stop within the configured shutdown timeout.
+    fn run(&self, token: CancellationToken) -> TaskResult<()>;
+
+    /// Optional cleanup after shutdown.
+    fn on_shutdown(&self) -> TaskResult<()> {
+        Ok(())
+    }
+}
+
+/// Where a [`BlockingTask`] is executed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlockingMode {
+    /// Spawn a dedicated OS thread named after the task, pinned to the task's core.
+    #[default]
+    DedicatedThread,
+
+    /// Run on tokio's blocking thread pool.
+    ///
+    /// Pool threads are shared, so they are never pinned. Note that the runtime waits
+    /// for blocking pool work to finish when it is dropped.
+    BlockingPool,
+}
+
+/// Runs a [`BlockingTask`] as a [`RunnableTask`].
+pub struct BlockingTaskAdapter<T: BlockingTask> {
+    task: Arc<T>,
+    mode: BlockingMode,
+}
+
+impl<T: BlockingTask> BlockingTaskAdapter<T> {
+    /// Run the task on a dedicated thread.
+    pub fn new(task: T) -> Self {
+        Self::with_mode(task, BlockingMode::default())
+    }
+
+    pub fn with_mode(task: T, mode: BlockingMode) -> Self {
+        Self {
+            task: Arc::new(task),
+            mode,
+        }
+    }
+
+    async fn execute<F>(&self, f: F) -> TaskResult<()>
+    where
+        F: FnOnce(&T) -> TaskResult<()> + Send + 'static,
+    {
+        let task = self.task.clone();
+
+        let outcome = match self.mode {
+            BlockingMode::DedicatedThread => {
+                let core = assigned_core();
+                let (tx, rx) = oneshot::channel();
+
+                std::thread::Builder::new()
+                    .name(self.task.name().to_string())
+                    .spawn(move || {
+                        if let Some(core) = core {
+                            core_affinity::set_for_current(core);
+                        }
+                        let _ = tx.send(std::panic::catch_unwind(AssertUnwindSafe(|| f(&task))));
+                    })
+                    .map_err(|e| {
+                        TaskError::new(
+                            self.task.name(),
+                            TaskErrorKind::StartupFailed {
+                                message: format!("failed to spawn thread: {}", e).into(),
+                            },
+                        )
+                    })?;
+
+                rx.await
+                    .map_err(|e| TaskError::execution(self.task.name(), e))?
+            }
+
+            BlockingMode::BlockingPool => tokio::task::spawn_blocking(move || {
+                std::panic::catch_unwind(AssertUnwindSafe(|| f(&task)))
+            })
+            .await
+            .map_err(|e| TaskError::execution(self.task.name(), e))?,
+        };
+
+        outcome.unwrap_or_else(|payload| {
+            Err(TaskError::panic(self.task.name(), panic_message(payload)))
+        })
+    }
+}
+
+#[async_trait]
+impl<T: BlockingTask> RunnableTask for BlockingTaskAdapter<T> {
+    fn name(&self) -> &str {
+        self.task.name()
+    }
+
+    async fn run(&self, token: CancellationToken) -> TaskResult<()> {
+        self.execute(move |task| task.run(token)).await
+    }
+
+    async fn on_shutdown(&self) -> TaskResult<()> {
+        self.execute(|task| task.on_shutdown()).await
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> Cow<'static, str> {
+    match payload.downcast::<String>() {
+        Ok(message) => Cow::Owned(*message),
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => Cow::Borrowed(*message),
+            Err(_) => Cow::Borrowed("unknown panic payload"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskManager, task_manager::TaskManagerConfig};
+
+    struct ThreadNameTask;
+
+    impl BlockingTask for ThreadNameTask {
+        fn name(&self) -> &str {
+            "fix_session"
+        }
+
+        fn run(&self, _token: CancellationToken) -> TaskResult<()> {
+            assert_eq!(std::thread::current().name(), Some("fix_session"));
+            Ok(())
+        }
+    }
+
+    struct PanickingTask;
+
+    impl BlockingTask for PanickingTask {
+        fn name(&self) -> &str {
+            "risk_recalc"
+        }
+
+        fn run(&self, _token: CancellationToken) -> TaskResult<()> {
+            panic!("matrix is singular");
+        }
+    }
+
+    struct QuickTask;
+
+    impl BlockingTask for QuickTask {
+        fn name(&self) -> &str {
+            "quick"
+        }
+
+        fn run(&self, _token: CancellationToken) -> TaskResult<()> {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dedicated_thread_is_named_after_task() {
+        let adapter = BlockingTaskAdapter::new(ThreadNameTask);
+        assert!(adapter.run(CancellationToken::new()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_panic_is_captured_as_task_error() {
+        for mode in [BlockingMode::DedicatedThread, BlockingMode::BlockingPool] {
+            let adapter = BlockingTaskAdapter::with_mode(PanickingTask, mode);
+            let err = adapter.run(CancellationToken::new()).await.unwrap_err();
+
+            assert_eq!(err.task_name, "risk_recalc");
+            assert!(
+                matches!(err.kind, TaskErrorKind::Panic { ref message } if message == "matrix is singular")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registers_through_task_manager() {
+        let mut manager = TaskManager::new(TaskManagerConfig {
+            catch_signals: false,
+            ..Default::default()
+        });
+        manager.register(BlockingTaskAdapter::new(QuickTask));
+        manager.register(BlockingTaskAdapter::with_mode(
+            QuickTask,
+            BlockingMode::BlockingPool,
+        ));
+
+        assert!(manager.run().await.is_ok());
+    }
+}
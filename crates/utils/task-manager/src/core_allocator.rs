@@ -1,4 +1,10 @@
-use std::collections::HashMap;
+use core_affinity::CoreId;
+use logger::{error, info};
+use std::{collections::HashMap, future::Future};
+
+tokio::task_local! {
+    static ASSIGNED_CORE: Option<CoreId>;
+}
 
 /// Core pinning config for a task and/or worker group.
 #[derive(Debug, Clone)]
@@ -173,3 +179,76 @@ impl CoreAllocator {
         Err(errors)
     }
 }
+
+/// Pin the current thread according to `affinity` and return the core it was pinned to.
+pub(crate) fn pin_current_thread(
+    task_name: &str,
+    affinity: &CoreAffinityConfig,
+    instance_index: Option<usize>,
+) -> Option<CoreId> {
+    let core_ids = core_affinity::get_core_ids()?;
+
+    match affinity {
+        CoreAffinityConfig::None => None,
+
+        CoreAffinityConfig::Fixed(id) => {
+            // fixed always works regardless of instance_index
+            if let Some(core) = core_ids.into_iter().find(|c| c.id == *id) {
+                core_affinity::set_for_current(core);
+                info!(task = %task_name, core = id, "pinned to specific core");
+                Some(core)
+            } else {
+                error!(task = %task_name, core = id, "requested core not available");
+                None
+            }
+        }
+
+        CoreAffinityConfig::Range { start, end } => {
+            let range: Vec<_> = core_ids
+                .into_iter()
+                .filter(|c| (*start..=*end).contains(&c.id))
+                .collect();
+
+            // for multi-instance: distribute across range, for single instance: use first core
+            let core = match instance_index {
+                Some(i) => i.checked_rem(range.len()).and_then(|i| range.get(i)),
+                None => range.first(),
+            };
+            if let Some(&core) = core {
+                core_affinity::set_for_current(core);
+                info!(task = %task_name, core = core.id, instance = ?instance_index, "pinned to core in range");
+                Some(core)
+            } else {
+                error!(task = %task_name, "no cores in requested range {}-{}", start, end);
+                None
+            }
+        }
+
+        CoreAffinityConfig::Auto => {
+            if let Some(i) = instance_index {
+                // for multi-instance: round-robin across all cores
+                let core = *core_ids.get(i.checked_rem(core_ids.len())?)?;
+                core_affinity::set_for_current(core);
+                info!(task = %task_name, core = core.id, instance = i, "auto-pinned by index");
+                Some(core)
+            } else {
+                // for single instance: Auto doesn't make sense, warn and skip
+                error!(task = %task_name, "Auto affinity not supported for single instance tasks, use Fixed or Range");
+                None
+            }
+        }
+    }
+}
+
+/// Run `fut` with `core` recorded as the core assigned to the current task.
+pub(crate) async fn with_assigned_core<F: Future>(core: Option<CoreId>, fut: F) -> F::Output {
+    ASSIGNED_CORE.scope(core, fut).await
+}
+
+/// Core the supervisor pinned the current task to, if any.
+///
+/// Adapters that move work off the async executor use this to pin their own thread
+/// to the same core.
+pub fn assigned_core() -> Option<CoreId> {
+    ASSIGNED_CORE.try_with(|core| *core).ok().flatten()
+}
@@ -114,6 +114,48 @@ impl ShutdownError {
     }
 }
 
+/// Error returned when controlling tasks through a [`crate::TaskManagerHandle`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ControlError {
+    /// No task or factory group with this name has been started.
+    #[error("no task or group named '{name}'")]
+    NotFound { name: String },
+
+    /// The task didn't opt into pause/resume.
+    #[error("task '{task_name}' does not support pause/resume")]
+    NotPausable { task_name: String },
+}
+
+/// Error returned when a job can't be queued on a [`crate::WorkerPool`]. Carries the job back.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SubmitError<J> {
+    /// The queue is full.
+    #[error("worker pool queue is full")]
+    Full(J),
+
+    /// The pool's workers have shut down.
+    #[error("worker pool is shut down")]
+    Closed(J),
+}
+
+impl<J> SubmitError<J> {
+    /// The job that couldn't be queued.
+    pub fn into_inner(self) -> J {
+        match self {
+            Self::Full(job) | Self::Closed(job) => job,
+        }
+    }
+
+    pub(crate) fn map<U>(self, f: impl FnOnce(J) -> U) -> SubmitError<U> {
+        match self {
+            Self::Full(job) => SubmitError::Full(f(job)),
+            Self::Closed(job) => SubmitError::Closed(f(job)),
+        }
+    }
+}
+
 impl From<TaskErrorKind> for TaskError {
     fn from(kind: TaskErrorKind) -> Self {
         Self {
@@ -127,6 +169,8 @@ pub type TaskResult<T> = Result<T, TaskError>;
 
 pub type ShutdownResult<T> = Result<T, ShutdownError>;
 
+pub type ControlResult<T> = Result<T, ControlError>;
+
 #[cfg(test)]
 mod tests {
     use std::error::Error;
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    error::{ControlError, ControlResult},
+    report::{ShutdownCause, ShutdownState},
+    status::{StatusRegistry, TaskStatus},
+};
+
+/// Cloneable handle for controlling a running [`crate::TaskManager`].
+#[derive(Debug, Clone)]
+pub struct TaskManagerHandle {
+    pub(crate) shutdown_token: CancellationToken,
+    pub(crate) shutdown_state: Arc<ShutdownState>,
+    pub(crate) status: StatusRegistry,
+    pub(crate) scale_tx: mpsc::UnboundedSender<(String, usize)>,
+}
+
+impl TaskManagerHandle {
+    /// Request a graceful shutdown of all tasks.
+    pub fn shutdown(&self) {
+        self.shutdown_state.record(ShutdownCause::Requested);
+        self.shutdown_token.cancel();
+    }
+
+    /// Whether a shutdown has been requested, for any reason.
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.shutdown_token.is_cancelled()
+    }
+
+    /// Pause the task called `name`, or every task of the factory group `name`.
+    ///
+    /// Only tasks that opted in via [`crate::RunnableTask::pausable`] can be paused.
+    pub fn pause(&self, name: &str) -> ControlResult<()> {
+        self.status.set_paused(name, true)
+    }
+
+    /// Resume the task or factory group called `name`.
+    pub fn resume(&self, name: &str) -> ControlResult<()> {
+        self.status.set_paused(name, false)
+    }
+
+    /// Run `instances` instances of the factory group `name`, starting new ones from its
+    /// factory or shutting down the highest-indexed ones.
+    ///
+    /// Applied asynchronously by the running manager.
+    pub fn scale(&self, name: &str, instances: usize) -> ControlResult<()> {
+        if !self.status.has_group(name) {
+            return Err(ControlError::NotFound {
+                name: name.to_string(),
+            });
+        }
+        // the manager is gone once the run is over, nothing left to scale
+        let _ = self.scale_tx.send((name.to_string(), instances));
+        Ok(())
+    }
+
+    /// Current status of every started task.
+    pub fn status(&self) -> Vec<TaskStatus> {
+        self.status.snapshot()
+    }
+
+    /// Shared status registry, e.g. for health endpoints.
+    pub fn status_registry(&self) -> &StatusRegistry {
+        &self.status
+    }
+}
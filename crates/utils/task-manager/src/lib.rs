@@ -1,8 +1,24 @@
 pub use tokio_util::sync::CancellationToken;
+pub mod blocking;
+pub use blocking::{BlockingMode, BlockingTask, BlockingTaskAdapter};
 pub mod error;
-pub use error::{ShutdownError, ShutdownResult, TaskError, TaskErrorKind, TaskResult};
+pub use error::{
+    ControlError, ControlResult, ShutdownError, ShutdownResult, SubmitError, TaskError,
+    TaskErrorKind, TaskResult,
+};
+pub mod handle;
+pub use handle::TaskManagerHandle;
+pub mod pause;
+pub use pause::{PausableTask, PausableTaskAdapter, PauseToken};
+pub mod pool;
+pub use pool::{Backpressure, JobHandler, WorkerPool, WorkerPoolConfig, WorkerPoolMetrics};
+pub mod report;
+pub use report::{ShutdownCause, ShutdownReport};
+mod signal;
+pub mod status;
+pub use status::{StatusRegistry, TaskState, TaskStatus};
 pub use task_manager::TaskManager;
-pub use tasks::RunnableTask;
+pub use tasks::{FnTask, RunnableTask, StatefulFnTask};
 pub mod core_allocator;
 pub mod task_manager;
 pub mod tasks;
@@ -0,0 +1,218 @@
+use std::future::Future;
+
+use async_trait::async_trait;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::{RunnableTask, TaskResult};
+
+tokio::task_local! {
+    static PAUSE_TOKEN: PauseToken;
+}
+
+/// Pause-aware token handed to tasks that opt into pause/resume.
+///
+/// Pausing is cooperative: tasks check the token between units of work and wait
+/// for [`PauseToken::resumed`] before sending more.
+#[derive(Debug, Clone)]
+pub struct PauseToken {
+    rx: watch::Receiver<bool>,
+}
+
+impl PauseToken {
+    pub(crate) fn new() -> (watch::Sender<bool>, Self) {
+        let (tx, rx) = watch::channel(false);
+        (tx, Self { rx })
+    }
+
+    /// Token that is never paused, for running pausable tasks outside a manager.
+    pub fn never() -> Self {
+        Self::new().1
+    }
+
+    /// Pause token of the current task, if it was started as pausable by the manager.
+    pub fn current() -> Option<Self> {
+        PAUSE_TOKEN.try_with(|token| token.clone()).ok()
+    }
+
+    /// Whether the task is currently paused.
+    pub fn is_paused(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Wait until the task is paused.
+    pub async fn paused(&self) {
+        let mut rx = self.rx.clone();
+        if rx.wait_for(|paused| *paused).await.is_err() {
+            // manager is gone, nobody can pause us anymore
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Wait until the task is resumed. Returns immediately if it isn't paused.
+    pub async fn resumed(&self) {
+        let mut rx = self.rx.clone();
+        let _ = rx.wait_for(|paused| !*paused).await;
+    }
+}
+
+/// Run `fut` with `token` as the current task's pause token.
+pub(crate) async fn with_pause_token<F: Future>(token: Option<PauseToken>, fut: F) -> F::Output {
+    match token {
+        Some(token) => PAUSE_TOKEN.scope(token, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Trait for tasks that can be paused and resumed without being torn down.
+///
+/// Wrap the task in a [`PausableTaskAdapter`] and register the adapter like any other task.
+#[async_trait]
+pub trait PausableTask: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    /// Main execution - receives a cancellation token and a pause token.
+    ///
+    /// The PauseToken provides:
+    /// - `pause.is_paused()` - Check if the task should hold off
+    /// - `pause.paused().await` - Wait for a pause request
+    /// - `pause.resumed().await` - Wait until the task may continue
+    async fn run(&self, token: CancellationToken, pause: PauseToken) -> TaskResult<()>;
+
+    /// Optional cleanup after shutdown.
+    async fn on_shutdown(&self) -> TaskResult<()> {
+        Ok(())
+    }
+}
+
+/// Runs a [`PausableTask`] as a [`RunnableTask`].
+pub struct PausableTaskAdapter<T: PausableTask> {
+    task: T,
+}
+
+impl<T: PausableTask> PausableTaskAdapter<T> {
+    pub fn new(task: T) -> Self {
+        Self { task }
+    }
+}
+
+#[async_trait]
+impl<T: PausableTask> RunnableTask for PausableTaskAdapter<T> {
+    fn name(&self) -> &str {
+        self.task.name()
+    }
+
+    async fn run(&self, token: CancellationToken) -> TaskResult<()> {
+        let pause = PauseToken::current().unwrap_or_else(PauseToken::never);
+        self.task.run(token, pause).await
+    }
+
+    async fn on_shutdown(&self) -> TaskResult<()> {
+        self.task.on_shutdown().await
+    }
+
+    fn pausable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ControlError, TaskManager, TaskState, task_manager::TaskManagerConfig};
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    };
+
+    struct OrderSender {
+        saw_pause: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl PausableTask for OrderSender {
+        fn name(&self) -> &str {
+            "order_sender"
+        }
+
+        async fn run(&self, token: CancellationToken, pause: PauseToken) -> TaskResult<()> {
+            pause.paused().await;
+            self.saw_pause.store(true, Ordering::SeqCst);
+            tokio::select! {
+                _ = pause.resumed() => token.cancelled().await,
+                _ = token.cancelled() => {}
+            }
+            Ok(())
+        }
+    }
+
+    fn manager() -> TaskManager {
+        TaskManager::new(TaskManagerConfig {
+            catch_signals: false,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_group() {
+        let saw_pause = Arc::new(AtomicBool::new(false));
+        let mut manager = manager();
+
+        let s = saw_pause.clone();
+        manager.register_factory(
+            "senders",
+            move || {
+                Arc::new(PausableTaskAdapter::new(OrderSender {
+                    saw_pause: s.clone(),
+                }))
+            },
+            2,
+        );
+
+        let handle = manager.handle();
+        let run = tokio::spawn(manager.run());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        handle.pause("senders").unwrap();
+        assert!(handle.status().iter().all(|s| s.state == TaskState::Paused));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(saw_pause.load(Ordering::SeqCst));
+
+        handle.resume("senders-1").unwrap();
+        assert_eq!(
+            handle.status_registry().get("senders-1").unwrap().state,
+            TaskState::Running
+        );
+
+        handle.shutdown();
+        assert!(run.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pause_rejects_non_pausable_tasks() {
+        let mut manager = manager();
+        manager.spawn_fn("heartbeat", |token| async move {
+            token.cancelled().await;
+            Ok(())
+        });
+
+        let handle = manager.handle();
+        let run = tokio::spawn(manager.run());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert!(matches!(
+            handle.pause("heartbeat"),
+            Err(ControlError::NotPausable { .. })
+        ));
+        assert!(matches!(
+            handle.pause("missing"),
+            Err(ControlError::NotFound { .. })
+        ));
+
+        handle.shutdown();
+        assert!(run.await.unwrap().is_ok());
+    }
+}
@@ -0,0 +1,561 @@
+use std::{
+    future::Future,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use crossbeam_channel::{Receiver, Sender, TryRecvError, TrySendError};
+use logger::{error, info, warn};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    PauseToken, RunnableTask, TaskResult, core_allocator::CoreAffinityConfig, error::SubmitError,
+};
+
+/// What [`WorkerPool::submit`] does when the queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Wait until a worker frees up space.
+    #[default]
+    Block,
+
+    /// Reject the new job.
+    DropNewest,
+
+    /// Discard the oldest queued job to make room for the new one.
+    DropOldest,
+}
+
+/// Configuration for a [`WorkerPool`].
+#[derive(Debug, Clone)]
+pub struct WorkerPoolConfig {
+    /// Factory group name; workers are named `{name}-{index}`.
+    pub name: String,
+
+    /// Number of workers started, see [`crate::TaskManagerHandle::scale`] to change it.
+    pub workers: usize,
+
+    /// Maximum number of queued jobs (at least 1).
+    pub capacity: usize,
+
+    pub backpressure: Backpressure,
+
+    /// Core placement of the workers.
+    pub affinity: CoreAffinityConfig,
+}
+
+impl WorkerPoolConfig {
+    pub fn new(name: impl Into<String>, workers: usize, capacity: usize) -> Self {
+        Self {
+            name: name.into(),
+            workers,
+            capacity,
+            backpressure: Backpressure::default(),
+            affinity: CoreAffinityConfig::None,
+        }
+    }
+}
+
+/// Processes jobs taken from a [`WorkerPool`] queue.
+#[async_trait]
+pub trait JobHandler<J>: Send + Sync + 'static {
+    /// Handle a single job on worker `worker`.
+    ///
+    /// Errors are logged and counted; the worker keeps going.
+    async fn handle(&self, worker: usize, job: J) -> TaskResult<()>;
+}
+
+#[async_trait]
+impl<J, F, Fut> JobHandler<J> for F
+where
+    J: Send + 'static,
+    F: Fn(usize, J) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = TaskResult<()>> + Send + 'static,
+{
+    async fn handle(&self, worker: usize, job: J) -> TaskResult<()> {
+        (self)(worker, job).await
+    }
+}
+
+/// Point-in-time metrics of a [`WorkerPool`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WorkerPoolMetrics {
+    pub queue_depth: usize,
+    pub capacity: usize,
+    pub submitted: u64,
+    pub processed: u64,
+    pub failed: u64,
+    pub dropped: u64,
+    /// Average time jobs spent queued before a worker picked them up.
+    pub avg_queue_latency: Duration,
+    pub max_queue_latency: Duration,
+    /// Average time spent in [`JobHandler::handle`].
+    pub avg_processing_time: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    submitted: AtomicU64,
+    processed: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+    queue_latency_ns: AtomicU64,
+    max_queue_latency_ns: AtomicU64,
+    processing_ns: AtomicU64,
+}
+
+struct Queued<J> {
+    job: J,
+    enqueued_at: Instant,
+}
+
+struct Shared<J> {
+    config: WorkerPoolConfig,
+    tx: Sender<Queued<J>>,
+    rx: Receiver<Queued<J>>,
+    job_available: Notify,
+    space_available: Notify,
+    closed: AtomicBool,
+    /// Guards opening and closing the queue as workers start and stop.
+    live_workers: Mutex<usize>,
+    counters: Counters,
+}
+
+impl<J> Shared<J> {
+    /// Stop accepting jobs, and count the ones still queued as dropped.
+    fn close(&self) {
+        if !self.closed.swap(true, Ordering::SeqCst) {
+            self.space_available.notify_waiters();
+        }
+
+        let dropped = self.rx.try_iter().count() as u64;
+        if dropped > 0 {
+            self.counters.dropped.fetch_add(dropped, Ordering::Relaxed);
+            warn!(pool = %self.config.name, dropped, "worker pool closed with jobs still queued");
+        }
+    }
+
+    /// Reopen the queue, e.g. when scaling back up from zero workers.
+    fn worker_started(&self) {
+        let mut live = self.live_workers.lock().unwrap();
+        *live += 1;
+        self.closed.store(false, Ordering::SeqCst);
+    }
+
+    fn worker_stopped(&self) {
+        let mut live = self.live_workers.lock().unwrap();
+        *live -= 1;
+        // the queue outlives workers stopped one by one, e.g. when scaling down
+        if *live == 0 {
+            self.close();
+        }
+    }
+
+    fn enqueued(&self) {
+        self.counters.submitted.fetch_add(1, Ordering::Relaxed);
+        self.job_available.notify_one();
+    }
+}
+
+/// Bounded job queue drained by supervised workers.
+///
+/// Workers are registered as a factory group through [`crate::TaskManager::register_pool`],
+/// so they get the same core placement, status reporting and pause/shutdown handling as
+/// any other factory task, and are scaled with [`crate::TaskManagerHandle::scale`].
+///
+/// The queue closes once the last worker stops, and reopens when one starts again; jobs
+/// still queued when it closes are counted as dropped.
+pub struct WorkerPool<J> {
+    shared: Arc<Shared<J>>,
+    handler: Arc<dyn JobHandler<J>>,
+}
+
+impl<J> Clone for WorkerPool<J> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            handler: self.handler.clone(),
+        }
+    }
+}
+
+impl<J: Send + 'static> WorkerPool<J> {
+    pub fn new<H: JobHandler<J>>(config: WorkerPoolConfig, handler: H) -> Self {
+        // a zero-capacity channel only hands over to a waiting receiver, which workers never are
+        let (tx, rx) = crossbeam_channel::bounded(config.capacity.max(1));
+
+        Self {
+            shared: Arc::new(Shared {
+                config,
+                tx,
+                rx,
+                job_available: Notify::new(),
+                space_available: Notify::new(),
+                closed: AtomicBool::new(false),
+                live_workers: Mutex::new(0),
+                counters: Counters::default(),
+            }),
+            handler: Arc::new(handler),
+        }
+    }
+
+    pub fn config(&self) -> &WorkerPoolConfig {
+        &self.shared.config
+    }
+
+    /// Queue a job, applying the configured [`Backpressure`] when the queue is full.
+    pub async fn submit(&self, job: J) -> Result<(), SubmitError<J>> {
+        let mut job = self.queued(job);
+
+        loop {
+            let space_available = self.shared.space_available.notified();
+            tokio::pin!(space_available);
+            space_available.as_mut().enable();
+
+            match self.try_enqueue(job) {
+                Err(SubmitError::Full(rejected))
+                    if self.shared.config.backpressure == Backpressure::Block =>
+                {
+                    job = rejected
+                }
+                result => return result.map_err(|e| e.map(|q| q.job)),
+            }
+
+            if self.shared.closed.load(Ordering::SeqCst) {
+                return Err(SubmitError::Closed(job.job));
+            }
+            space_available.await;
+        }
+    }
+
+    /// Queue a job without waiting. [`Backpressure::Block`] returns [`SubmitError::Full`].
+    pub fn try_submit(&self, job: J) -> Result<(), SubmitError<J>> {
+        self.try_enqueue(self.queued(job))
+            .map_err(|e| e.map(|q| q.job))
+    }
+
+    pub fn metrics(&self) -> WorkerPoolMetrics {
+        let c = &self.shared.counters;
+        let processed = c.processed.load(Ordering::Relaxed);
+        let handled = processed + c.failed.load(Ordering::Relaxed);
+        let avg =
+            |total_ns: u64| Duration::from_nanos(total_ns.checked_div(handled).unwrap_or_default());
+
+        WorkerPoolMetrics {
+            queue_depth: self.shared.rx.len(),
+            capacity: self.shared.config.capacity,
+            submitted: c.submitted.load(Ordering::Relaxed),
+            processed,
+            failed: c.failed.load(Ordering::Relaxed),
+            dropped: c.dropped.load(Ordering::Relaxed),
+            avg_queue_latency: avg(c.queue_latency_ns.load(Ordering::Relaxed)),
+            max_queue_latency: Duration::from_nanos(c.max_queue_latency_ns.load(Ordering::Relaxed)),
+            avg_processing_time: avg(c.processing_ns.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Worker task for instance `index`, used by the factory registration.
+    pub(crate) fn worker(&self, index: usize) -> Arc<dyn RunnableTask> {
+        Arc::new(PoolWorker {
+            name: format!("{}-{}", self.shared.config.name, index),
+            index,
+            pool: self.clone(),
+        })
+    }
+
+    fn queued(&self, job: J) -> Queued<J> {
+        Queued {
+            job,
+            enqueued_at: Instant::now(),
+        }
+    }
+
+    fn try_enqueue(&self, mut job: Queued<J>) -> Result<(), SubmitError<Queued<J>>> {
+        let shared = &self.shared;
+
+        if shared.closed.load(Ordering::SeqCst) {
+            return Err(SubmitError::Closed(job));
+        }
+
+        loop {
+            match shared.tx.try_send(job) {
+                Ok(()) => {
+                    shared.enqueued();
+                    return Ok(());
+                }
+                Err(TrySendError::Disconnected(rejected)) => {
+                    return Err(SubmitError::Closed(rejected));
+                }
+                Err(TrySendError::Full(rejected)) => match shared.config.backpressure {
+                    Backpressure::Block => return Err(SubmitError::Full(rejected)),
+                    Backpressure::DropNewest => {
+                        shared.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                        return Err(SubmitError::Full(rejected));
+                    }
+                    Backpressure::DropOldest => {
+                        if shared.rx.try_recv().is_ok() {
+                            shared.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        job = rejected;
+                    }
+                },
+            }
+        }
+    }
+
+    async fn work(&self, index: usize, token: CancellationToken, pause: Option<PauseToken>) {
+        let shared = &self.shared;
+        shared.worker_started();
+
+        loop {
+            if let Some(pause) = &pause
+                && pause.is_paused()
+            {
+                tokio::select! {
+                    _ = pause.resumed() => {}
+                    _ = token.cancelled() => break,
+                }
+            }
+
+            match shared.rx.try_recv() {
+                Ok(queued) => {
+                    shared.space_available.notify_one();
+                    if !shared.rx.is_empty() {
+                        // let an idle worker pick up the rest
+                        shared.job_available.notify_one();
+                    }
+                    self.process(index, queued).await;
+
+                    if token.is_cancelled() {
+                        break;
+                    }
+                }
+                Err(TryRecvError::Empty) => {
+                    tokio::select! {
+                        _ = shared.job_available.notified() => {}
+                        _ = token.cancelled() => break,
+                    }
+                }
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+
+        shared.worker_stopped();
+    }
+
+    async fn process(&self, index: usize, queued: Queued<J>) {
+        let counters = &self.shared.counters;
+
+        let waited = queued.enqueued_at.elapsed().as_nanos() as u64;
+        counters
+            .queue_latency_ns
+            .fetch_add(waited, Ordering::Relaxed);
+        counters
+            .max_queue_latency_ns
+            .fetch_max(waited, Ordering::Relaxed);
+
+        let started = Instant::now();
+        let result = self.handler.handle(index, queued.job).await;
+        counters
+            .processing_ns
+            .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+
+        match result {
+            Ok(()) => counters.processed.fetch_add(1, Ordering::Relaxed),
+            Err(e) => {
+                error!(pool = %self.shared.config.name, worker = index, error = %e, "job failed");
+                counters.failed.fetch_add(1, Ordering::Relaxed)
+            }
+        };
+    }
+}
+
+struct PoolWorker<J> {
+    name: String,
+    index: usize,
+    pool: WorkerPool<J>,
+}
+
+#[async_trait]
+impl<J: Send + 'static> RunnableTask for PoolWorker<J> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, token: CancellationToken) -> TaskResult<()> {
+        info!(task = %self.name, "worker started");
+        self.pool
+            .work(self.index, token, PauseToken::current())
+            .await;
+        Ok(())
+    }
+
+    fn pausable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskManager, task_manager::TaskManagerConfig};
+
+    fn pool(capacity: usize, backpressure: Backpressure) -> WorkerPool<u32> {
+        let mut config = WorkerPoolConfig::new("quotes", 2, capacity);
+        config.backpressure = backpressure;
+        WorkerPool::new(config, |_worker, _job: u32| async { Ok(()) })
+    }
+
+    #[test]
+    fn test_drop_newest_rejects_when_full() {
+        let pool = pool(2, Backpressure::DropNewest);
+
+        assert!(pool.try_submit(1).is_ok());
+        assert!(pool.try_submit(2).is_ok());
+        assert!(matches!(pool.try_submit(3), Err(SubmitError::Full(3))));
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics.queue_depth, 2);
+        assert_eq!(metrics.dropped, 1);
+    }
+
+    #[test]
+    fn test_drop_oldest_makes_room() {
+        let pool = pool(2, Backpressure::DropOldest);
+
+        for job in 1..=3 {
+            assert!(pool.try_submit(job).is_ok());
+        }
+
+        let queued: Vec<u32> = pool.shared.rx.try_iter().map(|q| q.job).collect();
+        assert_eq!(queued, vec![2, 3]);
+        assert_eq!(pool.metrics().dropped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_workers_drain_queue() {
+        let pool = pool(4, Backpressure::Block);
+        let mut manager = TaskManager::new(TaskManagerConfig {
+            catch_signals: false,
+            ..Default::default()
+        });
+        manager.register_pool(&pool);
+
+        let handle = manager.handle();
+        let run = tokio::spawn(manager.run());
+
+        for job in 0..20 {
+            pool.submit(job).await.unwrap();
+        }
+        while pool.metrics().processed < 20 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        assert_eq!(handle.status().len(), 2);
+        handle.shutdown();
+        assert!(run.await.unwrap().is_ok());
+        assert!(matches!(
+            pool.submit(99).await,
+            Err(SubmitError::Closed(99))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_scaling_keeps_queue_open_and_shutdown_counts_leftovers() {
+        let pool = pool(8, Backpressure::Block);
+        let mut manager = TaskManager::new(TaskManagerConfig {
+            catch_signals: false,
+            ..Default::default()
+        });
+        manager.register_pool(&pool);
+
+        let handle = manager.handle();
+        let run = tokio::spawn(manager.run());
+        while handle.status().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        handle.scale("quotes", 1).unwrap();
+        while !handle.status().iter().any(|s| s.state.is_terminal()) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        pool.submit(1).await.unwrap();
+        while pool.metrics().processed < 1 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        handle.scale("quotes", 3).unwrap();
+        while handle.status().len() < 3 || handle.status().iter().any(|s| s.state.is_terminal()) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        // the stopped instance is replaced by the one started under its name
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(handle.status().len(), 3);
+        let restarted = handle.status_registry().get("quotes-1").unwrap();
+        assert!(!restarted.state.is_terminal());
+        assert!(handle.scale("orders", 1).is_err());
+
+        handle.pause("quotes").unwrap();
+        for job in 0..3 {
+            pool.submit(job).await.unwrap();
+        }
+        handle.shutdown();
+        assert!(run.await.unwrap().is_ok());
+        assert_eq!(pool.metrics().dropped, 3);
+        assert_eq!(pool.metrics().queue_depth, 0);
+    }
+
+    #[tokio::test]
+    async fn test_scaling_up_from_zero_reopens_queue() {
+        let pool = pool(8, Backpressure::Block);
+        let mut manager = TaskManager::new(TaskManagerConfig {
+            catch_signals: false,
+            ..Default::default()
+        });
+        manager.register_pool(&pool);
+        // keeps the run going while the pool has no workers
+        manager.spawn_fn("feed", |token| async move {
+            token.cancelled().await;
+            Ok(())
+        });
+
+        let handle = manager.handle();
+        let run = tokio::spawn(manager.run());
+        while handle.status().len() < 3 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        handle.scale("quotes", 0).unwrap();
+        while !handle
+            .status()
+            .iter()
+            .filter(|s| s.group.is_some())
+            .all(|s| s.state.is_terminal())
+        {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(matches!(pool.try_submit(1), Err(SubmitError::Closed(1))));
+
+        handle.scale("quotes", 2).unwrap();
+        let reopened = async {
+            while let Err(SubmitError::Closed(_)) = pool.try_submit(2) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), reopened)
+            .await
+            .expect("queue still closed after scaling up");
+        while pool.metrics().processed < 1 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        handle.shutdown();
+        assert!(run.await.unwrap().is_ok());
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use logger::{LoggingGuard, error, info, warn};
+
+use crate::{
+    error::{ShutdownError, ShutdownResult},
+    status::{TaskState, TaskStatus},
+};
+
+/// What initiated the shutdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShutdownCause {
+    /// An OS signal was received (e.g. `"SIGTERM"`).
+    Signal(&'static str),
+
+    /// A task failed and `shutdown_on_error` is enabled.
+    TaskFailed { task_name: String },
+
+    /// Shutdown was requested through a [`crate::TaskManagerHandle`].
+    Requested,
+
+    /// Every task finished on its own.
+    Completed,
+
+    /// Tasks were never started, e.g. because of an invalid core allocation.
+    StartupFailed,
+}
+
+/// Records the first shutdown cause and when it was recorded.
+#[derive(Debug)]
+pub(crate) struct ShutdownState {
+    cause: Mutex<Option<(ShutdownCause, Instant)>>,
+}
+
+impl ShutdownState {
+    pub(crate) fn new() -> Self {
+        Self {
+            cause: Mutex::new(None),
+        }
+    }
+
+    /// Record `cause` unless another cause was recorded first.
+    pub(crate) fn record(&self, cause: ShutdownCause) {
+        let mut current = self.cause.lock().unwrap();
+        if current.is_none() {
+            *current = Some((cause, Instant::now()));
+        }
+    }
+
+    pub(crate) fn cause(&self) -> Option<(ShutdownCause, Instant)> {
+        self.cause.lock().unwrap().clone()
+    }
+}
+
+/// Outcome of [`crate::TaskManager::run_with_report`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ShutdownReport {
+    pub cause: ShutdownCause,
+    /// Final status of every task, in start order.
+    pub tasks: Vec<TaskStatus>,
+    /// Time from `run` being called until every task stopped.
+    pub uptime: Duration,
+    /// Time from the shutdown request until every task stopped.
+    pub shutdown_duration: Option<Duration>,
+    pub error: Option<ShutdownError>,
+}
+
+impl ShutdownReport {
+    /// Whether every task stopped cleanly.
+    pub fn is_clean(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Conventional process exit code for this shutdown.
+    ///
+    /// - `1` if a task failed or tasks couldn't be started
+    /// - `2` if the shutdown timed out
+    /// - `130`/`143` after SIGINT/SIGTERM (`128 + signal number`)
+    /// - `0` otherwise
+    pub fn exit_code(&self) -> i32 {
+        match &self.error {
+            Some(ShutdownError::Timeout { .. }) => 2,
+            Some(_) => 1,
+            None => match self.cause {
+                ShutdownCause::Signal("SIGINT" | "CTRL_C" | "CTRL_BREAK") => 130,
+                ShutdownCause::Signal(_) => 143,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Log the shutdown cause, every task's final state and any errors.
+    pub fn log(&self) {
+        info!(
+            cause = ?self.cause,
+            uptime = ?self.uptime,
+            shutdown_duration = ?self.shutdown_duration,
+            "task manager stopped"
+        );
+
+        for task in &self.tasks {
+            let duration = task.run_duration();
+            match task.state {
+                TaskState::Failed | TaskState::Panicked | TaskState::TimedOut => {
+                    warn!(task = %task.name, state = ?task.state, ?duration, error = ?task.error, "task did not stop cleanly")
+                }
+                _ => info!(task = %task.name, state = ?task.state, ?duration, "task stopped"),
+            }
+        }
+
+        if let Some(e) = &self.error {
+            error!(error = %e, exit_code = self.exit_code(), "shutdown finished with errors");
+            if let ShutdownError::SubsystemsFailed { failures } = e {
+                for failure in failures {
+                    error!("  {}", failure);
+                }
+            }
+        }
+    }
+
+    /// Convert into the plain result returned by [`crate::TaskManager::run`].
+    pub fn into_result(self) -> ShutdownResult<()> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Log the report, flush logging and exit the process with [`Self::exit_code`].
+    pub fn log_and_exit(self, guard: LoggingGuard) -> ! {
+        self.log();
+        let code = self.exit_code();
+        drop(guard);
+        std::process::exit(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskError, TaskManager, task_manager::TaskManagerConfig};
+
+    fn manager(shutdown_timeout: Duration) -> TaskManager {
+        TaskManager::new(TaskManagerConfig {
+            catch_signals: false,
+            shutdown_timeout,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_report_for_handle_request() {
+        let mut manager = manager(Duration::from_secs(1));
+        manager.spawn_fn("waiter", |token| async move {
+            token.cancelled().await;
+            Ok(())
+        });
+
+        let handle = manager.handle();
+        let run = tokio::spawn(manager.run_with_report());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handle.shutdown();
+
+        let report = run.await.unwrap();
+        assert_eq!(report.cause, ShutdownCause::Requested);
+        assert_eq!(report.exit_code(), 0);
+        assert!(report.shutdown_duration.is_some());
+        assert_eq!(report.tasks[0].state, TaskState::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_report_for_task_failure() {
+        let mut manager = manager(Duration::from_secs(1));
+        manager.spawn_fn("failing", |_token| async {
+            Err(TaskError::execution("failing", "boom"))
+        });
+
+        let report = manager.run_with_report().await;
+        assert_eq!(
+            report.cause,
+            ShutdownCause::TaskFailed {
+                task_name: "failing".into()
+            }
+        );
+        assert_eq!(report.exit_code(), 1);
+        assert_eq!(report.tasks[0].state, TaskState::Failed);
+
+        let Some(ShutdownError::SubsystemsFailed { failures }) = &report.error else {
+            panic!("expected subsystem failure, got {:?}", report.error);
+        };
+        assert_eq!(failures[0].task_name, "failing");
+    }
+
+    #[tokio::test]
+    async fn test_report_for_shutdown_timeout() {
+        let mut manager = manager(Duration::from_millis(20));
+        manager.spawn_fn("stubborn", |_token| async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        });
+
+        let handle = manager.handle();
+        handle.shutdown();
+
+        let report = manager.run_with_report().await;
+        assert_eq!(report.exit_code(), 2);
+        assert_eq!(report.tasks[0].state, TaskState::TimedOut);
+    }
+
+    #[test]
+    fn test_exit_code_for_signals() {
+        let report = |signal| ShutdownReport {
+            cause: ShutdownCause::Signal(signal),
+            tasks: Vec::new(),
+            uptime: Duration::ZERO,
+            shutdown_duration: None,
+            error: None,
+        };
+
+        assert_eq!(report("SIGINT").exit_code(), 130);
+        assert_eq!(report("SIGTERM").exit_code(), 143);
+    }
+}
@@ -0,0 +1,66 @@
+use logger::{error, warn};
+
+/// Wait for a shutdown signal and return its name.
+///
+/// Falls back to ctrl-c if the handlers can't be registered.
+#[cfg(unix)]
+pub(crate) async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let handlers = (|| {
+        std::io::Result::Ok((
+            signal(SignalKind::terminate())?,
+            signal(SignalKind::interrupt())?,
+        ))
+    })();
+
+    match handlers {
+        Ok((mut terminate, mut interrupt)) => tokio::select! {
+            _ = terminate.recv() => "SIGTERM",
+            _ = interrupt.recv() => "SIGINT",
+        },
+        Err(e) => {
+            warn!(error = %e, "failed to register signal handlers, falling back to ctrl-c");
+            ctrl_c("SIGINT").await
+        }
+    }
+}
+
+/// Wait for a shutdown signal and return its name.
+///
+/// Falls back to ctrl-c if the handlers can't be registered.
+#[cfg(windows)]
+pub(crate) async fn wait_for_signal() -> &'static str {
+    use tokio::signal::windows;
+
+    let handlers = (|| {
+        std::io::Result::Ok((
+            windows::ctrl_c()?,
+            windows::ctrl_break()?,
+            windows::ctrl_close()?,
+            windows::ctrl_shutdown()?,
+        ))
+    })();
+
+    match handlers {
+        Ok((mut ctrl_c, mut ctrl_break, mut ctrl_close, mut ctrl_shutdown)) => tokio::select! {
+            _ = ctrl_c.recv() => "CTRL_C",
+            _ = ctrl_break.recv() => "CTRL_BREAK",
+            _ = ctrl_close.recv() => "CTRL_CLOSE",
+            _ = ctrl_shutdown.recv() => "CTRL_SHUTDOWN",
+        },
+        Err(e) => {
+            warn!(error = %e, "failed to register signal handlers, falling back to ctrl-c");
+            ctrl_c("CTRL_C").await
+        }
+    }
+}
+
+/// Wait for ctrl-c, or forever if it can't be listened for either.
+async fn ctrl_c(name: &'static str) -> &'static str {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!(error = %e, "failed to listen for ctrl-c, shutdown signals are ignored");
+        std::future::pending::<()>().await;
+    }
+    name
+}
@@ -0,0 +1,297 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::watch;
+
+use crate::{
+    error::{ControlError, ControlResult},
+    pause::PauseToken,
+};
+
+/// Lifecycle state of a supervised task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaskState {
+    /// Registered but not started yet.
+    Pending,
+    Running,
+    /// Paused through a [`crate::TaskManagerHandle`].
+    Paused,
+    /// Returned `Ok(())`.
+    Stopped,
+    /// Returned an error.
+    Failed,
+    Panicked,
+    /// Still running when the shutdown timeout elapsed.
+    TimedOut,
+}
+
+impl TaskState {
+    /// Whether the task has finished, one way or another.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(
+            self,
+            TaskState::Pending | TaskState::Running | TaskState::Paused
+        )
+    }
+}
+
+/// Point-in-time status of a single task.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TaskStatus {
+    pub name: String,
+    /// Factory group the task was created by, if any.
+    pub group: Option<String>,
+    pub state: TaskState,
+    pub pausable: bool,
+    pub started_at: Option<Instant>,
+    pub stopped_at: Option<Instant>,
+    /// Display of the error the task failed with, if any.
+    pub error: Option<String>,
+}
+
+impl TaskStatus {
+    /// How long the task ran, up to now if it is still running.
+    pub fn run_duration(&self) -> Option<Duration> {
+        let started = self.started_at?;
+        Some(self.stopped_at.unwrap_or_else(Instant::now) - started)
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    id: usize,
+    status: TaskStatus,
+    pause: Option<watch::Sender<bool>>,
+}
+
+impl Entry {
+    fn set_paused(&mut self, paused: bool) {
+        if let Some(pause) = &self.pause {
+            pause.send_replace(paused);
+        }
+
+        match (self.status.state, paused) {
+            (TaskState::Running, true) => self.status.state = TaskState::Paused,
+            (TaskState::Paused, false) => self.status.state = TaskState::Running,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    list: Vec<Entry>,
+    next_id: usize,
+}
+
+/// Shared registry of task statuses, updated by the supervisor.
+#[derive(Debug, Clone, Default)]
+pub struct StatusRegistry {
+    inner: Arc<Mutex<Entries>>,
+}
+
+impl StatusRegistry {
+    /// Statuses of all tasks, in start order.
+    pub fn snapshot(&self) -> Vec<TaskStatus> {
+        self.inner
+            .lock()
+            .unwrap()
+            .list
+            .iter()
+            .map(|e| e.status.clone())
+            .collect()
+    }
+
+    /// Status of the latest task started with the given name.
+    pub fn get(&self, name: &str) -> Option<TaskStatus> {
+        self.inner
+            .lock()
+            .unwrap()
+            .list
+            .iter()
+            .rfind(|e| e.status.name == name)
+            .map(|e| e.status.clone())
+    }
+
+    /// Whether a task of the factory group `name` has been started.
+    pub(crate) fn has_group(&self, name: &str) -> bool {
+        self.inner
+            .lock()
+            .unwrap()
+            .list
+            .iter()
+            .any(|e| e.status.group.as_deref() == Some(name))
+    }
+
+    /// Add a task and return its slot, plus a pause token if it is pausable.
+    ///
+    /// A finished task with the same name, e.g. an instance of a group scaled down and
+    /// back up, is replaced; one still stopping is removed once it finishes.
+    pub(crate) fn register(
+        &self,
+        name: impl Into<String>,
+        group: Option<&str>,
+        pausable: bool,
+    ) -> (usize, Option<PauseToken>) {
+        let (pause, token) = if pausable {
+            let (tx, token) = PauseToken::new();
+            (Some(tx), Some(token))
+        } else {
+            (None, None)
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        // instances added to a paused group start paused
+        if let (Some(pause), Some(group)) = (&pause, group) {
+            let mut siblings = inner
+                .list
+                .iter()
+                .filter(|e| e.status.group.as_deref() == Some(group))
+                .peekable();
+            if siblings.peek().is_some()
+                && siblings.all(|e| e.pause.as_ref().is_some_and(|p| *p.borrow()))
+            {
+                pause.send_replace(true);
+            }
+        }
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let entry = Entry {
+            id,
+            status: TaskStatus {
+                name: name.into(),
+                group: group.map(str::to_string),
+                state: TaskState::Pending,
+                pausable,
+                started_at: None,
+                stopped_at: None,
+                error: None,
+            },
+            pause,
+        };
+        match inner
+            .list
+            .iter_mut()
+            .find(|e| e.status.name == entry.status.name && e.status.state.is_terminal())
+        {
+            Some(finished) => *finished = entry,
+            None => inner.list.push(entry),
+        }
+        (id, token)
+    }
+
+    pub(crate) fn mark_running(&self, slot: usize) {
+        self.update(slot, |e| {
+            let paused = e.pause.as_ref().is_some_and(|p| *p.borrow());
+            e.status.state = if paused {
+                TaskState::Paused
+            } else {
+                TaskState::Running
+            };
+            e.status.started_at = Some(Instant::now());
+        });
+    }
+
+    pub(crate) fn mark_finished(&self, slot: usize, error: Option<String>) {
+        self.update(slot, |e| {
+            e.status.state = if error.is_some() {
+                TaskState::Failed
+            } else {
+                TaskState::Stopped
+            };
+            e.status.stopped_at = Some(Instant::now());
+            e.status.error = error;
+        });
+        self.remove_superseded(slot);
+    }
+
+    pub(crate) fn mark_panicked(&self, name: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(e) = inner
+            .list
+            .iter_mut()
+            .find(|e| e.status.name == name && !e.status.state.is_terminal())
+        {
+            e.status.state = TaskState::Panicked;
+            e.status.stopped_at = Some(Instant::now());
+            let id = e.id;
+            drop(inner);
+            self.remove_superseded(id);
+        }
+    }
+
+    /// Mark every task that hasn't finished as timed out.
+    pub(crate) fn mark_unfinished_timed_out(&self) {
+        let now = Instant::now();
+        for e in self.inner.lock().unwrap().list.iter_mut() {
+            if !e.status.state.is_terminal() && e.status.state != TaskState::Pending {
+                e.status.state = TaskState::TimedOut;
+                e.status.stopped_at = Some(now);
+            }
+        }
+    }
+
+    /// Pause or resume the task called `name`, or every task of the factory group `name`.
+    ///
+    /// Fails without changing anything if any matching task isn't pausable.
+    pub(crate) fn set_paused(&self, name: &str, paused: bool) -> ControlResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+
+        let mut matching: Vec<&mut Entry> = inner
+            .list
+            .iter_mut()
+            .filter(|e| e.status.name == name || e.status.group.as_deref() == Some(name))
+            .collect();
+
+        if matching.is_empty() {
+            return Err(ControlError::NotFound {
+                name: name.to_string(),
+            });
+        }
+
+        if let Some(e) = matching.iter().find(|e| e.pause.is_none()) {
+            return Err(ControlError::NotPausable {
+                task_name: e.status.name.clone(),
+            });
+        }
+
+        for e in matching.iter_mut() {
+            e.set_paused(paused);
+        }
+
+        Ok(())
+    }
+
+    fn update(&self, slot: usize, f: impl FnOnce(&mut Entry)) {
+        if let Some(e) = self
+            .inner
+            .lock()
+            .unwrap()
+            .list
+            .iter_mut()
+            .find(|e| e.id == slot)
+        {
+            f(e);
+        }
+    }
+
+    /// Drop the finished task `slot` if a task with the same name was started after it.
+    fn remove_superseded(&self, slot: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(pos) = inner.list.iter().position(|e| e.id == slot) else {
+            return;
+        };
+        let name = &inner.list[pos].status.name;
+        if inner
+            .list
+            .iter()
+            .any(|e| e.id > slot && e.status.name == *name)
+        {
+            inner.list.remove(pos);
+        }
+    }
+}
@@ -1,12 +1,26 @@
 pub use crate::error::{ShutdownError, ShutdownResult, TaskError, TaskErrorKind, TaskResult};
 use crate::{
     RunnableTask,
-    core_allocator::{CoreAffinityConfig, CoreAllocator},
+    core_allocator::{CoreAffinityConfig, CoreAllocator, pin_current_thread, with_assigned_core},
+    handle::TaskManagerHandle,
+    pause::{PauseToken, with_pause_token},
+    pool::WorkerPool,
+    report::{ShutdownCause, ShutdownReport, ShutdownState},
+    signal::wait_for_signal,
+    status::StatusRegistry,
+    tasks::{FnTask, StatefulFnTask},
 };
 use logger::{error, info, warn};
-use std::{fmt::Debug, sync::Arc, time::Duration};
+use std::{
+    fmt::Debug,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
 use tokio_graceful_shutdown::{
-    SubsystemBuilder, SubsystemHandle, Toplevel, errors::GracefulShutdownError,
+    NestedSubsystem, SubsystemBuilder, SubsystemHandle, Toplevel,
+    errors::{GracefulShutdownError, SubsystemError},
 };
 pub use tokio_util::sync::CancellationToken;
 
@@ -42,15 +56,36 @@ pub struct TaskManager {
     task_affinities: Vec<CoreAffinityConfig>,
     config: TaskManagerConfig,
     factories: Vec<TaskFactory>,
+    shutdown_token: CancellationToken,
+    shutdown_state: Arc<ShutdownState>,
+    status: StatusRegistry,
+    scale_tx: mpsc::UnboundedSender<(String, usize)>,
+    scale_rx: mpsc::UnboundedReceiver<(String, usize)>,
 }
 
 impl TaskManager {
     pub fn new(config: TaskManagerConfig) -> Self {
+        let (scale_tx, scale_rx) = mpsc::unbounded_channel();
         Self {
             tasks: Vec::new(),
             task_affinities: Vec::new(),
             config,
             factories: Vec::new(),
+            shutdown_token: CancellationToken::new(),
+            shutdown_state: Arc::new(ShutdownState::new()),
+            status: StatusRegistry::default(),
+            scale_tx,
+            scale_rx,
+        }
+    }
+
+    /// Handle for requesting shutdown and inspecting task status while the manager runs.
+    pub fn handle(&self) -> TaskManagerHandle {
+        TaskManagerHandle {
+            shutdown_token: self.shutdown_token.clone(),
+            shutdown_state: self.shutdown_state.clone(),
+            status: self.status.clone(),
+            scale_tx: self.scale_tx.clone(),
         }
     }
 
@@ -75,17 +110,31 @@ impl TaskManager {
         self.task_affinities.push(affinity);
     }
 
+    /// Register a closure as a task.
+    pub fn spawn_fn<F, Fut>(&mut self, name: impl Into<String>, f: F)
+    where
+        F: Fn(CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TaskResult<()>> + Send + 'static,
+    {
+        self.register(FnTask::new(name, f));
+    }
+
+    /// Register a closure as a task that receives shared, typed state.
+    pub fn spawn_fn_with_state<S, F, Fut>(&mut self, name: impl Into<String>, state: Arc<S>, f: F)
+    where
+        S: Send + Sync + 'static,
+        F: Fn(Arc<S>, CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = TaskResult<()>> + Send + 'static,
+    {
+        self.register(StatefulFnTask::new(name, state, f));
+    }
+
     /// Register a factory for creating multiple instances of a task.
     pub fn register_factory<F>(&mut self, name: impl Into<String>, factory: F, instances: usize)
     where
         F: Fn() -> Arc<dyn RunnableTask> + Send + Sync + 'static,
     {
-        self.factories.push(TaskFactory {
-            name: name.into(),
-            factory: Arc::new(factory),
-            instances,
-            affinity: CoreAffinityConfig::None,
-        });
+        self.register_indexed_factory(name, move |_| factory(), instances);
     }
 
     /// Register a factory for creating multiple instances of a task and pinned to specific core.
@@ -97,6 +146,36 @@ impl TaskManager {
         affinity: CoreAffinityConfig,
     ) where
         F: Fn() -> Arc<dyn RunnableTask> + Send + Sync + 'static,
+    {
+        self.register_indexed_factory_with_affinity(name, move |_| factory(), instances, affinity);
+    }
+
+    /// Register a factory that receives the instance index (`0..instances`), e.g. to shard work.
+    pub fn register_indexed_factory<F>(
+        &mut self,
+        name: impl Into<String>,
+        factory: F,
+        instances: usize,
+    ) where
+        F: Fn(usize) -> Arc<dyn RunnableTask> + Send + Sync + 'static,
+    {
+        self.register_indexed_factory_with_affinity(
+            name,
+            factory,
+            instances,
+            CoreAffinityConfig::None,
+        );
+    }
+
+    /// Register a factory that receives the instance index and pinned to specific core.
+    pub fn register_indexed_factory_with_affinity<F>(
+        &mut self,
+        name: impl Into<String>,
+        factory: F,
+        instances: usize,
+        affinity: CoreAffinityConfig,
+    ) where
+        F: Fn(usize) -> Arc<dyn RunnableTask> + Send + Sync + 'static,
     {
         self.factories.push(TaskFactory {
             name: name.into(),
@@ -106,23 +185,66 @@ impl TaskManager {
         });
     }
 
+    /// Register the workers of a [`WorkerPool`] as a factory group.
+    pub fn register_pool<J: Send + 'static>(&mut self, pool: &WorkerPool<J>) {
+        let config = pool.config();
+        let (name, workers, affinity) =
+            (config.name.clone(), config.workers, config.affinity.clone());
+        let pool = pool.clone();
+
+        self.register_indexed_factory_with_affinity(
+            name,
+            move |i| pool.worker(i),
+            workers,
+            affinity,
+        );
+    }
+
     /// Start all tasks and wait for shutdown.
     pub async fn run(self) -> ShutdownResult<()> {
-        if self.config.validate_core_allocation {
-            self.validate_allocations()?;
+        self.run_with_report().await.into_result()
+    }
+
+    /// Start all tasks, wait for shutdown and report how it went.
+    pub async fn run_with_report(self) -> ShutdownReport {
+        let started_at = Instant::now();
+
+        if self.config.validate_core_allocation
+            && let Err(e) = self.validate_allocations()
+        {
+            return ShutdownReport {
+                cause: ShutdownCause::StartupFailed,
+                tasks: self.status.snapshot(),
+                uptime: started_at.elapsed(),
+                shutdown_duration: None,
+                error: Some(e),
+            };
         }
 
         let tasks = self.tasks;
         let factories = self.factories;
         let config = self.config;
         let affinities = self.task_affinities;
+        let status = self.status;
+        let shutdown_state = self.shutdown_state;
+        let shutdown_token = self.shutdown_token;
+        let mut scale_rx = self.scale_rx;
+
+        let supervision = Supervision {
+            status: status.clone(),
+            shutdown_state: shutdown_state.clone(),
+            shutdown_on_error: config.shutdown_on_error,
+        };
 
-        let toplevel_fn = move |subsys: &mut SubsystemHandle| {
+        let toplevel_fn = async move |subsys: &mut SubsystemHandle| {
             // single instance tasks
             for (i, task) in tasks.into_iter().enumerate() {
                 let task_clone = task.clone();
-                let task_config = config;
                 let affinity = affinities[i].clone();
+                let supervision = supervision.clone();
+                let (slot, pause) = supervision
+                    .status
+                    .register(task.name(), None, task.pausable());
 
                 subsys.start(SubsystemBuilder::new(
                     task.name(),
@@ -132,87 +254,120 @@ impl TaskManager {
                         let token = subsys.create_cancellation_token();
 
                         async move {
-                            supervise(
+                            let run = TaskRun {
+                                slot,
                                 name,
-                                t,
-                                token,
-                                task_config.shutdown_on_error,
+                                task: t,
                                 affinity,
-                                Some(i),
-                            )
-                            .await
+                                instance_index: Some(i),
+                                pause: pause.clone(),
+                            };
+                            supervise(supervision, run, token).await
                         }
                     },
                 ));
             }
 
             // multiple tasks intsance from factories
-            for reg in factories {
-                let factory = reg.factory.clone();
-                let group_name = reg.name.clone();
-                let factory_config = config;
-                let affinity = reg.affinity.clone();
-
-                for i in 0..reg.instances {
-                    let task = (factory)();
-                    let task_name = format!("{}-{}", group_name, i);
-                    let t = task.clone();
-                    let affinity = affinity.clone();
-
-                    subsys.start(SubsystemBuilder::new(
-                        task_name.clone(),
-                        move |subsys: &mut SubsystemHandle| {
-                            let t = t.clone();
-                            let token = subsys.create_cancellation_token();
-
-                            async move {
-                                supervise(
-                                    task_name,
-                                    t,
-                                    token,
-                                    factory_config.shutdown_on_error,
-                                    affinity,
-                                    Some(i),
-                                )
-                                .await
-                            }
-                        },
-                    ));
+            let mut groups: Vec<(TaskFactory, Vec<NestedSubsystem>)> = factories
+                .into_iter()
+                .map(|reg| {
+                    let instances = (0..reg.instances)
+                        .map(|i| start_instance(subsys, &reg, i, &supervision))
+                        .collect();
+                    (reg, instances)
+                })
+                .collect();
+
+            // resize factory groups on request, until every task is done or shutdown starts
+            loop {
+                tokio::select! {
+                    Some((name, target)) = scale_rx.recv() => {
+                        let Some((reg, instances)) =
+                            groups.iter_mut().find(|(reg, _)| reg.name == name)
+                        else {
+                            continue;
+                        };
+                        info!(group = %name, from = instances.len(), to = target, "scaling factory group");
+                        while instances.len() < target {
+                            let instance = start_instance(subsys, reg, instances.len(), &supervision);
+                            instances.push(instance);
+                        }
+                        for instance in instances.drain(target.min(instances.len())..) {
+                            instance.initiate_shutdown();
+                        }
+                    }
+                    _ = subsys.wait_for_children() => break,
+                    _ = subsys.on_shutdown_requested() => break,
                 }
             }
-
-            async {}
         };
 
-        let mut builder = Toplevel::new(toplevel_fn);
+        // aborted once the run is over, even if it ended without a shutdown request
+        let signal_listener = config.catch_signals.then(|| {
+            let shutdown_state = shutdown_state.clone();
+            let shutdown_token = shutdown_token.clone();
+            tokio::spawn(async move {
+                tokio::select! {
+                    signal = wait_for_signal() => {
+                        info!(signal, "received shutdown signal");
+                        shutdown_state.record(ShutdownCause::Signal(signal));
+                        shutdown_token.cancel();
+                    }
+                    _ = shutdown_token.cancelled() => {}
+                }
+            })
+        });
 
-        if config.catch_signals {
-            builder = builder.catch_signals();
+        let result = Toplevel::new_with_shutdown_token(toplevel_fn, shutdown_token.clone())
+            .handle_shutdown_requests(config.shutdown_timeout)
+            .await;
+        let stopped_at = Instant::now();
+        if let Some(listener) = signal_listener {
+            listener.abort();
         }
 
-        builder
-            .handle_shutdown_requests(config.shutdown_timeout)
-            .await
-            .map_err(|e| match e {
-                GracefulShutdownError::ShutdownTimeout(_) => {
-                    ShutdownError::timeout(config.shutdown_timeout)
-                }
-                GracefulShutdownError::SubsystemsFailed(failures) => {
-                    let task_errors = failures
-                        .into_iter()
-                        .map(|f| {
-                            let error_msg = format!("{:?}", f);
-                            TaskError::new(
-                                f.name().to_string(),
-                                TaskErrorKind::Execution {
-                                    source: error_msg.into(),
-                                },
-                            )
-                        })
-                        .collect();
-                    ShutdownError::subsystems_failed(task_errors)
+        let error = result.err().map(|e| match e {
+            GracefulShutdownError::ShutdownTimeout(_) => {
+                status.mark_unfinished_timed_out();
+                ShutdownError::timeout(config.shutdown_timeout)
+            }
+            GracefulShutdownError::SubsystemsFailed(failures) => {
+                let task_errors = failures
+                    .into_iter()
+                    .map(|f| {
+                        let e = into_task_error(f);
+                        if let TaskErrorKind::Panic { .. } = e.kind {
+                            status.mark_panicked(&e.task_name);
+                        }
+                        e
+                    })
+                    .collect();
+                ShutdownError::subsystems_failed(task_errors)
+            }
+        });
+
+        let recorded = shutdown_state.cause();
+        let cause = match (&recorded, &error) {
+            (Some((cause, _)), _) => cause.clone(),
+            (None, Some(ShutdownError::SubsystemsFailed { failures })) => {
+                ShutdownCause::TaskFailed {
+                    task_name: failures
+                        .first()
+                        .map(|f| f.task_name.clone())
+                        .unwrap_or_default(),
                 }
-            })
+            }
+            (None, _) => ShutdownCause::Completed,
+        };
+
+        ShutdownReport {
+            cause,
+            tasks: status.snapshot(),
+            uptime: stopped_at - started_at,
+            shutdown_duration: recorded.map(|(_, requested_at)| stopped_at - requested_at),
+            error,
+        }
     }
 
     fn validate_allocations(&self) -> Result<CoreAllocator, ShutdownError> {
@@ -255,88 +410,126 @@ impl TaskManager {
     }
 }
 
-async fn supervise(
-    task_name: String,
-    task: Arc<dyn RunnableTask>,
-    token: CancellationToken,
+/// State shared by every supervised task.
+#[derive(Clone)]
+struct Supervision {
+    status: StatusRegistry,
+    shutdown_state: Arc<ShutdownState>,
     shutdown_on_error: bool,
+}
+
+/// A single task instance to supervise.
+struct TaskRun {
+    slot: usize,
+    name: String,
+    task: Arc<dyn RunnableTask>,
     affinity: CoreAffinityConfig,
     instance_index: Option<usize>,
-) -> TaskResult<()> {
-    if let Some(core_ids) = core_affinity::get_core_ids() {
-        match affinity {
-            CoreAffinityConfig::None => {}
-
-            CoreAffinityConfig::Fixed(id) => {
-                // fixed always works regardless of instance_index
-                if let Some(core) = core_ids.into_iter().find(|c| c.id == id) {
-                    core_affinity::set_for_current(core);
-                    info!(task = %task_name, core = id, "pinned to specific core");
-                } else {
-                    error!(task = %task_name, core = id, "requested core not available");
-                }
-            }
-
-            CoreAffinityConfig::Range { start, end } => {
-                let range: Vec<_> = core_ids
-                    .into_iter()
-                    .filter(|c| (start..=end).contains(&c.id))
-                    .collect();
+    pause: Option<PauseToken>,
+}
 
-                if range.is_empty() {
-                    error!(task = %task_name, "no cores in requested range {}-{}", start, end);
-                } else if let Some(i) = instance_index {
-                    // for multi-instance: distribute across range
-                    if let Some(core) = range.get(i % range.len()) {
-                        core_affinity::set_for_current(*core);
-                        info!(task = %task_name, core = core.id, instance = i, "pinned to core in range");
-                    }
-                } else {
-                    // for single instance: use first core in range
-                    if let Some(core) = range.first() {
-                        core_affinity::set_for_current(*core);
-                        info!(task = %task_name, core = core.id, "single task pinned to first core in range");
-                    }
-                }
-            }
+async fn supervise(
+    supervision: Supervision,
+    run: TaskRun,
+    token: CancellationToken,
+) -> TaskResult<()> {
+    let TaskRun {
+        slot,
+        name: task_name,
+        task,
+        affinity,
+        instance_index,
+        pause,
+    } = run;
 
-            CoreAffinityConfig::Auto => {
-                if let Some(i) = instance_index {
-                    // for multi-instance: round-robin across all cores
-                    if let Some(core) = core_ids.get(i % core_ids.len()) {
-                        core_affinity::set_for_current(*core);
-                        info!(task = %task_name, core = core.id, instance = i, "auto-pinned by index");
-                    }
-                } else {
-                    // for single instance: Auto doesn't make sense, warn and skip
-                    error!(task = %task_name, "Auto affinity not supported for single instance tasks, use Fixed or Range");
-                }
-            }
-        }
-    }
+    let core = pin_current_thread(&task_name, &affinity, instance_index);
 
     info!(task = %task_name, "starting subsystem");
+    supervision.status.mark_running(slot);
 
-    let res = task.run(token).await;
-    let _ = task.on_shutdown().await;
+    let res = with_assigned_core(core, with_pause_token(pause, task.run(token))).await;
+    let _ = with_assigned_core(core, task.on_shutdown()).await;
 
     info!(task = %task_name, "subsystem stopped");
+    supervision
+        .status
+        .mark_finished(slot, res.as_ref().err().map(|e| e.to_string()));
 
-    if !shutdown_on_error {
+    if !supervision.shutdown_on_error {
         if let Err(ref e) = res {
             error!(task = %task_name, ?e, "task failed but shutdown_on_error=false");
         }
         Ok(())
     } else {
+        if res.is_err() {
+            supervision
+                .shutdown_state
+                .record(ShutdownCause::TaskFailed {
+                    task_name: task_name.clone(),
+                });
+        }
         res
     }
 }
 
+/// Recover the [`TaskError`] a subsystem failed with.
+fn into_task_error(failure: SubsystemError) -> TaskError {
+    // subsystem names are absolute paths ("/name")
+    let name = failure.name().trim_start_matches('/').to_string();
+
+    match failure {
+        SubsystemError::Panicked(_) => TaskError::panic(name, "subsystem panicked"),
+        SubsystemError::Failed(_, failure) => match failure.into_error().downcast::<TaskError>() {
+            Ok(e) => *e,
+            Err(e) => TaskError::new(name, TaskErrorKind::Execution { source: e }),
+        },
+    }
+}
+
+/// Start instance `index` of the factory group `reg`.
+fn start_instance(
+    subsys: &SubsystemHandle,
+    reg: &TaskFactory,
+    index: usize,
+    supervision: &Supervision,
+) -> NestedSubsystem {
+    let task = (reg.factory)(index);
+    let task_name = format!("{}-{}", reg.name, index);
+    let affinity = reg.affinity.clone();
+    let supervision = supervision.clone();
+    let (slot, pause) =
+        supervision
+            .status
+            .register(task_name.clone(), Some(&reg.name), task.pausable());
+
+    subsys.start(SubsystemBuilder::new(
+        task_name.clone(),
+        move |subsys: &mut SubsystemHandle| {
+            let token = subsys.create_cancellation_token();
+
+            async move {
+                let run = TaskRun {
+                    slot,
+                    name: task_name,
+                    task,
+                    affinity,
+                    instance_index: Some(index),
+                    pause,
+                };
+                supervise(supervision, run, token).await
+            }
+        },
+    ))
+}
+
 pub type Factory = Arc<dyn Fn() -> Arc<dyn RunnableTask> + Send + Sync>;
 
+/// Builds the task for the given instance index.
+pub type IndexedFactory = Arc<dyn Fn(usize) -> Arc<dyn RunnableTask> + Send + Sync>;
+
 pub struct TaskFactory {
     pub name: String,
-    pub factory: Factory,
+    pub factory: IndexedFactory,
     pub instances: usize,
     pub affinity: CoreAffinityConfig,
 }
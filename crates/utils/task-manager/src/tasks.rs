@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use std::{future::Future, sync::Arc};
 use tokio_util::sync::CancellationToken;
 
 use crate::TaskResult;
@@ -35,4 +36,151 @@ pub trait RunnableTask: Send + Sync + 'static {
     async fn metrics(&self) -> TaskResult<()> {
         Ok(())
     }
+
+    /// Whether the task supports pause/resume.
+    ///
+    /// Pausable tasks get their token from [`crate::PauseToken::current`] inside `run`.
+    fn pausable(&self) -> bool {
+        false
+    }
+}
+
+/// Task backed by a closure, for small loops that don't warrant their own type.
+pub struct FnTask<F> {
+    name: String,
+    f: F,
+}
+
+impl<F, Fut> FnTask<F>
+where
+    F: Fn(CancellationToken) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = TaskResult<()>> + Send + 'static,
+{
+    pub fn new(name: impl Into<String>, f: F) -> Self {
+        Self {
+            name: name.into(),
+            f,
+        }
+    }
+}
+
+#[async_trait]
+impl<F, Fut> RunnableTask for FnTask<F>
+where
+    F: Fn(CancellationToken) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = TaskResult<()>> + Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, token: CancellationToken) -> TaskResult<()> {
+        (self.f)(token).await
+    }
+}
+
+/// Closure-backed task that receives shared, typed state on every run.
+pub struct StatefulFnTask<S, F> {
+    name: String,
+    state: Arc<S>,
+    f: F,
+}
+
+impl<S, F, Fut> StatefulFnTask<S, F>
+where
+    S: Send + Sync + 'static,
+    F: Fn(Arc<S>, CancellationToken) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = TaskResult<()>> + Send + 'static,
+{
+    pub fn new(name: impl Into<String>, state: Arc<S>, f: F) -> Self {
+        Self {
+            name: name.into(),
+            state,
+            f,
+        }
+    }
+
+    /// Shared state handed to the closure.
+    pub fn state(&self) -> &Arc<S> {
+        &self.state
+    }
+}
+
+#[async_trait]
+impl<S, F, Fut> RunnableTask for StatefulFnTask<S, F>
+where
+    S: Send + Sync + 'static,
+    F: Fn(Arc<S>, CancellationToken) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = TaskResult<()>> + Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(&self, token: CancellationToken) -> TaskResult<()> {
+        (self.f)(self.state.clone(), token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskManager, task_manager::TaskManagerConfig};
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    fn manager() -> TaskManager {
+        TaskManager::new(TaskManagerConfig {
+            catch_signals: false,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_spawn_fn_runs_closures() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut manager = manager();
+
+        let c = counter.clone();
+        manager.spawn_fn("heartbeat", move |_token| {
+            let c = c.clone();
+            async move {
+                c.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        manager.spawn_fn_with_state("stateful", counter.clone(), |state, _token| async move {
+            state.fetch_add(10, Ordering::SeqCst);
+            Ok(())
+        });
+
+        assert!(manager.run().await.is_ok());
+        assert_eq!(counter.load(Ordering::SeqCst), 11);
+    }
+
+    #[tokio::test]
+    async fn test_indexed_factory_receives_instance_index() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut manager = manager();
+
+        let s = seen.clone();
+        manager.register_indexed_factory(
+            "shard",
+            move |i| {
+                s.lock().unwrap().push(i);
+                Arc::new(FnTask::new(format!("shard-{}", i), |_token| async {
+                    Ok(())
+                }))
+            },
+            4,
+        );
+
+        assert!(manager.run().await.is_ok());
+
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+    }
 }